@@ -27,7 +27,7 @@ fn conversions(c: &mut Criterion) {
         b.iter(|| {
             let mut input = input.clone();
             for q in &mut input {
-                let _ = q.convert(ConvertTo::Best(System::Imperial), &converter);
+                let _ = q.convert(ConvertTo::Best(System::Imperial), None, &converter);
                 let _ = q.fit(&converter);
             }
         })
@@ -36,7 +36,7 @@ fn conversions(c: &mut Criterion) {
         b.iter(|| {
             let mut input = input.clone();
             for q in &mut input {
-                let _ = q.convert(ConvertTo::Best(System::Metric), &converter);
+                let _ = q.convert(ConvertTo::Best(System::Metric), None, &converter);
                 let _ = q.fit(&converter);
             }
         })
@@ -61,5 +61,26 @@ fn bundled_units(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, conversions, bundled_units);
+fn build_converter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_converter");
+    group.bench_function("builder", |b| {
+        b.iter(|| {
+            Converter::builder()
+                .with_units_file(UnitsFile::bundled())
+                .unwrap()
+                .finish()
+                .unwrap()
+        })
+    });
+    group.bench_function("bundled", |b| b.iter(Converter::bundled));
+    #[cfg(feature = "converter_cache")]
+    {
+        let bytes = Converter::bundled().to_bytes();
+        group.bench_function("from_bytes", |b| {
+            b.iter(|| Converter::from_bytes(black_box(&bytes)).unwrap())
+        });
+    }
+}
+
+criterion_group!(benches, conversions, bundled_units, build_converter);
 criterion_main!(benches);
@@ -0,0 +1,252 @@
+use std::sync::{Arc, Mutex};
+
+use cooklang::cook_session::{
+    CookSession as OriginalCookSession, SessionTimer as OriginalSessionTimer,
+    TimerState as OriginalTimerState,
+};
+use cooklang::step_graph::StepNode;
+use cooklang::{CooklangParser, Recipe as OriginalRecipe};
+
+use crate::model::{into_simple_recipe, CooklangRecipe};
+
+/// Errors returned by cook session functions.
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum CookSessionError {
+    #[error("failed to parse recipe: {reason}")]
+    Parse { reason: String },
+    #[error("failed to restore session: {reason}")]
+    Restore { reason: String },
+    #[error("{reason}")]
+    Event { reason: String },
+}
+
+impl From<cooklang::cook_session::CookSessionError> for CookSessionError {
+    fn from(err: cooklang::cook_session::CookSessionError) -> Self {
+        CookSessionError::Event {
+            reason: err.to_string(),
+        }
+    }
+}
+
+/// Position of a step in a recipe
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct StepPosition {
+    /// Index of the section
+    pub section: u32,
+    /// Index of the block in the section
+    pub block: u32,
+}
+
+/// State of a timer
+#[derive(uniffi::Enum, Debug, Clone, PartialEq)]
+pub enum TimerState {
+    Running,
+    Paused,
+    Finished,
+}
+
+/// A started timer, times are in seconds
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct SessionTimer {
+    /// Index of the timer in the recipe
+    pub timer: u32,
+    pub duration: f64,
+    pub remaining: f64,
+    pub state: TimerState,
+}
+
+impl From<&OriginalSessionTimer> for SessionTimer {
+    fn from(timer: &OriginalSessionTimer) -> Self {
+        SessionTimer {
+            timer: timer.timer as u32,
+            duration: timer.duration,
+            remaining: timer.remaining,
+            state: match timer.state {
+                OriginalTimerState::Running => TimerState::Running,
+                OriginalTimerState::Paused => TimerState::Paused,
+                OriginalTimerState::Finished => TimerState::Finished,
+            },
+        }
+    }
+}
+
+/// State of a recipe being cooked
+///
+/// Tracks the current step, the checked ingredients, the timers and the
+/// scale. The state can be saved with `save` and restored with `restore`.
+#[derive(uniffi::Object, Debug)]
+pub struct CookSession {
+    parser: CooklangParser,
+    recipe: OriginalRecipe,
+    state: Mutex<OriginalCookSession>,
+}
+
+impl CookSession {
+    fn parse(input: &str) -> Result<(CooklangParser, OriginalRecipe), CookSessionError> {
+        let parser = CooklangParser::canonical();
+        let (recipe, _warnings) =
+            parser
+                .parse(input)
+                .into_result()
+                .map_err(|report| CookSessionError::Parse {
+                    reason: report.to_string(),
+                })?;
+        Ok((parser, recipe))
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, OriginalCookSession> {
+        self.state.lock().unwrap()
+    }
+}
+
+#[uniffi::export]
+impl CookSession {
+    /// Starts cooking a recipe at the first step
+    ///
+    /// # Arguments
+    /// * `input` - The raw recipe text in Cooklang format
+    #[uniffi::constructor]
+    pub fn new(input: String) -> Result<Arc<Self>, CookSessionError> {
+        let (parser, recipe) = Self::parse(&input)?;
+        let state = OriginalCookSession::new(&recipe);
+        Ok(Arc::new(Self {
+            parser,
+            recipe,
+            state: Mutex::new(state),
+        }))
+    }
+
+    /// Restores a session saved with `save`
+    ///
+    /// # Arguments
+    /// * `input` - The same recipe text the session was started with
+    /// * `saved` - The saved state
+    #[uniffi::constructor]
+    pub fn restore(input: String, saved: String) -> Result<Arc<Self>, CookSessionError> {
+        let (parser, recipe) = Self::parse(&input)?;
+        let state = serde_json::from_str(&saved).map_err(|e| CookSessionError::Restore {
+            reason: e.to_string(),
+        })?;
+        Ok(Arc::new(Self {
+            parser,
+            recipe,
+            state: Mutex::new(state),
+        }))
+    }
+
+    /// Saves the state of the session as JSON
+    pub fn save(&self) -> String {
+        serde_json::to_string(&*self.state()).unwrap()
+    }
+
+    /// Returns the recipe scaled with the session scale
+    pub fn recipe(&self) -> Arc<CooklangRecipe> {
+        let scaled = self
+            .state()
+            .scaled_recipe(&self.recipe, self.parser.converter());
+        Arc::new(into_simple_recipe(&scaled))
+    }
+
+    /// Returns the current step, or none if the session is finished
+    pub fn current_step(&self) -> Option<StepPosition> {
+        match self.state().current()? {
+            StepNode::Step { section, content } => Some(StepPosition {
+                section: section as u32,
+                block: content as u32,
+            }),
+            StepNode::Section { .. } => None,
+        }
+    }
+
+    /// Returns true when there are no more steps
+    pub fn is_finished(&self) -> bool {
+        self.state().is_finished()
+    }
+
+    /// Goes to the next step, returns false if the session was finished
+    pub fn next(&self) -> bool {
+        self.state().next(&self.recipe)
+    }
+
+    /// Goes to the previous step, returns false if it was the first step
+    pub fn back(&self) -> bool {
+        self.state().back(&self.recipe)
+    }
+
+    /// Goes to a step
+    pub fn go_to(&self, position: StepPosition) -> Result<(), CookSessionError> {
+        Ok(self.state().go_to(
+            &self.recipe,
+            position.section as usize,
+            position.block as usize,
+        )?)
+    }
+
+    /// Marks an ingredient as checked
+    pub fn check(&self, ingredient: u32) -> Result<(), CookSessionError> {
+        Ok(self.state().check(&self.recipe, ingredient as usize)?)
+    }
+
+    /// Unmarks a checked ingredient
+    pub fn uncheck(&self, ingredient: u32) {
+        self.state().uncheck(ingredient as usize)
+    }
+
+    /// Returns true if the ingredient is checked
+    pub fn is_checked(&self, ingredient: u32) -> bool {
+        self.state().is_checked(ingredient as usize)
+    }
+
+    /// Starts a timer, resumes it if paused or restarts it if finished
+    pub fn start_timer(&self, timer: u32) -> Result<(), CookSessionError> {
+        Ok(self
+            .state()
+            .start_timer(&self.recipe, self.parser.converter(), timer as usize)?)
+    }
+
+    /// Pauses a running timer
+    pub fn pause_timer(&self, timer: u32) -> Result<(), CookSessionError> {
+        Ok(self.state().pause_timer(timer as usize)?)
+    }
+
+    /// Resumes a paused timer
+    pub fn resume_timer(&self, timer: u32) -> Result<(), CookSessionError> {
+        Ok(self.state().resume_timer(timer as usize)?)
+    }
+
+    /// Removes a timer
+    pub fn cancel_timer(&self, timer: u32) {
+        self.state().cancel_timer(timer as usize)
+    }
+
+    /// Advances the running timers
+    ///
+    /// # Returns
+    /// The indices of the timers that finished
+    pub fn tick(&self, seconds: f64) -> Vec<u32> {
+        self.state()
+            .tick(seconds)
+            .into_iter()
+            .map(|t| t as u32)
+            .collect()
+    }
+
+    /// Returns the started timers
+    pub fn timers(&self) -> Vec<SessionTimer> {
+        self.state()
+            .timers()
+            .iter()
+            .map(SessionTimer::from)
+            .collect()
+    }
+
+    /// Returns the scale factor
+    pub fn scale(&self) -> f64 {
+        self.state().scale()
+    }
+
+    /// Changes the scale factor, see `recipe`
+    pub fn set_scale(&self, factor: f64) -> Result<(), CookSessionError> {
+        Ok(self.state().set_scale(factor)?)
+    }
+}
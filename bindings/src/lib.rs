@@ -4,6 +4,7 @@ use cooklang::aisle::parse_lenient;
 use cooklang::metadata::StdKey as OriginalStdKey;
 
 pub mod aisle;
+pub mod cook_session;
 pub mod model;
 pub mod shopping_list;
 
@@ -691,6 +692,50 @@ pub fn compact_shopping_checked(
     shopping_list::compact_checked_impl(entries, current_ingredients)
 }
 
+/// Merges two versions of a shopping list edited from a common base
+///
+/// Recipes are matched by path and ingredients by name. The changes of
+/// both versions are combined, and when both change the same item in
+/// different ways `ours` is kept and a conflict is reported.
+///
+/// # Arguments
+/// * `base` - The version both lists were edited from
+/// * `ours` - The local version
+/// * `theirs` - The remote version
+///
+/// # Returns
+/// The merged list and the conflicts
+#[uniffi::export]
+pub fn merge_shopping_lists(
+    base: &shopping_list::ShoppingList,
+    ours: &shopping_list::ShoppingList,
+    theirs: &shopping_list::ShoppingList,
+) -> shopping_list::ShoppingListMerge {
+    shopping_list::merge_shopping_lists_impl(base, ours, theirs)
+}
+
+/// Merges two versions of a checked log appended to from a common base
+///
+/// Ingredients changed in only one version take its state. When both
+/// versions end with different states, `ours` is kept and a conflict is
+/// reported.
+///
+/// # Arguments
+/// * `base` - The log both versions were appended to
+/// * `ours` - The local log
+/// * `theirs` - The remote log
+///
+/// # Returns
+/// The compacted merged log and the conflicts
+#[uniffi::export]
+pub fn merge_shopping_checked(
+    base: &[shopping_list::CheckEntry],
+    ours: &[shopping_list::CheckEntry],
+    theirs: &[shopping_list::CheckEntry],
+) -> shopping_list::ShoppingCheckedMerge {
+    shopping_list::merge_checked_impl(base, ours, theirs)
+}
+
 uniffi::setup_scaffolding!();
 
 #[cfg(test)]
@@ -1466,6 +1511,7 @@ Serve the @./pasta/spaghetti{1%portion} with sauce
                 path,
                 multiplier,
                 children,
+                ..
             } => {
                 assert_eq!(path, "Breakfast/Easy Pancakes");
                 assert_eq!(*multiplier, Some(2.0));
@@ -1489,6 +1535,7 @@ Serve the @./pasta/spaghetti{1%portion} with sauce
                 path,
                 multiplier,
                 children,
+                ..
             } => {
                 assert_eq!(path, "Thai Green Curry");
                 assert_eq!(*multiplier, None);
@@ -1507,9 +1554,11 @@ Serve the @./pasta/spaghetti{1%portion} with sauce
                 ShoppingListItem::Recipe {
                     path: "Breakfast/Pancakes".to_string(),
                     multiplier: Some(2.0),
+                    target: None,
                     children: vec![ShoppingListItem::Recipe {
                         path: "Shared/Syrup".to_string(),
                         multiplier: None,
+                        target: None,
                         children: vec![],
                     }],
                 },
@@ -1621,4 +1670,71 @@ Serve the @./pasta/spaghetti{1%portion} with sauce
         assert_eq!(compacted.len(), 1);
         assert!(matches!(&compacted[0], CheckEntry::Checked { name } if name == "salt"));
     }
+
+    #[test]
+    fn test_merge_shopping_lists() {
+        use crate::shopping_list::CheckEntry;
+
+        let base = crate::parse_shopping_list("./Pancakes{2}\nmilk\n".to_string()).unwrap();
+        let ours = crate::parse_shopping_list("./Pancakes{3}\nmilk\n".to_string()).unwrap();
+        let theirs =
+            crate::parse_shopping_list("./Pancakes{4}\nmilk\neggs\n".to_string()).unwrap();
+
+        let merged = crate::merge_shopping_lists(&base, &ours, &theirs);
+        assert_eq!(
+            crate::write_shopping_list(&merged.list).unwrap(),
+            "./Pancakes{3}\nmilk\neggs\n"
+        );
+        assert_eq!(merged.conflicts.len(), 1);
+
+        let base = crate::parse_shopping_checked("+ salt\n".to_string());
+        let ours = crate::parse_shopping_checked("+ salt\n+ milk\n".to_string());
+        let theirs = crate::parse_shopping_checked("+ salt\n- salt\n".to_string());
+        let merged = crate::merge_shopping_checked(&base, &ours, &theirs);
+        assert_eq!(merged.entries.len(), 1);
+        assert!(matches!(&merged.entries[0], CheckEntry::Checked { name } if name == "milk"));
+        assert!(merged.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_cook_session() {
+        use crate::cook_session::{CookSession, StepPosition, TimerState};
+
+        let input = r#"
+Boil @water{1%l}.
+
+Cook @pasta{200%g} for ~{10%minutes}.
+"#
+        .to_string();
+
+        let session = CookSession::new(input.clone()).unwrap();
+        assert_eq!(
+            session.current_step(),
+            Some(StepPosition {
+                section: 0,
+                block: 0
+            })
+        );
+        session.check(0).unwrap();
+        assert!(session.next());
+        session.start_timer(0).unwrap();
+        assert_eq!(session.tick(300.0), Vec::<u32>::new());
+        session.set_scale(2.0).unwrap();
+
+        let restored = CookSession::restore(input, session.save()).unwrap();
+        assert!(restored.is_checked(0));
+        assert_eq!(restored.timers()[0].remaining, 300.0);
+        assert_eq!(restored.tick(300.0), vec![0]);
+        assert_eq!(restored.timers()[0].state, TimerState::Finished);
+        assert_eq!(
+            restored.recipe().ingredients[1]
+                .amount
+                .as_ref()
+                .unwrap()
+                .quantity,
+            crate::Value::Number { value: 400.0 }
+        );
+        assert!(restored.next());
+        assert!(restored.is_finished());
+    }
 }
@@ -1,6 +1,7 @@
 use cooklang::shopping_list::{
     self as sl, CheckEntry as OriginalCheckEntry, IngredientItem as OriginalIngredientItem,
-    RecipeItem as OriginalRecipeItem, ShoppingList as OriginalShoppingList,
+    RecipeItem as OriginalRecipeItem, ScaleTarget as OriginalScaleTarget,
+    ShoppingList as OriginalShoppingList,
     ShoppingListItem as OriginalShoppingListItem,
 };
 use std::collections::HashSet;
@@ -34,12 +35,22 @@ pub enum ShoppingListItem {
     Recipe {
         path: String,
         multiplier: Option<f64>,
+        target: Option<ScaleTarget>,
         children: Vec<ShoppingListItem>,
     },
     /// A free-hand ingredient with a name and optional quantity
     Ingredient { name: String, quantity: Option<String> },
 }
 
+/// Target servings or yield of a recipe in the shopping list
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct ScaleTarget {
+    /// Number of servings or yield amount
+    pub value: f64,
+    /// `servings` or the unit of the recipe yield
+    pub unit: String,
+}
+
 /// An entry in the checked log
 #[derive(uniffi::Enum, Debug, Clone)]
 pub enum CheckEntry {
@@ -49,6 +60,47 @@ pub enum CheckEntry {
     Unchecked { name: String },
 }
 
+/// Result of merging two versions of a shopping list
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct ShoppingListMerge {
+    /// The merged list, conflicts are already solved in it
+    pub list: ShoppingList,
+    /// Items changed in both versions in different ways
+    pub conflicts: Vec<ShoppingListConflict>,
+}
+
+/// An item changed in both versions of a merged shopping list
+///
+/// A missing version means the item does not exist in that version.
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct ShoppingListConflict {
+    /// Paths of the recipes the item is nested in, outermost first
+    pub parents: Vec<String>,
+    pub base: Option<ShoppingListItem>,
+    pub ours: Option<ShoppingListItem>,
+    pub theirs: Option<ShoppingListItem>,
+}
+
+/// Result of merging two versions of a checked log
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct ShoppingCheckedMerge {
+    /// Compacted merged log
+    pub entries: Vec<CheckEntry>,
+    /// Ingredients checked in one version and unchecked in the other
+    pub conflicts: Vec<ShoppingCheckConflict>,
+}
+
+/// An ingredient changed in both versions of a merged checked log
+#[derive(uniffi::Record, Debug, Clone)]
+pub struct ShoppingCheckConflict {
+    /// Name of the ingredient, as written in `ours`
+    pub name: String,
+    /// If it is checked in `ours`, this is the state that is kept
+    pub ours: bool,
+    /// If it is checked in `theirs`
+    pub theirs: bool,
+}
+
 // ---------------------------------------------------------------------------
 // Conversions: original → binding
 // ---------------------------------------------------------------------------
@@ -67,6 +119,10 @@ impl From<&OriginalShoppingListItem> for ShoppingListItem {
             OriginalShoppingListItem::Recipe(r) => ShoppingListItem::Recipe {
                 path: r.path.clone(),
                 multiplier: r.multiplier,
+                target: r.target.as_ref().map(|t| ScaleTarget {
+                    value: t.value,
+                    unit: t.unit.clone(),
+                }),
                 children: r.children.iter().map(ShoppingListItem::from).collect(),
             },
             OriginalShoppingListItem::Ingredient(i) => ShoppingListItem::Ingredient {
@@ -108,10 +164,16 @@ impl From<&ShoppingListItem> for OriginalShoppingListItem {
             ShoppingListItem::Recipe {
                 path,
                 multiplier,
+                target,
                 children,
             } => OriginalShoppingListItem::Recipe(OriginalRecipeItem {
                 path: path.clone(),
                 multiplier: *multiplier,
+                target: target.as_ref().map(|t| OriginalScaleTarget {
+                    value: t.value,
+                    unit: t.unit.clone(),
+                    span: None,
+                }),
                 children: children.iter().map(OriginalShoppingListItem::from).collect(),
             }),
             ShoppingListItem::Ingredient { name, quantity } => {
@@ -205,3 +267,49 @@ pub fn compact_checked_impl(
     .map(CheckEntry::from)
     .collect()
 }
+
+/// Three-way merge of shopping lists.
+pub fn merge_shopping_lists_impl(
+    base: &ShoppingList,
+    ours: &ShoppingList,
+    theirs: &ShoppingList,
+) -> ShoppingListMerge {
+    let merged = sl::merge(&base.into(), &ours.into(), &theirs.into());
+    ShoppingListMerge {
+        list: ShoppingList::from(&merged.list),
+        conflicts: merged
+            .conflicts
+            .iter()
+            .map(|c| ShoppingListConflict {
+                parents: c.parents.clone(),
+                base: c.base.as_ref().map(ShoppingListItem::from),
+                ours: c.ours.as_ref().map(ShoppingListItem::from),
+                theirs: c.theirs.as_ref().map(ShoppingListItem::from),
+            })
+            .collect(),
+    }
+}
+
+/// Three-way merge of checked logs.
+pub fn merge_checked_impl(
+    base: &[CheckEntry],
+    ours: &[CheckEntry],
+    theirs: &[CheckEntry],
+) -> ShoppingCheckedMerge {
+    let original = |entries: &[CheckEntry]| -> Vec<OriginalCheckEntry> {
+        entries.iter().map(OriginalCheckEntry::from).collect()
+    };
+    let merged = sl::merge_checked(&original(base), &original(ours), &original(theirs));
+    ShoppingCheckedMerge {
+        entries: merged.entries.iter().map(CheckEntry::from).collect(),
+        conflicts: merged
+            .conflicts
+            .into_iter()
+            .map(|c| ShoppingCheckConflict {
+                name: c.name,
+                ours: c.ours,
+                theirs: c.theirs,
+            })
+            .collect(),
+    }
+}
@@ -22,6 +22,17 @@ fn generate_bundled() {
         }};
     }
 
+    fn quote_quantity(q: &str) -> proc_macro2::TokenStream {
+        // ! IMPORTANT: Same as the built-in PhysicalQuantity variants
+        const BUILTIN: [&str; 5] = ["volume", "mass", "length", "temperature", "time"];
+        if BUILTIN.contains(&q) {
+            let q = quote_enum!(PhysicalQuantity::q);
+            quote! { QuantityName::Builtin(#q) }
+        } else {
+            quote! { QuantityName::Custom(#q.to_string()) }
+        }
+    }
+
     fn none() -> proc_macro2::TokenStream {
         quote! { None }
     }
@@ -151,7 +162,7 @@ fn generate_bundled() {
                         let t = t.as_table().unwrap();
                         n = t.len();
                         let entries = t.iter().map(|(k, v)| {
-                            let q = quote_enum!(PhysicalQuantity::k);
+                            let q = quote_quantity(k);
                             let val = quote_fractions_config_wrapper(v);
                             quote! { m.insert(#q, #val); }
                         });
@@ -195,12 +206,102 @@ fn generate_bundled() {
         })
         .unwrap_or_else(none);
 
+    let humanize = uf
+        .get("humanize")
+        .map(|h| {
+            fn quote_rounding_step(v: &toml::Value) -> proc_macro2::TokenStream {
+                let t = v.as_table().unwrap();
+                let float = |v: &toml::Value| {
+                    v.as_float()
+                        .or_else(|| v.as_integer().map(|i| i as f64))
+                        .unwrap()
+                };
+                let above = t.get("above").map(float).unwrap_or(0.0);
+                let step = float(t.get("step").expect("no step in rounding step"));
+                quote! { RoundingStep { above: #above, step: #step } }
+            }
+
+            fn quote_rounding_rule(v: &toml::Value) -> proc_macro2::TokenStream {
+                if let Some(b) = v.as_bool() {
+                    quote! { RoundingRule::Toggle(#b) }
+                } else if let Some(a) = v.as_array() {
+                    let steps = a.iter().map(quote_rounding_step);
+                    quote! { RoundingRule::Tiers(vec![#(#steps),*]) }
+                } else if v.is_table() {
+                    let step = quote_rounding_step(v);
+                    quote! { RoundingRule::Step(#step) }
+                } else {
+                    panic!("bad humanize value")
+                }
+            }
+
+            let layer = |key: &str| {
+                h.get(key)
+                    .map(quote_rounding_rule)
+                    .map(some)
+                    .unwrap_or_else(none)
+            };
+            let all = layer("all");
+            let metric = layer("metric");
+            let imperial = layer("imperial");
+            let unitless = layer("unitless");
+
+            let quantity = h
+                .get("quantity")
+                .map(|t| t.as_table().unwrap().iter())
+                .into_iter()
+                .flatten()
+                .map(|(k, v)| {
+                    let q = quote_quantity(k);
+                    let val = quote_rounding_rule(v);
+                    quote! { (#q, #val) }
+                });
+            let unit = h
+                .get("unit")
+                .map(|t| t.as_table().unwrap().iter())
+                .into_iter()
+                .flatten()
+                .map(|(k, v)| {
+                    let val = quote_rounding_rule(v);
+                    quote! { (#k.to_string(), #val) }
+                });
+
+            quote! { Some(Humanize {
+                all: #all,
+                metric: #metric,
+                imperial: #imperial,
+                quantity: HashMap::from([#(#quantity),*]),
+                unit: HashMap::from([#(#unit),*]),
+                unitless: #unitless,
+            }) }
+        })
+        .unwrap_or_else(none);
+
     let extend = if uf.get("extend").is_some() {
         unimplemented!("base units.toml does not have extend");
     } else {
         quote! { None }
     };
 
+    let unitless = uf
+        .get("unitless")
+        .map(|u| {
+            let u = u.as_str().unwrap();
+            quote! { Some(#u.to_string()) }
+        })
+        .unwrap_or_else(none);
+
+    let custom_quantities = uf
+        .get("custom_quantities")
+        .map(|v| {
+            let names = v.as_array().unwrap().iter().map(|n| {
+                let n = n.as_str().unwrap();
+                quote! { #n.to_string() }
+            });
+            quote! { vec![#(#names),*] }
+        })
+        .unwrap_or_else(|| quote! { Vec::new() });
+
     let quantity = uf
         .get("quantity")
         .map(|v| {
@@ -209,7 +310,7 @@ fn generate_bundled() {
                 let qg = qg.as_table().unwrap();
 
                 let q = qg.get("quantity").unwrap().as_str().unwrap();
-                let quantity = quote_enum!(PhysicalQuantity::q);
+                let quantity = quote_quantity(q);
 
                 let best = qg
                     .get("best")
@@ -289,6 +390,38 @@ fn generate_bundled() {
                                     v.as_float().or_else(|| v.as_integer().map(|i| i as f64))
                                 })
                                 .unwrap_or_default();
+                            let table = v
+                                .get("table")
+                                .map(|t| {
+                                    let num = |v: &toml::Value| {
+                                        v.as_float().or_else(|| v.as_integer().map(|i| i as f64))
+                                    };
+                                    let points = t.as_array().unwrap().iter().map(|p| {
+                                        let p = p.as_array().unwrap();
+                                        let a = num(&p[0]).unwrap();
+                                        let b = num(&p[1]).unwrap();
+                                        quote! { (#a, #b) }
+                                    });
+                                    quote! { vec![#(#points),*] }
+                                })
+                                .unwrap_or_else(|| quote! { vec![] });
+                            let presets = v
+                                .get("presets")
+                                .map(|t| {
+                                    let entries = t.as_table().unwrap().iter().map(|(k, v)| {
+                                        let v = v
+                                            .as_float()
+                                            .or_else(|| v.as_integer().map(|i| i as f64))
+                                            .unwrap();
+                                        quote! { (Arc::from(#k), #v) }
+                                    });
+                                    quote! { HashMap::from([#(#entries),*]) }
+                                })
+                                .unwrap_or_else(|| quote! { HashMap::new() });
+                            let before_value = v
+                                .get("before_value")
+                                .and_then(|v| v.as_bool())
+                                .unwrap_or_default();
                             let expand_si = v
                                 .get("expand_si")
                                 .and_then(|v| v.as_bool())
@@ -300,6 +433,9 @@ fn generate_bundled() {
                                 aliases: #aliases,
                                 ratio: #ratio,
                                 difference: #difference,
+                                table: #table,
+                                presets: #presets,
+                                before_value: #before_value,
                                 expand_si: #expand_si,
                             } }
                         }
@@ -347,6 +483,64 @@ fn generate_bundled() {
         })
         .unwrap_or_else(|| quote! { vec![] });
 
+    let region = {
+        let entries = uf
+            .get("region")
+            .map(|t| t.as_table().unwrap().iter())
+            .into_iter()
+            .flatten()
+            .map(|(name, r)| {
+                let r = r.as_table().unwrap();
+                let system = r
+                    .get("system")
+                    .map(|sys| {
+                        let sys = quote_enum!(System::sys.as_str().unwrap());
+                        quote! { Some(#sys) }
+                    })
+                    .unwrap_or_else(none);
+                let locales = r
+                    .get("locales")
+                    .map(|v| v.as_array().unwrap().iter())
+                    .into_iter()
+                    .flatten()
+                    .map(|l| {
+                        let l = l.as_str().unwrap();
+                        quote! { #l.to_string() }
+                    });
+                let units = r
+                    .get("units")
+                    .map(|v| v.as_table().unwrap().iter())
+                    .into_iter()
+                    .flatten()
+                    .map(|(k, v)| {
+                        let ratio = v.as_float().or_else(|| v.as_integer().map(|i| i as f64));
+                        quote! { (#k.to_string(), #ratio) }
+                    });
+                let best = r
+                    .get("best")
+                    .map(|v| v.as_table().unwrap().iter())
+                    .into_iter()
+                    .flatten()
+                    .map(|(k, v)| {
+                        let q = quote_quantity(k);
+                        let vals = v.as_array().unwrap().iter().map(|v| {
+                            let v = v.as_str().unwrap();
+                            quote! {#v.to_string()}
+                        });
+                        quote! { (#q, vec![#(#vals),*]) }
+                    });
+                quote! {
+                    (#name.to_string(), RegionEntry {
+                        system: #system,
+                        locales: vec![#(#locales),*],
+                        units: HashMap::from([#(#units),*]),
+                        best: HashMap::from([#(#best),*]),
+                    })
+                }
+            });
+        quote! { HashMap::from([#(#entries),*]) }
+    };
+
     let tokens = quote! {
         mod __bundled_units {
             use super::*;
@@ -355,8 +549,12 @@ fn generate_bundled() {
                     default_system: #default_system,
                     si: #si,
                     fractions: #fractions,
+                    humanize: #humanize,
                     extend: #extend,
+                    unitless: #unitless,
+                    custom_quantities: #custom_quantities,
                     quantity: #quantity,
+                    region: #region,
                 }
             }
         }
@@ -162,6 +162,7 @@ async function run(): Promise<void> {
         ["RANGE_VALUES", 1 << 9],
         ["TIMER_REQUIRES_TIME", 1 << 10],
         ["INTERMEDIATE_PREPARATIONS", (1 << 11) | (1 << 1)],
+        ["LOOSE_NUMBERS", 1 << 12],
     ];
 
     extensions.forEach(([e, bits]) => {
@@ -1,20 +1,26 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use crate::convert::{Converter, PhysicalQuantity};
+use crate::convert::{convert_f64, Converter, PhysicalQuantity, Unit};
 use crate::error::{label, CowStr, PassResult, SourceDiag, SourceReport};
+use crate::format::FormatOptions;
 use crate::located::Located;
 use crate::metadata::{check_std_entry, StdKey};
 use crate::parser::{
     self, BlockKind, Event, IntermediateData, IntermediateRefMode, IntermediateTargetKind,
     Modifiers,
 };
-use crate::quantity::{Quantity, Value};
+use crate::quantity::{Number, Quantity, Value};
 use crate::span::Span;
+use crate::suggest;
 use crate::text::Text;
 use crate::{model::*, Extensions, ParseOptions};
 
-use super::{AnalysisResult, CheckOptions, DefineMode, DuplicateMode};
+use super::{
+    loose_numbers::{default_words, LooseNumbers},
+    AnalysisResult, CheckOptions, DefineMode, DuplicateMode,
+};
 
 macro_rules! error {
     ($msg:expr, $label:expr $(,)?) => {
@@ -266,6 +272,12 @@ impl<'i> RecipeCollector<'i, '_> {
             if !action.run_std_checks {
                 continue;
             }
+            if let Some(mut diag) = key.as_str().and_then(std_key_typo) {
+                if let Some(pos) = yaml_find_key_position(&yaml_str, key.as_str().unwrap()) {
+                    diag.add_label(label!(Span::pos(yaml_text.span().start() + pos)));
+                }
+                self.ctx.warn(diag);
+            }
             if let Some(sk) = key.as_str().and_then(|s| StdKey::from_str(s).ok()) {
                 if let Err(err) = check_std_entry(sk, value, self.converter) {
                     let mut diag = warning!(format!(
@@ -327,13 +339,16 @@ impl<'i> RecipeCollector<'i, '_> {
     fn metadata(&mut self, key: Text<'i>, value: Text<'i>) {
         let key_t = key.text_trimmed();
         let value_t = value.text_outer_trimmed();
-        let invalid_value = |possible| {
-            error!(
+        let invalid_value = |possible: Vec<&str>| {
+            let mut e = error!(
                 format!("Invalid value for config key '{key_t}': {value_t}"),
                 label!(value.span(), "this value")
             )
-            .label(label!(key.span(), "this key does not support"))
-            .hint(format!("Possible values are: {possible:?}"))
+            .label(label!(key.span(), "this key does not support"));
+            if let Some(s) = suggest::closest(&value_t, possible.iter().copied()) {
+                e.add_hint(suggest::did_you_mean(s));
+            }
+            e.hint(format!("Possible values are: {possible:?}"))
         };
 
         if self.extensions.contains(Extensions::MODES)
@@ -357,13 +372,15 @@ impl<'i> RecipeCollector<'i, '_> {
                     _ => self.ctx.error(invalid_value(vec!["new", "reference"])),
                 },
                 _ => {
-                    self.ctx.warn(
-                        warning!(
-                            format!("Unknown config metadata key: {key_t}"),
-                            label!(key.span())
-                        )
-                        .hint("Possible config keys are '[mode]' and '[duplicate]''"),
+                    let mut w = warning!(
+                        format!("Unknown config metadata key: {key_t}"),
+                        label!(key.span())
                     );
+                    if let Some(s) = suggest::closest(config_key, ["define", "mode", "duplicate"]) {
+                        w.add_hint(suggest::did_you_mean(&format!("[{s}]")));
+                    }
+                    self.ctx
+                        .warn(w.hint("Possible config keys are '[mode]' and '[duplicate]''"));
                     if self.old_style_metadata {
                         self.content.metadata.map.insert(
                             serde_yaml::Value::String(key_t.into_owned()),
@@ -402,6 +419,9 @@ impl<'i> RecipeCollector<'i, '_> {
         if !action.run_std_checks {
             return;
         }
+        if let Some(diag) = std_key_typo(&key_t) {
+            self.ctx.warn(diag.label(label!(key.span())));
+        }
         if let Ok(sp_key) = StdKey::from_str(&key_t) {
             let check_result = crate::metadata::check_std_entry(
                 sp_key,
@@ -565,7 +585,12 @@ impl<'i> RecipeCollector<'i, '_> {
         }
     }
 
-    fn ingredient(&mut self, ingredient: Located<parser::Ingredient<'i>>) -> usize {
+    fn ingredient(&mut self, mut ingredient: Located<parser::Ingredient<'i>>) -> usize {
+        if let Some(q) = &mut ingredient.quantity {
+            self.loose_quantity(q);
+            self.compound_quantity(q);
+            self.unit_typo_check(q);
+        }
         let located_ingredient = ingredient.clone();
         let (ingredient, location) = ingredient.take_pair();
 
@@ -881,7 +906,12 @@ impl<'i> RecipeCollector<'i, '_> {
         Ok(relation)
     }
 
-    fn cookware(&mut self, cookware: Located<parser::Cookware<'i>>) -> usize {
+    fn cookware(&mut self, mut cookware: Located<parser::Cookware<'i>>) -> usize {
+        if let Some(q) = &mut cookware.quantity {
+            self.loose_quantity(q);
+            self.compound_quantity(q);
+            self.unit_typo_check(q);
+        }
         let located_cookware = cookware.clone();
         let (cookware, location) = cookware.take_pair();
 
@@ -961,7 +991,15 @@ impl<'i> RecipeCollector<'i, '_> {
         self.content.cookware.len() - 1
     }
 
-    fn timer(&mut self, timer: Located<parser::Timer<'i>>) -> usize {
+    fn timer(&mut self, mut timer: Located<parser::Timer<'i>>) -> usize {
+        if let Some(q) = &mut timer.quantity {
+            self.loose_quantity(q);
+            self.compound_quantity(q);
+            // with advanced units there is an error for unknown units
+            if !self.extensions.contains(Extensions::ADVANCED_UNITS) {
+                self.unit_typo_check(q);
+            }
+        }
         let located_timer = timer.clone();
         let (timer, _span) = timer.take_pair();
         let quantity = timer.quantity.map(|q| {
@@ -989,10 +1027,16 @@ impl<'i> RecipeCollector<'i, '_> {
                                 ));
                             }
                         }
-                        None => self.ctx.error(error!(
-                            format!("Unknown timer unit: {unit_text}"),
-                            label!(unit_span, "expected time unit")
-                        )),
+                        None => {
+                            let mut e = error!(
+                                format!("Unknown timer unit: {unit_text}"),
+                                label!(unit_span, "expected time unit")
+                            );
+                            if let Some(s) = self.converter.suggest_unit(unit_text) {
+                                e.add_hint(suggest::did_you_mean(s));
+                            }
+                            self.ctx.error(e)
+                        }
                     }
                 }
             }
@@ -1008,6 +1052,174 @@ impl<'i> RecipeCollector<'i, '_> {
         self.content.timers.len() - 1
     }
 
+    /// Reads text values as numbers with [`Extensions::LOOSE_NUMBERS`]
+    ///
+    /// With [`Extensions::ADVANCED_UNITS`], a text value without unit, like
+    /// `½ cup`, is split in the value and the unit if it's a known unit.
+    fn loose_quantity(&self, quantity: &mut Located<parser::Quantity<'i>>) {
+        if !self.extensions.contains(Extensions::LOOSE_NUMBERS) {
+            return;
+        }
+        let Value::Text(text) = quantity.value.value.value() else {
+            return;
+        };
+
+        let decimal_comma = self
+            .content
+            .metadata
+            .locale()
+            .is_some_and(|(lang, dialect)| {
+                FormatOptions::for_locale(lang, dialect).decimal_separator == ','
+            });
+        let loose = LooseNumbers {
+            words: self.parse_options.number_words.unwrap_or(default_words()),
+            decimal_comma,
+            ranges: self.extensions.contains(Extensions::RANGE_VALUES),
+        };
+
+        if let Some(value) = loose.value(text) {
+            *quantity.value.value = value;
+            return;
+        }
+
+        if quantity.unit.is_some() || !self.extensions.contains(Extensions::ADVANCED_UNITS) {
+            return;
+        }
+        let span = quantity.value.value.span();
+        let input: &'i str = self.input;
+        let raw = &input[span.range()];
+        // with comments or escaped chars the positions would be wrong
+        if raw.trim() != text {
+            return;
+        }
+        let is_unit = |unit: &str| self.converter.find_unit(unit).is_some();
+        let Some((value, unit)) = loose.split_value(raw, is_unit) else {
+            return;
+        };
+        let start = raw.len() - raw.trim_start().len();
+        let unit_start = unit.as_ptr() as usize - raw.as_ptr() as usize;
+        let value_end = raw[..unit_start].trim_end().len();
+        quantity.value.value = Located::new(
+            value,
+            Span::new(span.start() + start, span.start() + value_end),
+        );
+        quantity.unit = Some(Text::from_str(unit, span.start() + unit_start));
+    }
+
+    /// Reads compound quantities like `1%lb 4%oz` with [`Extensions::ADVANCED_UNITS`]
+    ///
+    /// The parser gives `lb 4%oz` as the unit. All the parts are added in the
+    /// first unit, so the result is a single `1.25 lb` quantity.
+    fn compound_quantity(&mut self, quantity: &mut Located<parser::Quantity<'i>>) {
+        if !self.extensions.contains(Extensions::ADVANCED_UNITS) {
+            return;
+        }
+        let Some(unit) = &quantity.unit else {
+            return;
+        };
+        let span = unit.span();
+        let input: &'i str = self.input;
+        let raw = &input[span.range()];
+        // with comments or escaped chars the positions would be wrong
+        if !raw.contains('%') || raw != unit.text() {
+            return;
+        }
+        let Value::Number(first) = quantity.value.value.value() else {
+            return;
+        };
+
+        let invalid = |reason: String| {
+            error!("Invalid compound quantity", label!(span, reason))
+                .hint("Compound quantities look like `1%lb 4%oz` or `1%h 30%min`")
+        };
+
+        // "lb 4", "oz" -> [("lb", 1), ("oz", 4)]
+        let mut parts = vec![];
+        let mut value = first.value();
+        let mut pieces = raw.split('%').peekable();
+        while let Some(piece) = pieces.next() {
+            if pieces.peek().is_none() {
+                parts.push((piece.trim(), value));
+                break;
+            }
+            let split = piece
+                .char_indices()
+                .filter(|(_, c)| c.is_whitespace())
+                .map(|(i, _)| piece.split_at(i))
+                .find_map(|(u, v)| {
+                    let u = u.trim();
+                    let v = compound_part_value(v.trim())?;
+                    self.converter.find_unit(u).is_some().then_some((u, v))
+                });
+            let Some((unit, next_value)) = split else {
+                // not a compound if it doesn't start like one
+                if !parts.is_empty() {
+                    self.ctx
+                        .error(invalid("expected a unit and a number".into()));
+                }
+                return;
+            };
+            parts.push((unit, value));
+            value = next_value;
+        }
+
+        let mut units = Vec::with_capacity(parts.len());
+        for &(u, _) in &parts {
+            let Some(unit) = self.converter.find_unit(u) else {
+                self.ctx.error(invalid(format!("unknown unit: {u}")));
+                return;
+            };
+            units.push(unit);
+        }
+        let first_unit = &units[0];
+        if units
+            .iter()
+            .any(|u| u.physical_quantity != first_unit.physical_quantity)
+        {
+            self.ctx
+                .error(invalid("the units are not of the same type".into()));
+            return;
+        }
+        if first_unit.physical_quantity == PhysicalQuantity::Temperature {
+            self.ctx
+                .error(invalid("temperatures can't be added".into()));
+            return;
+        }
+
+        let total = parts
+            .iter()
+            .zip(&units)
+            .map(|(&(_, v), u)| convert_f64(v, u, first_unit))
+            .sum::<f64>();
+        let first_unit_text = parts[0].0;
+        let offset = first_unit_text.as_ptr() as usize - raw.as_ptr() as usize;
+        *quantity.value.value = Value::Number(Number::Regular(total));
+        quantity.unit = Some(Text::from_str(first_unit_text, span.start() + offset));
+    }
+
+    /// Warns about unknown units that look like a typo of a known one
+    ///
+    /// Other unknown units are fine, but they can't be converted.
+    fn unit_typo_check(&mut self, quantity: &Located<parser::Quantity<'i>>) {
+        let Some(unit) = &quantity.unit else {
+            return;
+        };
+        let unit_text = unit.text_trimmed();
+        if self.converter.find_unit(&unit_text).is_some() {
+            return;
+        }
+        if let Some(s) = self.converter.suggest_unit(&unit_text) {
+            self.ctx.warn(
+                warning!(
+                    format!("Unknown unit: '{unit_text}'"),
+                    label!(unit.span(), "not a known unit")
+                )
+                .hint(suggest::did_you_mean(s))
+                .hint("Quantities with unknown units can't be converted"),
+            );
+        }
+    }
+
     fn quantity(
         &mut self,
         quantity: Located<parser::Quantity<'i>>,
@@ -1019,6 +1231,7 @@ impl<'i> RecipeCollector<'i, '_> {
             value,
             unit: unit.map(|t| t.text_trimmed().into_owned()),
             scalable,
+            exact: None,
         }
     }
 
@@ -1195,12 +1408,21 @@ impl<'i> RecipeCollector<'i, '_> {
             // extra reference checks
             Some((references_to, implicit))
         } else {
+            let suggestion = suggest::closest(
+                new.name(),
+                all.iter()
+                    .filter(|c| !c.modifiers().contains(Modifiers::REF))
+                    .map(|c| c.name()),
+            );
             self.ctx.error({
                 let mut e = error!(
                     format!("Reference not found: {}", new.name()),
                     label!(location)
-                )
-                .hint(format!(
+                );
+                if let Some(s) = suggestion {
+                    e.add_hint(suggest::did_you_mean(s));
+                }
+                e.add_hint(format!(
                     "A non reference {} with the same name defined BEFORE cannot be found",
                     C::container()
                 ));
@@ -1324,91 +1546,176 @@ impl RefComponent for Cookware {
     }
 }
 
+/// Max number of words in the unit of an inline quantity, like `°C fan`
+const INLINE_UNIT_MAX_WORDS: usize = 3;
+
+/// Finds the first inline quantity in the text
+///
+/// It can be a number with a unit after (`180 °C`, `160 °C fan`) or before
+/// it (`Gas Mark 4`), or a preset of a unit (`medium heat`).
 fn find_inline_quantity<'a>(
     text: &'a str,
     converter: &Converter,
 ) -> Option<(&'a str, Quantity, &'a str)> {
-    let mut i = 0;
-
-    fn eat_word<'a>(text: &'a str, i: &mut usize) -> Option<&'a str> {
-        let s = &text[*i..];
-        let offset = s.find(|c: char| c.is_whitespace()).or({
-            // if no whitespace until the end if there is anything left
-            if !s.is_empty() {
-                Some(s.len())
-            } else {
-                None
-            }
-        })?;
-        let word = &s[..offset];
-        *i += offset;
-        Some(word)
+    let number = find_number_quantity(text, converter);
+    let preset = find_preset_quantity(text, converter);
+    match (number, preset) {
+        (Some(n), Some(p)) if p.0.len() < n.0.len() => Some(p),
+        (n, p) => n.or(p),
     }
+}
 
-    fn eat_whitespace<'a>(text: &'a str, i: &mut usize) -> Option<&'a str> {
-        let offset = text[*i..].find(|c: char| !c.is_whitespace())?;
-        let ws = &text[*i..*i + offset];
-        *i += offset;
-        Some(ws)
-    }
+fn find_number_quantity<'a>(
+    text: &'a str,
+    converter: &Converter,
+) -> Option<(&'a str, Quantity, &'a str)> {
+    let bytes = text.as_bytes();
+    let eat_digits = |mut i: usize| {
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        i
+    };
 
-    #[cfg(debug_assertions)]
-    let mut prev = 0;
+    let mut i = 0;
     while let Some(offset) = text[i..].find(|c: char| c.is_ascii_digit()) {
-        i += offset;
-
-        // get "before" slice and check negative
-        let before: &str;
-        let neg: bool;
-        if i > 0 && text.as_bytes()[i - 1] == b'-' {
-            before = &text[..i - 1];
-            neg = true;
-        } else {
-            before = &text[..i];
-            neg = false;
+        let start = i + offset;
+        let mut end = eat_digits(start);
+        if end + 1 < bytes.len() && bytes[end] == b'.' && bytes[end + 1].is_ascii_digit() {
+            end = eat_digits(end + 1);
         }
+        i = end;
 
-        let w1 = eat_word(text, &mut i)?; // if no words, no more quantities
-        let first_non_digit =
-            w1.find(|c: char| !c.is_ascii_digit() && c != '.' && !c.is_whitespace());
-        let (mut number, mut unit) = if let Some(mid) = first_non_digit {
-            // split after number for unit
-            w1.split_at(mid)
-        } else {
-            // or take next word as unit
-            let _ = eat_whitespace(text, &mut i);
-            let w2 = eat_word(text, &mut i)?; // if no words, no more quantities
-            (w1, w2)
+        // check negative, but not in ranges like `1-2`
+        let mut q_start = start;
+        if start > 0 && bytes[start - 1] == b'-' {
+            if start > 1 && bytes[start - 2].is_ascii_alphanumeric() {
+                continue;
+            }
+            q_start = start - 1;
+        }
+        let Ok(mut number) = text[start..end].parse::<f64>() else {
+            continue;
         };
+        if q_start < start {
+            number = -number;
+        }
+        let value = Value::from(number);
+
+        // unit after the number, maybe without space like `2ºC`
+        let rest = &text[end..];
+        let unit_start = end + (rest.len() - rest.trim_start().len());
+        if let Some((unit, _)) = unit_at_start(&text[unit_start..], converter) {
+            let q = Quantity::new(value, Some(unit.to_string()));
+            return Some((&text[..q_start], q, &text[unit_start + unit.len()..]));
+        }
 
-        number = number.trim();
-        unit = unit.trim();
-
-        #[cfg(debug_assertions)]
+        // unit before the number, only for whole words and the units written
+        // that way, like `Gas Mark 4`
+        let head = &text[..q_start];
+        let is_word =
+            head.ends_with(char::is_whitespace) && !rest.starts_with(|c: char| c.is_alphanumeric());
+        if let Some(unit_start) = is_word
+            .then(|| unit_at_end(head.trim_end(), converter))
+            .flatten()
         {
-            debug_assert!(prev < i); // to be sure no infinite loop
-            prev = i;
+            let unit = head[unit_start..].trim_end();
+            let q = Quantity::new(value, Some(unit.to_string()));
+            return Some((&text[..unit_start], q, rest));
         }
+    }
 
-        let after = &text[i..];
-        let Ok(mut number) = number.parse::<f64>() else {
+    None
+}
+
+fn find_preset_quantity<'a>(
+    text: &'a str,
+    converter: &Converter,
+) -> Option<(&'a str, Quantity, &'a str)> {
+    let mut i = 0;
+    while let Some(offset) = text[i..].find(|c: char| !c.is_whitespace()) {
+        let start = i + offset;
+        let word_len = text[start..]
+            .find(char::is_whitespace)
+            .unwrap_or(text.len() - start);
+        i = start + word_len;
+
+        let word = &text[start..i];
+        let preset = word.trim_start_matches('(');
+        let rest = &text[i..];
+        let unit_start = i + (rest.len() - rest.trim_start().len());
+        if preset.is_empty() || unit_start == i || unit_start == text.len() {
             continue;
-        };
-        if converter.find_unit(unit).is_none() {
+        }
+        let Some((unit, info)) = unit_at_start(&text[unit_start..], converter) else {
             continue;
         };
-
-        if neg {
-            number = -number;
+        if info.preset(preset).is_some() {
+            let preset_start = start + word.len() - preset.len();
+            let q = Quantity::new(Value::Text(preset.to_string()), Some(unit.to_string()));
+            return Some((&text[..preset_start], q, &text[unit_start + unit.len()..]));
         }
-
-        let q = Quantity::new(Value::from(number), Some(unit.to_string()));
-        return Some((before, q, after));
     }
 
     None
 }
 
+/// Punctuation that can follow a unit but is not part of it
+const UNIT_TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')'];
+
+/// Longest known unit at the start of the text
+///
+/// The unit is up to [`INLINE_UNIT_MAX_WORDS`] words and may be followed by
+/// punctuation.
+fn unit_at_start<'a>(text: &'a str, converter: &Converter) -> Option<(&'a str, Arc<Unit>)> {
+    let mut ends = Vec::with_capacity(INLINE_UNIT_MAX_WORDS);
+    let mut end = 0;
+    while ends.len() < INLINE_UNIT_MAX_WORDS {
+        let Some(offset) = text[end..].find(|c: char| !c.is_whitespace()) else {
+            break;
+        };
+        let start = end + offset;
+        end = text[start..]
+            .find(char::is_whitespace)
+            .map_or(text.len(), |len| start + len);
+        ends.push(end);
+    }
+
+    ends.into_iter().rev().find_map(|end| {
+        let unit = &text[..end];
+        [unit, unit.trim_end_matches(UNIT_TRAILING_PUNCTUATION)]
+            .into_iter()
+            .filter(|u| !u.is_empty())
+            .find_map(|u| Some((u, converter.find_unit(u)?)))
+    })
+}
+
+/// Start of the longest unit at the end of the text that can be written
+/// before the number, see [`Unit::before_value`]
+///
+/// The unit is up to [`INLINE_UNIT_MAX_WORDS`] words.
+fn unit_at_end(text: &str, converter: &Converter) -> Option<usize> {
+    let mut starts = Vec::with_capacity(INLINE_UNIT_MAX_WORDS);
+    let mut start = text.len();
+    while starts.len() < INLINE_UNIT_MAX_WORDS {
+        let Some(end) = text[..start].rfind(|c: char| !c.is_whitespace()) else {
+            break;
+        };
+        start = text[..end]
+            .rfind(char::is_whitespace)
+            .map_or(0, |ws| ws + text[ws..].chars().next().unwrap().len_utf8());
+        starts.push(start);
+    }
+
+    starts.into_iter().rev().find_map(|start| {
+        let unit = text[start..].trim_start_matches('(');
+        converter
+            .find_unit(unit)
+            .filter(|u| u.before_value)
+            .map(|_| text.len() - unit.len())
+    })
+}
+
 fn note_reference_error(
     span: Span,
     implicit: bool,
@@ -1514,6 +1821,29 @@ fn parse_reference(name: &str) -> Option<RecipeReference> {
     }
 }
 
+/// Warning for unknown metadata keys that look like a typo of a [`StdKey`]
+fn std_key_typo(key: &str) -> Option<SourceDiag> {
+    if StdKey::from_str(key).is_ok() {
+        return None;
+    }
+    let s = suggest::closest(key, StdKey::NAMES.iter().map(|&(name, _)| name))?;
+    Some(
+        warning!(format!("Unknown metadata key: '{key}'"))
+            .hint(suggest::did_you_mean(s))
+            .hint("It will be a regular metadata entry"),
+    )
+}
+
+/// A number in a compound quantity: `4`, `1.5` or `1/2`
+fn compound_part_value(s: &str) -> Option<f64> {
+    if let Some((num, den)) = s.split_once('/') {
+        let num = num.trim().parse::<u32>().ok()?;
+        let den = den.trim().parse::<u32>().ok()?;
+        return (den != 0).then(|| num as f64 / den as f64);
+    }
+    s.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
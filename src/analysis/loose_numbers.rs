@@ -0,0 +1,236 @@
+//! Numbers written in other ways than the cooklang syntax
+//!
+//! See [`Extensions::LOOSE_NUMBERS`](crate::Extensions::LOOSE_NUMBERS).
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use crate::{
+    format::VULGAR_FRACTIONS,
+    quantity::{Number, Value},
+};
+
+/// Words that are read as numbers
+///
+/// Used with the [`LOOSE_NUMBERS`](crate::Extensions::LOOSE_NUMBERS)
+/// extension. A word can be more than one actual word, like `"a dozen"`.
+/// Matching is case insensitive and ignores repeated whitespace.
+///
+/// The [default](NumberWords::default) is [`NumberWords::english`].
+///
+/// ```
+/// # use cooklang::analysis::NumberWords;
+/// let words = NumberWords::new().with("dos", 2.0).with("media docena", 6.0);
+/// assert_eq!(words.get("Media  docena"), Some(6.0));
+/// assert_eq!(words.get("two"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberWords(HashMap<String, f64>);
+
+impl NumberWords {
+    /// Creates an empty list
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// English number words, from `one` to `twelve`, `half`, `a dozen`...
+    pub fn english() -> Self {
+        ENGLISH.clone()
+    }
+
+    /// Adds a new word, replacing the value if it already exists
+    pub fn insert(&mut self, word: &str, value: f64) {
+        self.0.insert(normalize(word), value);
+    }
+
+    /// Same as [`Self::insert`] but chainable
+    pub fn with(mut self, word: &str, value: f64) -> Self {
+        self.insert(word, value);
+        self
+    }
+
+    /// Gets the value of a word
+    pub fn get(&self, word: &str) -> Option<f64> {
+        self.0.get(&normalize(word)).copied()
+    }
+}
+
+impl Default for NumberWords {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+/// Words used when none are given in the [`ParseOptions`](crate::ParseOptions)
+pub(crate) fn default_words() -> &'static NumberWords {
+    &ENGLISH
+}
+
+static ENGLISH: LazyLock<NumberWords> = LazyLock::new(|| {
+    const WORDS: &[(&str, f64)] = &[
+        ("one", 1.0),
+        ("two", 2.0),
+        ("three", 3.0),
+        ("four", 4.0),
+        ("five", 5.0),
+        ("six", 6.0),
+        ("seven", 7.0),
+        ("eight", 8.0),
+        ("nine", 9.0),
+        ("ten", 10.0),
+        ("eleven", 11.0),
+        ("twelve", 12.0),
+        ("a couple", 2.0),
+        ("a couple of", 2.0),
+        ("dozen", 12.0),
+        ("a dozen", 12.0),
+        ("half a dozen", 6.0),
+        ("half", 0.5),
+        ("a half", 0.5),
+        ("one half", 0.5),
+        ("a third", 1.0 / 3.0),
+        ("one third", 1.0 / 3.0),
+        ("a quarter", 0.25),
+        ("one quarter", 0.25),
+    ];
+    let mut words = NumberWords::new();
+    for &(word, value) in WORDS {
+        words.insert(word, value);
+    }
+    words
+});
+
+fn normalize(word: &str) -> String {
+    word.split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Options to parse loose numbers
+pub(crate) struct LooseNumbers<'a> {
+    pub words: &'a NumberWords,
+    pub decimal_comma: bool,
+    pub ranges: bool,
+}
+
+impl LooseNumbers<'_> {
+    /// Parses a whole text as a value
+    pub fn value(&self, text: &str) -> Option<Value> {
+        let text = text.trim();
+        if let Some(n) = self.number(text) {
+            return Some(Value::Number(n));
+        }
+        // words are not used in ranges, so `half-dozen` is not `0.5-12`
+        if self.ranges {
+            let (start, end) = text.split_once('-')?;
+            let start = self.symbols(start.trim()).or_else(|| plain_number(start))?;
+            let end = self.symbols(end.trim()).or_else(|| plain_number(end))?;
+            return Some(Value::Range { start, end });
+        }
+        None
+    }
+
+    /// Splits a text in a value and the rest, which must be a unit
+    ///
+    /// The value is the longest group of whitespace separated words followed
+    /// by a unit.
+    pub fn split_value<'t>(
+        &self,
+        text: &'t str,
+        is_unit: impl Fn(&str) -> bool,
+    ) -> Option<(Value, &'t str)> {
+        let text = text.trim();
+        let splits = text
+            .char_indices()
+            .filter(|(_, c)| c.is_whitespace())
+            .map(|(i, _)| i)
+            .rev();
+        for at in splits {
+            let (value, unit) = text.split_at(at);
+            let unit = unit.trim();
+            if !is_unit(unit) {
+                continue;
+            }
+            if let Some(value) = self.value(value) {
+                return Some((value, unit));
+            }
+        }
+        None
+    }
+
+    fn number(&self, text: &str) -> Option<Number> {
+        if let Some(n) = self.words.get(text) {
+            return Some(Number::Regular(n));
+        }
+        self.symbols(text)
+    }
+
+    /// Numbers without words
+    fn symbols(&self, text: &str) -> Option<Number> {
+        if self.decimal_comma {
+            if let Some((int, dec)) = text.split_once(',') {
+                if is_digits(int) && is_digits(dec) {
+                    return format!("{int}.{dec}").parse().ok().map(Number::Regular);
+                }
+            }
+        }
+        unicode_fraction(text)
+    }
+}
+
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Numbers the parser already understands, but are needed for ranges like
+/// `1-1½`
+fn plain_number(text: &str) -> Option<Number> {
+    let text = text.trim();
+    if !is_digits(&text.replacen('.', "", 1)) {
+        return None;
+    }
+    text.parse().ok().map(Number::Regular)
+}
+
+/// `½`, `1½`, `1 ½` and `1⁄2` (with a fraction slash)
+fn unicode_fraction(text: &str) -> Option<Number> {
+    let (num, den, whole_part) = if let Some((a, b)) = text.split_once('\u{2044}') {
+        let (whole_part, num) = match a.trim().rsplit_once(char::is_whitespace) {
+            Some((w, n)) => (w, n),
+            None => ("", a.trim()),
+        };
+        let den = b.trim();
+        if !is_digits(num) || !is_digits(den) {
+            return None;
+        }
+        (num.parse().ok()?, den.parse().ok()?, whole_part)
+    } else {
+        let c = text.chars().next_back()?;
+        let (num, den) = vulgar_fraction_value(c)?;
+        (num, den, &text[..text.len() - c.len_utf8()])
+    };
+    if den == 0 {
+        return None;
+    }
+    let whole_part = whole_part.trim();
+    let whole = if whole_part.is_empty() {
+        0
+    } else if is_digits(whole_part) {
+        whole_part.parse().ok()?
+    } else {
+        return None;
+    };
+    Some(Number::Fraction {
+        whole,
+        num,
+        den,
+        err: 0.0,
+    })
+}
+
+fn vulgar_fraction_value(c: char) -> Option<(u32, u32)> {
+    VULGAR_FRACTIONS
+        .iter()
+        .find(|&&(v, _, _)| v == c)
+        .map(|&(_, num, den)| (num, den))
+}
@@ -9,8 +9,10 @@ use crate::{
 };
 
 mod event_consumer;
+mod loose_numbers;
 
 pub use event_consumer::parse_events;
+pub use loose_numbers::NumberWords;
 
 pub type AnalysisResult = PassResult<Recipe>;
 
@@ -40,6 +42,11 @@ pub struct ParseOptions<'a> {
     /// can customize what happens to the key, including not running the default
     /// checks.
     pub metadata_validator: Option<MetadataValidator<'a>>,
+    /// Words read as numbers with the
+    /// [`LOOSE_NUMBERS`](crate::Extensions::LOOSE_NUMBERS) extension
+    ///
+    /// If not given, [`NumberWords::english`].
+    pub number_words: Option<&'a NumberWords>,
 }
 
 /// Return type for check functions in [`ParseOptions`]
@@ -5,28 +5,41 @@ use thiserror::Error;
 
 use super::{
     convert_f64,
-    units_file::{self, BestUnits, Extend, Precedence, SIPrefix, UnitEntry, Units, UnitsFile, SI},
-    BestConversions, BestConversionsStore, Converter, Fractions, PhysicalQuantity, System, Unit,
-    UnitIndex, UnknownUnit,
+    humanize::{Humanize, RoundingSteps},
+    ingredients_file::IngredientsFile,
+    units_file::{
+        self, BestUnits, Extend, Precedence, QuantityName, RegionEntry, RoundingRule, SIPrefix,
+        UnitEntry, Units, UnitsFile, SI,
+    },
+    BestConversions, BestConversionsStore, Converter, CustomQuantity, Fractions, IngredientIndex,
+    PhysicalQuantity, QuantityMap, Region, System, Unit, UnitIndex, UnitQuantityIndex, UnknownUnit,
 };
 
+#[cfg(feature = "units_diagnostics")]
+mod lenient;
+
 /// Builder to create a custom [`Converter`]
 ///
 /// The builder uses [`UnitsFile`] to configure the converter. More than one
 /// file can be layered. Order matters, as one file can extend the units of
 /// another added before, or be overwritten by others after.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct ConverterBuilder {
     all_units: Vec<UnitBuilder>,
     unit_index: UnitIndex,
     extend: Vec<Extend>,
     si: SI,
     fractions: Vec<units_file::Fractions>,
-    best_units: EnumMap<PhysicalQuantity, Option<BestUnits>>,
+    humanize: Vec<units_file::Humanize>,
+    best_units: QuantityMap<Option<BestUnits>>,
+    custom_quantities: Vec<Arc<str>>,
     default_system: System,
+    unitless: Option<String>,
+    regions: HashMap<String, RegionEntry>,
+    ingredients: IngredientIndex,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct UnitBuilder {
     unit: Unit,
     is_expanded: bool,
@@ -80,7 +93,12 @@ impl ConverterBuilder {
 
     /// Add a [`UnitsFile`] to the builder
     pub fn add_units_file(&mut self, units: UnitsFile) -> Result<&mut Self, ConverterBuilderError> {
+        for name in units.custom_quantities {
+            self.declare_quantity(name)?;
+        }
+
         for group in units.quantity {
+            let quantity = resolve_quantity(&group.quantity, &self.custom_quantities)?;
             // Add all units to an index
             let mut add_units =
                 |units: Vec<UnitEntry>, system| -> Result<(), ConverterBuilderError> {
@@ -91,9 +109,19 @@ impl ConverterBuilder {
                             aliases: entry.aliases,
                             ratio: entry.ratio,
                             difference: entry.difference,
-                            physical_quantity: group.quantity,
+                            table: entry.table,
+                            presets: entry.presets,
+                            before_value: entry.before_value,
+                            physical_quantity: quantity,
                             system,
                         };
+                        check_table(&unit)?;
+                        if entry.expand_si && !unit.table.is_empty() {
+                            return Err(ConverterBuilderError::InvalidTable {
+                                unit: unit.symbol().to_string(),
+                                reason: "units with a table can't expand SI",
+                            });
+                        }
                         let _id = self.add_unit(UnitBuilder {
                             unit,
                             is_expanded: false,
@@ -126,12 +154,13 @@ impl ConverterBuilder {
                         metric.is_empty() || imperial.is_empty()
                     }
                 } {
-                    return Err(ConverterBuilderError::EmptyBest {
-                        reason: "empty list of units",
-                        quantity: group.quantity,
-                    });
+                    return Err(empty_best(
+                        quantity,
+                        "empty list of units",
+                        &self.custom_quantities,
+                    ));
                 }
-                self.best_units[group.quantity] = Some(best_units);
+                *self.best_units.entry(quantity) = Some(best_units);
             }
         }
 
@@ -159,6 +188,98 @@ impl ConverterBuilder {
             self.fractions.push(fractions);
         }
 
+        if let Some(humanize) = units.humanize {
+            self.humanize.push(humanize);
+        }
+
+        if let Some(unitless) = units.unitless {
+            self.unitless = Some(unitless);
+        }
+
+        // Join the regions with the same name
+        for (name, region) in units.region {
+            let entry = self.regions.entry(name).or_default();
+            if region.system.is_some() {
+                entry.system = region.system;
+            }
+            if !region.locales.is_empty() {
+                entry.locales = region.locales;
+            }
+            entry.units.extend(region.units);
+            entry.best.extend(region.best);
+        }
+
+        Ok(self)
+    }
+
+    fn declare_quantity(&mut self, name: String) -> Result<(), ConverterBuilderError> {
+        let invalid = |reason| ConverterBuilderError::InvalidQuantity {
+            name: name.clone(),
+            reason,
+        };
+        if name.trim().is_empty() {
+            return Err(invalid("empty name"));
+        }
+        if PhysicalQuantity::builtin(&name).is_some() {
+            return Err(invalid("it's a built-in quantity"));
+        }
+        if self.custom_quantities.iter().any(|n| n.as_ref() == name) {
+            return Ok(());
+        }
+        let Some(id) = CustomQuantity::new(self.custom_quantities.len()) else {
+            return Err(invalid("too many custom quantities"));
+        };
+        // without best units the converter can't be built
+        self.best_units.entry(PhysicalQuantity::Custom(id));
+        self.custom_quantities.push(name.into());
+        Ok(())
+    }
+
+    /// Add an [`IngredientsFile`] to the builder
+    pub fn with_ingredients_file(
+        mut self,
+        ingredients: IngredientsFile,
+    ) -> Result<Self, ConverterBuilderError> {
+        self.add_ingredients_file(ingredients)?;
+        Ok(self)
+    }
+
+    /// Add an [`IngredientsFile`] to the builder
+    ///
+    /// Names of ingredients in this file override the same names of files added
+    /// before.
+    pub fn add_ingredients_file(
+        &mut self,
+        ingredients: IngredientsFile,
+    ) -> Result<&mut Self, ConverterBuilderError> {
+        let mut added = HashMap::new();
+        for entry in ingredients.ingredient {
+            let main_name = entry.names.first().cloned().unwrap_or_default();
+            let invalid = |reason| ConverterBuilderError::InvalidIngredient {
+                name: main_name.clone(),
+                reason,
+            };
+            if entry.names.is_empty() || entry.names.iter().any(|n| n.trim().is_empty()) {
+                return Err(invalid("empty name"));
+            }
+            let valid = |v: f64| v.is_finite() && v > 0.0;
+            if entry.density.is_some_and(|d| !valid(d))
+                || entry.piece.is_some_and(|p| !valid(p))
+                || entry.units.values().any(|&m| !valid(m))
+            {
+                return Err(invalid("values must be positive numbers"));
+            }
+
+            let id = self.ingredients.entries.len();
+            for name in &entry.names {
+                let key = name.trim().to_lowercase();
+                if added.insert(key.clone(), id).is_some() {
+                    return Err(ConverterBuilderError::DuplicateIngredient { name: name.clone() });
+                }
+                self.ingredients.index.insert(key, id);
+            }
+            self.ingredients.entries.push(entry);
+        }
         Ok(self)
     }
 
@@ -184,25 +305,49 @@ impl ConverterBuilder {
             &self.si,
         )?;
 
-        let best = enum_map! {
-            q =>  {
-                if let Some(best_units) = &self.best_units[q] {
-                    BestConversionsStore::new(best_units, &self.unit_index, &self.all_units)?
-                } else {
-                    return Err(ConverterBuilderError::EmptyBest { reason: "no best units given", quantity: q })
-                }
+        let best = std::mem::take(&mut self.best_units).try_map(|q, best_units| {
+            if let Some(best_units) = &best_units {
+                BestConversionsStore::new(best_units, &self.unit_index, &self.all_units)
+            } else {
+                Err(empty_best(
+                    q,
+                    "no best units given",
+                    &self.custom_quantities,
+                ))
             }
-        };
+        })?;
 
         let quantity_index = {
-            let mut index: EnumMap<PhysicalQuantity, Vec<usize>> = EnumMap::default();
+            let mut index = UnitQuantityIndex::default();
             for (id, unit) in self.all_units.iter().enumerate() {
-                index[unit.physical_quantity].push(id);
+                index.entry(unit.physical_quantity).push(id);
             }
             index
         };
 
-        let fractions = build_fractions_config(&self.fractions, &self.unit_index, &self.all_units)?;
+        let fractions = build_fractions_config(
+            &self.fractions,
+            &self.unit_index,
+            &self.all_units,
+            &self.custom_quantities,
+        )?;
+        let humanize =
+            build_humanize_config(&self.humanize, &self.unit_index, &self.custom_quantities)?;
+
+        let regions = build_regions(
+            self.regions,
+            &mut self.all_units,
+            &self.unit_index,
+            &best,
+            &self.custom_quantities,
+            self.default_system,
+        )?;
+
+        let unitless = self
+            .unitless
+            .as_deref()
+            .map(|key| self.unit_index.get_unit_id(key))
+            .transpose()?;
 
         Ok(Converter {
             all_units: self
@@ -213,8 +358,13 @@ impl ConverterBuilder {
             unit_index: self.unit_index,
             quantity_index,
             best,
+            custom_quantities: self.custom_quantities,
             fractions,
+            humanize,
             default_system: self.default_system,
+            unitless,
+            regions,
+            ingredients: self.ingredients,
         })
     }
 
@@ -251,11 +401,14 @@ impl BestConversions {
         unit_index: &UnitIndex,
         all_units: &[UnitBuilder],
     ) -> Result<Self, ConverterBuilderError> {
-        let mut units = units
+        let units = units
             .iter()
             .map(|n| unit_index.get_unit_id(n))
             .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_ids(units, all_units))
+    }
 
+    fn from_ids(mut units: Vec<usize>, all_units: &[UnitBuilder]) -> Self {
         units.sort_by(|a, b| {
             let a = &all_units[*a];
             let b = &all_units[*b];
@@ -275,7 +428,7 @@ impl BestConversions {
             conversions.push((v, unit));
         }
 
-        Ok(Self(conversions))
+        Self(conversions)
     }
 }
 
@@ -299,6 +452,9 @@ fn apply_extend_groups(
             if all_units[id].is_expanded
                 && (entry.ratio.is_some()
                     || entry.difference.is_some()
+                    || entry.table.is_some()
+                    || entry.presets.is_some()
+                    || entry.before_value.is_some()
                     || entry.names.is_some()
                     || entry.symbols.is_some())
             {
@@ -320,6 +476,22 @@ fn apply_extend_groups(
             if let Some(difference) = entry.difference {
                 unit.difference = difference;
             }
+            if let Some(table) = entry.table {
+                if !table.is_empty() && unit.expand_si {
+                    return Err(ConverterBuilderError::InvalidTable {
+                        unit: unit.symbol().to_string(),
+                        reason: "units with a table can't expand SI",
+                    });
+                }
+                unit.table = table;
+                check_table(unit)?;
+            }
+            if let Some(presets) = entry.presets {
+                unit.presets = presets;
+            }
+            if let Some(before_value) = entry.before_value {
+                unit.before_value = before_value;
+            }
             if let Some(names) = entry.names {
                 join_alias_vec(&mut unit.names, names, precedence);
             }
@@ -358,10 +530,127 @@ fn update_expanded_units(
     Ok(())
 }
 
+fn check_table(unit: &Unit) -> Result<(), ConverterBuilderError> {
+    if unit.table.is_empty() {
+        return Ok(());
+    }
+    let invalid = |reason| ConverterBuilderError::InvalidTable {
+        unit: unit.symbol().to_string(),
+        reason,
+    };
+    if unit.table.len() < 2 {
+        return Err(invalid("at least 2 points are needed"));
+    }
+    if unit
+        .table
+        .iter()
+        .any(|&(a, b)| !a.is_finite() || !b.is_finite())
+    {
+        return Err(invalid("values must be finite"));
+    }
+    if unit
+        .table
+        .windows(2)
+        .any(|w| w[0].0 >= w[1].0 || w[0].1 >= w[1].1)
+    {
+        return Err(invalid("values must be strictly increasing"));
+    }
+    Ok(())
+}
+
+fn build_regions(
+    regions: HashMap<String, RegionEntry>,
+    all_units: &mut Vec<UnitBuilder>,
+    unit_index: &UnitIndex,
+    best: &QuantityMap<BestConversionsStore>,
+    custom_quantities: &[Arc<str>],
+    default_system: System,
+) -> Result<Vec<Region>, ConverterBuilderError> {
+    let mut regions = regions.into_iter().collect::<Vec<_>>();
+    regions.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut built = Vec::with_capacity(regions.len());
+    for (name, entry) in regions {
+        let invalid = |reason| ConverterBuilderError::InvalidRegion {
+            region: name.clone(),
+            reason,
+        };
+        let system = entry.system.unwrap_or(default_system);
+
+        // Add the regional variants. They are not in the index, only in the
+        // region.
+        let mut variants = entry.units.into_iter().collect::<Vec<_>>();
+        variants.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut units = HashMap::with_capacity(variants.len());
+        for (key, ratio) in variants {
+            if !ratio.is_finite() || ratio <= 0.0 {
+                return Err(invalid("unit ratios must be positive numbers"));
+            }
+            let id = unit_index.get_unit_id(&key)?;
+            if units.contains_key(&id) {
+                return Err(invalid("more than one key for the same unit"));
+            }
+            if !all_units[id].table.is_empty() {
+                return Err(invalid("units with a table can't have variants"));
+            }
+            let variant = UnitBuilder {
+                unit: Unit {
+                    ratio,
+                    ..all_units[id].unit.clone()
+                },
+                is_expanded: false,
+                expand_si: false,
+                expanded_units: None,
+            };
+            units.insert(id, all_units.len());
+            all_units.push(variant);
+        }
+        let resolve = |id| units.get(&id).copied().unwrap_or(id);
+
+        let mut region_best = QuantityMap::<Option<BestConversions>>::default();
+        for (name, keys) in entry.best {
+            let quantity = resolve_quantity(&name, custom_quantities)?;
+            if keys.is_empty() {
+                return Err(empty_best(
+                    quantity,
+                    "empty list of units",
+                    custom_quantities,
+                ));
+            }
+            let ids = keys
+                .iter()
+                .map(|k| unit_index.get_unit_id(k).map(resolve))
+                .collect::<Result<Vec<_>, _>>()?;
+            *region_best.entry(quantity) = Some(BestConversions::from_ids(ids, all_units));
+        }
+        // the best units of the system, with the variants
+        for (quantity, store) in best.iter() {
+            if region_best.get(quantity).is_some_and(|b| b.is_some()) {
+                continue;
+            }
+            let ids = store.conversions(system).0.iter().map(|&(_, id)| id);
+            if ids.clone().any(|id| units.contains_key(&id)) {
+                let ids = ids.map(resolve).collect();
+                *region_best.entry(quantity) = Some(BestConversions::from_ids(ids, all_units));
+            }
+        }
+
+        built.push(Region {
+            name,
+            system,
+            locales: entry.locales,
+            units,
+            best: region_best,
+        });
+    }
+    Ok(built)
+}
+
 fn build_fractions_config(
     fractions: &[units_file::Fractions],
     unit_index: &UnitIndex,
     all_units: &[UnitBuilder],
+    custom_quantities: &[Arc<str>],
 ) -> Result<Fractions, ConverterBuilderError> {
     let mut all = None;
 
@@ -376,8 +665,9 @@ fn build_fractions_config(
     for cfg in fractions.iter() {
         metric = cfg.metric.map(|c| c.get()).or(metric);
         imperial = cfg.imperial.map(|c| c.get()).or(imperial);
-        for (q, cfg) in &cfg.quantity {
-            quantity.insert(*q, cfg.get());
+        for (name, cfg) in &cfg.quantity {
+            let q = resolve_quantity(name, custom_quantities)?;
+            quantity.insert(q, cfg.get());
         }
     }
 
@@ -416,6 +706,83 @@ fn build_fractions_config(
     })
 }
 
+fn build_humanize_config(
+    humanize: &[units_file::Humanize],
+    unit_index: &UnitIndex,
+    custom_quantities: &[Arc<str>],
+) -> Result<Humanize, ConverterBuilderError> {
+    let steps = |key: &str, rule: &RoundingRule| {
+        RoundingSteps::new(rule).map_err(|reason| ConverterBuilderError::InvalidHumanize {
+            key: key.to_string(),
+            reason,
+        })
+    };
+
+    // later layers overwrite the rules of the ones before
+    let mut built = Humanize::default();
+    for cfg in humanize {
+        if let Some(rule) = &cfg.all {
+            built.all = Some(steps("all", rule)?);
+        }
+        if let Some(rule) = &cfg.metric {
+            built.metric = Some(steps("metric", rule)?);
+        }
+        if let Some(rule) = &cfg.imperial {
+            built.imperial = Some(steps("imperial", rule)?);
+        }
+        if let Some(rule) = &cfg.unitless {
+            built.unitless = Some(steps("unitless", rule)?);
+        }
+        for (name, rule) in &cfg.quantity {
+            let q = resolve_quantity(name, custom_quantities)?;
+            let key = match name {
+                QuantityName::Builtin(q) => q.to_string(),
+                QuantityName::Custom(name) => name.clone(),
+            };
+            built.quantity.insert(q, steps(&key, rule)?);
+        }
+        for (key, rule) in &cfg.unit {
+            let unit_id = unit_index.get_unit_id(key)?;
+            built.unit.insert(unit_id, steps(key, rule)?);
+        }
+    }
+    Ok(built)
+}
+
+fn resolve_quantity(
+    name: &QuantityName,
+    custom_quantities: &[Arc<str>],
+) -> Result<PhysicalQuantity, ConverterBuilderError> {
+    let undeclared = |name: String| ConverterBuilderError::UndeclaredQuantity { name };
+    match name {
+        QuantityName::Builtin(PhysicalQuantity::Custom(c)) if c.id() >= custom_quantities.len() => {
+            Err(undeclared(c.to_string()))
+        }
+        QuantityName::Builtin(q) => Ok(*q),
+        QuantityName::Custom(name) => custom_quantities
+            .iter()
+            .position(|n| n.as_ref() == name)
+            .and_then(CustomQuantity::new)
+            .map(PhysicalQuantity::Custom)
+            .ok_or_else(|| undeclared(name.clone())),
+    }
+}
+
+/// Custom quantities are reported by name
+fn empty_best(
+    quantity: PhysicalQuantity,
+    reason: &'static str,
+    custom_quantities: &[Arc<str>],
+) -> ConverterBuilderError {
+    match quantity {
+        PhysicalQuantity::Custom(c) => ConverterBuilderError::InvalidQuantity {
+            name: custom_quantities[c.id()].to_string(),
+            reason,
+        },
+        quantity => ConverterBuilderError::EmptyBest { reason, quantity },
+    }
+}
+
 fn join_alias_vec(target: &mut Vec<Arc<str>>, mut src: Vec<Arc<str>>, src_precedence: Precedence) {
     match src_precedence {
         Precedence::Before => {
@@ -484,6 +851,9 @@ fn expand_si(
                 aliases: Vec::new(),
                 ratio: unit.ratio * prefix.ratio(),
                 difference: unit.difference,
+                table: Vec::new(),
+                presets: HashMap::new(),
+                before_value: false,
                 physical_quantity: unit.physical_quantity,
                 system: unit.system,
             },                expand_si: false,
@@ -566,4 +936,28 @@ pub enum ConverterBuilderError {
 
     #[error("No SI prefixes found when expandind SI on a unit")]
     EmptySIPrefixes,
+
+    #[error("Physical quantity '{name}' is not declared in the custom quantities")]
+    UndeclaredQuantity { name: String },
+
+    #[error("Invalid custom quantity '{name}': {reason}")]
+    InvalidQuantity { name: String, reason: &'static str },
+
+    #[error("Invalid region '{region}': {reason}")]
+    InvalidRegion {
+        region: String,
+        reason: &'static str,
+    },
+
+    #[error("Invalid conversion table for '{unit}': {reason}")]
+    InvalidTable { unit: String, reason: &'static str },
+
+    #[error("Invalid humanize rule for '{key}': {reason}")]
+    InvalidHumanize { key: String, reason: &'static str },
+
+    #[error("Duplicate ingredient: {name}")]
+    DuplicateIngredient { name: String },
+
+    #[error("Invalid ingredient conversion for '{name}': {reason}")]
+    InvalidIngredient { name: String, reason: &'static str },
 }
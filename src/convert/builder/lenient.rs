@@ -0,0 +1,436 @@
+//! Units files with diagnostics pointing into their TOML source
+
+use std::ops::Range;
+
+use toml_edit::{Item, TableLike, Value};
+
+use super::{ConverterBuilder, ConverterBuilderError};
+use crate::{
+    convert::{
+        units_file::{ExtendUnitEntry, Precedence, UnitsFile},
+        UnknownUnit,
+    },
+    error::{label, PassResult, Severity, SourceDiag, SourceReport, Stage},
+    span::Span,
+};
+
+impl ConverterBuilder {
+    /// Parse a [`UnitsFile`] in TOML and check it on top of the builder
+    ///
+    /// Unlike deserializing the file and adding it with
+    /// [`Self::add_units_file`], problems are reported as diagnostics that
+    /// point into `input`. This includes the ones that would only be found in
+    /// [`Self::finish`], like best units that are not defined. The files
+    /// already in the builder are taken into account, so usually the bundled
+    /// units should be added first.
+    ///
+    /// The builder is not modified. If there are no errors, the file is
+    /// returned and can be added to the builder. There are also warnings for
+    /// `extend` entries that drop names, symbols or aliases of a unit or
+    /// overwrite the changes of another file.
+    ///
+    /// This is only available with the `units_diagnostics` feature.
+    ///
+    /// ```
+    /// # use cooklang::Converter;
+    /// let builder = Converter::builder().with_bundled_units().unwrap();
+    /// let input = r#"
+    /// [extend.units]
+    /// cup = { aliases = ["mug"] }
+    /// tsp = { aliases = ["mug"] }
+    /// "#;
+    /// let report = builder.parse_units_file_lenient(input).into_report();
+    /// let error = report.errors().next().unwrap();
+    /// assert_eq!(error.message, "Duplicate unit: mug");
+    /// ```
+    pub fn parse_units_file_lenient(&self, input: &str) -> PassResult<UnitsFile> {
+        let mut report = SourceReport::empty();
+
+        let doc = match toml_edit::ImDocument::parse(input) {
+            Ok(doc) => doc,
+            Err(e) => {
+                report.error(at(e.message().to_string(), e.span(), Severity::Error));
+                return PassResult::new(None, report);
+            }
+        };
+        let file = match toml::from_str::<UnitsFile>(input) {
+            Ok(file) => file,
+            Err(e) => {
+                report.error(at(e.message().to_string(), e.span(), Severity::Error));
+                return PassResult::new(None, report);
+            }
+        };
+
+        let locs = Locations::new(doc.as_item());
+        self.extend_warnings(&file, &locs, &mut report);
+
+        let mut trial = self.clone();
+        let checked = match trial.add_units_file(file.clone()) {
+            Ok(_) => trial.finish().map(|_| ()),
+            Err(e) => Err(e),
+        };
+        match checked {
+            Ok(()) => PassResult::new(Some(file), report),
+            Err(e) => {
+                report.error(self.error_diag(e, &locs));
+                PassResult::new(None, report)
+            }
+        }
+    }
+
+    fn extend_warnings(&self, file: &UnitsFile, locs: &Locations, report: &mut SourceReport) {
+        if let Some(si) = &file.si {
+            let replaced = si.precedence == Precedence::Override
+                && (si.prefixes.is_some() && self.si.prefixes.is_some()
+                    || si.symbol_prefixes.is_some() && self.si.symbol_prefixes.is_some());
+            if replaced {
+                let span = locs.key("si.precedence").map(|l| l.span.clone());
+                report.warn(
+                    at(
+                        "SI prefixes from other files are replaced",
+                        span,
+                        Severity::Warning,
+                    )
+                    .hint("Use `precedence = \"before\"` or `\"after\"` to keep them"),
+                );
+            }
+        }
+
+        let Some(extend) = &file.extend else {
+            return;
+        };
+        let mut keys = extend.units.iter().collect::<Vec<_>>();
+        keys.sort_by_key(|(k, _)| locs.key(&format!("extend.units.{k}")).map(|l| l.span.start));
+        for (key, entry) in keys {
+            let Ok(id) = self.unit_index.get_unit_id(key) else {
+                // reported as an error later
+                continue;
+            };
+            let span = locs
+                .key(&format!("extend.units.{key}"))
+                .map(|l| l.span.clone());
+            let unit = &self.all_units[id];
+
+            if extend.precedence == Precedence::Override {
+                let removed = [
+                    (&entry.names, &unit.names),
+                    (&entry.symbols, &unit.symbols),
+                    (&entry.aliases, &unit.aliases),
+                ]
+                .into_iter()
+                .filter_map(|(new, old)| new.as_ref().map(|new| (new, old)))
+                .flat_map(|(new, old)| old.iter().filter(|o| !new.contains(o)))
+                .map(|s| format!("'{s}'"))
+                .collect::<Vec<_>>();
+                if !removed.is_empty() {
+                    report.warn(
+                        at(
+                            format!("Override removes {} from unit '{key}'", removed.join(", ")),
+                            span.clone(),
+                            Severity::Warning,
+                        )
+                        .hint("Recipes using them will not find the unit anymore"),
+                    );
+                }
+            }
+
+            let previous = self
+                .extend
+                .iter()
+                .flat_map(|e| e.units.iter().map(move |(k, v)| (k, v, e.precedence)))
+                .filter(|(k, _, _)| self.unit_index.get_unit_id(k).is_ok_and(|i| i == id));
+            for (_, prev, prev_precedence) in previous {
+                let overwritten = overwritten_fields(prev, entry, extend.precedence);
+                if overwritten.is_empty() && prev_precedence != Precedence::Override {
+                    continue;
+                }
+                let fields = if overwritten.is_empty() {
+                    "the changes".to_string()
+                } else {
+                    overwritten.join(", ")
+                };
+                report.warn(
+                    at(
+                        format!("Unit '{key}' is also extended by another units file"),
+                        span.clone(),
+                        Severity::Warning,
+                    )
+                    .hint(format!("This file overwrites {fields} from the other")),
+                );
+                break;
+            }
+        }
+    }
+
+    fn error_diag(&self, e: ConverterBuilderError, locs: &Locations) -> SourceDiag {
+        let units = |l: &&Loc| l.under("quantity.units") || l.under("extend.units");
+        let mut diag = match &e {
+            ConverterBuilderError::DuplicateUnit { name } => {
+                let found = locs.strings(name).filter(units).collect::<Vec<_>>();
+                let mut diag = at(
+                    e.to_string(),
+                    found.last().map(|l| l.span.clone()),
+                    Severity::Error,
+                );
+                if let [first, _, ..] = found.as_slice() {
+                    diag.add_label(label!(Span::from(first.span.clone()), "first defined here"));
+                } else {
+                    diag.add_hint(format!("'{name}' is already used by another unit"));
+                }
+                diag
+            }
+            ConverterBuilderError::UnknownUnit(UnknownUnit(unit)) => {
+                let found = locs
+                    .all(unit)
+                    .find(|l| !units(l))
+                    .or_else(|| locs.keys(unit).next());
+                let mut diag = at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                );
+                let known = self.unit_index.0.keys().map(|k| k.as_ref()).chain(
+                    locs.locs
+                        .iter()
+                        .filter(|l| !l.key && units(l))
+                        .map(|l| l.text.as_str()),
+                );
+                if let Some(suggestion) = crate::suggest::closest(unit, known) {
+                    diag.add_hint(crate::suggest::did_you_mean(suggestion));
+                }
+                diag
+            }
+            ConverterBuilderError::DuplicateExtendUnit { key }
+            | ConverterBuilderError::InvalidExtendExpanded { key } => {
+                let found = locs.key(&format!("extend.units.{key}"));
+                at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                )
+            }
+            ConverterBuilderError::EmptyUnitKey { .. } => {
+                let found = locs
+                    .locs
+                    .iter()
+                    .find(|l| !l.key && units(l) && l.text.trim().is_empty());
+                at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                )
+            }
+            ConverterBuilderError::EmptyBest { quantity, .. } => {
+                let name = quantity.to_string();
+                let found = locs.all(&name).next();
+                at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                )
+            }
+            ConverterBuilderError::UndeclaredQuantity { name }
+            | ConverterBuilderError::InvalidQuantity { name, .. } => {
+                let found = locs.all(name).next();
+                at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                )
+            }
+            ConverterBuilderError::EmptySIPrefixes => {
+                let found = locs.locs.iter().find(|l| l.key && l.text == "expand_si");
+                at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                )
+                .hint("Add `[si.prefixes]` and `[si.symbol_prefixes]`")
+            }
+            ConverterBuilderError::InvalidRegion { region, .. } => {
+                let found = locs.key(&format!("region.{region}"));
+                at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                )
+            }
+            ConverterBuilderError::InvalidTable { unit, .. } => {
+                // the table after the unit
+                let unit_loc = locs.strings(unit).find(units);
+                let found = unit_loc.and_then(|u| {
+                    locs.locs
+                        .iter()
+                        .find(|l| l.key && l.text == "table" && l.span.start > u.span.start)
+                        .or(Some(u))
+                });
+                at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                )
+            }
+            ConverterBuilderError::InvalidHumanize { key, .. } => {
+                let found = locs
+                    .keys(key)
+                    .find(|l| l.under("humanize"))
+                    .or_else(|| locs.all(key).next());
+                at(
+                    e.to_string(),
+                    found.map(|l| l.span.clone()),
+                    Severity::Error,
+                )
+            }
+            ConverterBuilderError::EmptyUnit { .. }
+            | ConverterBuilderError::DuplicateIngredient { .. }
+            | ConverterBuilderError::InvalidIngredient { .. } => {
+                at(e.to_string(), None, Severity::Error)
+            }
+        };
+        if diag.labels.is_empty() {
+            diag.add_hint("The problem may be in another units file");
+        }
+        diag.set_source(e)
+    }
+}
+
+/// Fields of a unit that `new` overwrites from `prev`
+fn overwritten_fields(
+    prev: &ExtendUnitEntry,
+    new: &ExtendUnitEntry,
+    precedence: Precedence,
+) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if prev.ratio.is_some() && new.ratio.is_some() {
+        fields.push("ratio");
+    }
+    if prev.difference.is_some() && new.difference.is_some() {
+        fields.push("difference");
+    }
+    if prev.table.is_some() && new.table.is_some() {
+        fields.push("table");
+    }
+    if precedence == Precedence::Override {
+        if prev.names.is_some() && new.names.is_some() {
+            fields.push("names");
+        }
+        if prev.symbols.is_some() && new.symbols.is_some() {
+            fields.push("symbols");
+        }
+        if prev.aliases.is_some() && new.aliases.is_some() {
+            fields.push("aliases");
+        }
+    }
+    fields
+}
+
+fn at(message: impl Into<String>, span: Option<Range<usize>>, severity: Severity) -> SourceDiag {
+    let message = message.into();
+    match span {
+        Some(span) => match severity {
+            Severity::Error => SourceDiag::error(message, label!(Span::from(span)), Stage::Parse),
+            Severity::Warning => {
+                SourceDiag::warning(message, label!(Span::from(span)), Stage::Parse)
+            }
+        },
+        None => SourceDiag::unlabeled(message, severity, Stage::Parse),
+    }
+}
+
+/// A key or string value in the TOML source
+struct Loc {
+    /// Dotted path of the keys to it, arrays are not included
+    path: String,
+    text: String,
+    span: Range<usize>,
+    key: bool,
+}
+
+impl Loc {
+    fn under(&self, prefix: &str) -> bool {
+        self.path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    }
+}
+
+/// All the keys and strings of the TOML source, in order
+struct Locations {
+    locs: Vec<Loc>,
+}
+
+impl Locations {
+    fn new(root: &Item) -> Self {
+        let mut locs = Vec::new();
+        walk_item(root, "", &mut locs);
+        locs.sort_by_key(|l| l.span.start);
+        Self { locs }
+    }
+
+    fn key(&self, path: &str) -> Option<&Loc> {
+        self.locs.iter().find(|l| l.key && l.path == path)
+    }
+
+    fn keys<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a Loc> {
+        self.locs.iter().filter(move |l| l.key && l.text == text)
+    }
+
+    fn strings<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a Loc> {
+        self.locs.iter().filter(move |l| !l.key && l.text == text)
+    }
+
+    /// Strings first, then keys
+    fn all<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a Loc> {
+        self.strings(text).chain(self.keys(text))
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn walk_item(item: &Item, path: &str, out: &mut Vec<Loc>) {
+    match item {
+        Item::Table(t) => walk_table(t, path, out),
+        Item::ArrayOfTables(a) => a.iter().for_each(|t| walk_table(t, path, out)),
+        Item::Value(v) => walk_value(v, path, out),
+        Item::None => {}
+    }
+}
+
+fn walk_table(table: &dyn TableLike, path: &str, out: &mut Vec<Loc>) {
+    for (k, item) in table.iter() {
+        let path = join(path, k);
+        if let Some(span) = table.get_key_value(k).and_then(|(key, _)| key.span()) {
+            out.push(Loc {
+                path: path.clone(),
+                text: k.to_string(),
+                span,
+                key: true,
+            });
+        }
+        walk_item(item, &path, out);
+    }
+}
+
+fn walk_value(value: &Value, path: &str, out: &mut Vec<Loc>) {
+    match value {
+        Value::String(s) => {
+            if let Some(span) = s.span() {
+                out.push(Loc {
+                    path: path.to_string(),
+                    text: s.value().clone(),
+                    span,
+                    key: false,
+                });
+            }
+        }
+        Value::Array(a) => a.iter().for_each(|v| walk_value(v, path, out)),
+        Value::InlineTable(t) => walk_table(t, path, out),
+        _ => {}
+    }
+}
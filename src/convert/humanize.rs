@@ -0,0 +1,201 @@
+//! Rounding of quantities to amounts that are easy to measure
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{units_file::RoundingRule, Converter, FractionsConfig, PhysicalQuantity, System, Unit};
+use crate::{
+    quantity::{Number, Quantity, Value},
+    Recipe,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Humanize {
+    pub all: Option<RoundingSteps>,
+    pub metric: Option<RoundingSteps>,
+    pub imperial: Option<RoundingSteps>,
+    pub quantity: HashMap<PhysicalQuantity, RoundingSteps>,
+    pub unit: HashMap<usize, RoundingSteps>,
+    pub unitless: Option<RoundingSteps>,
+}
+
+impl Humanize {
+    fn steps(
+        &self,
+        system: Option<System>,
+        quantity: PhysicalQuantity,
+        unit_id: usize,
+    ) -> Option<&RoundingSteps> {
+        self.unit
+            .get(&unit_id)
+            .or_else(|| self.quantity.get(&quantity))
+            .or_else(|| {
+                system.and_then(|s| match s {
+                    System::Metric => self.metric.as_ref(),
+                    System::Imperial => self.imperial.as_ref(),
+                })
+            })
+            .or(self.all.as_ref())
+    }
+}
+
+/// A resolved [`RoundingRule`]
+///
+/// `(above, step)` pairs sorted by `above`. Empty when rounding is disabled.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RoundingSteps(Vec<(f64, f64)>);
+
+impl RoundingSteps {
+    pub fn new(rule: &RoundingRule) -> Result<Self, &'static str> {
+        let mut steps = match rule {
+            RoundingRule::Toggle(false) => vec![],
+            RoundingRule::Toggle(true) => vec![(0.0, 1.0)],
+            RoundingRule::Step(s) => vec![(s.above, s.step)],
+            RoundingRule::Tiers(tiers) => {
+                if tiers.is_empty() {
+                    return Err("no steps given");
+                }
+                tiers.iter().map(|s| (s.above, s.step)).collect()
+            }
+        };
+        for &(above, step) in &steps {
+            if !step.is_finite() || step <= 0.0 {
+                return Err("steps must be positive");
+            }
+            if !above.is_finite() {
+                return Err("above values must be finite");
+            }
+        }
+        steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if steps.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err("duplicate above value");
+        }
+        Ok(Self(steps))
+    }
+
+    /// Rounds to the nearest multiple of the step for the value
+    ///
+    /// Returns `None` if there is no step for the value or if it would be
+    /// rounded to 0.
+    fn round(&self, value: f64) -> Option<f64> {
+        let &(_, step) = self.0.iter().rfind(|&&(above, _)| value >= above)?;
+        let rounded = (value / step).round() * step;
+        (rounded != 0.0).then_some(rounded)
+    }
+}
+
+impl Converter {
+    /// Gets the rounding steps for a unit or for no unit
+    ///
+    /// Units that are not in the converter have no steps.
+    fn humanize_steps(&self, unit: Option<&Unit>) -> Option<&RoundingSteps> {
+        let Some(unit) = unit else {
+            return self.humanize.unitless.as_ref();
+        };
+        let unit_id = self.unit_index.get_unit_id(unit.symbol()).ok()?;
+        self.humanize
+            .steps(unit.system, unit.physical_quantity, unit_id)
+    }
+}
+
+impl Quantity {
+    /// Rounds the quantity to an amount that is easy to measure
+    ///
+    /// For example, `0.37 tsp` becomes `3/8 tsp` and `123 g` becomes `125 g`.
+    /// The rounding rules are configured in the converter, see
+    /// [`UnitsFile::humanize`](super::UnitsFile::humanize), and the result is
+    /// a fraction when [fractions](super::UnitsFile::fractions) are enabled
+    /// for the unit.
+    ///
+    /// The true value is kept apart, see [`Quantity::exact_value`], so
+    /// adding, scaling or converting humanized quantities is still exact.
+    /// Use [`Quantity::is_approx`] to know if the value was changed and
+    /// [`Quantity::approx_error`] to know by how much.
+    ///
+    /// Text values, unknown units and values that would be rounded to `0` are
+    /// not changed. Returns `true` if any number was rounded.
+    pub fn humanize(&mut self, converter: &Converter) -> bool {
+        self.restore_exact();
+        let (steps, fractions) = match self.unit() {
+            Some(_) => {
+                let Some(unit) = self.unit_info(converter) else {
+                    return false;
+                };
+                (
+                    converter.humanize_steps(Some(&unit)),
+                    Some(converter.fractions_config(&unit)),
+                )
+            }
+            None => (
+                converter.humanize_steps(None),
+                converter
+                    .unitless_unit()
+                    .map(|u| converter.fractions_config(&u)),
+            ),
+        };
+        let Some(steps) = steps else {
+            return false;
+        };
+
+        let exact = self.value().clone();
+        match self.value_mut() {
+            Value::Number(n) => round_number(n, steps, fractions),
+            Value::Range { start, end } => {
+                round_number(start, steps, fractions);
+                round_number(end, steps, fractions);
+            }
+            Value::Text(_) => {}
+        }
+        if *self.value() == exact {
+            return false;
+        }
+        self.exact = Some(Box::new(exact));
+        true
+    }
+}
+
+fn round_number(n: &mut Number, steps: &RoundingSteps, fractions: Option<FractionsConfig>) {
+    let value = n.value();
+    let Some(rounded) = steps.round(value) else {
+        return;
+    };
+
+    let fraction = fractions.filter(|cfg| cfg.enabled).and_then(|cfg| {
+        Number::new_approx(rounded, cfg.accuracy, cfg.max_denominator, cfg.max_whole)
+    });
+    *n = match fraction {
+        Some(Number::Fraction {
+            whole, num, den, ..
+        }) => Number::Fraction {
+            whole,
+            num,
+            den,
+            err: 0.0,
+        },
+        _ => Number::Regular(rounded),
+    };
+}
+
+impl Recipe {
+    /// [Humanize](Quantity::humanize) all the quantities of the recipe in place
+    pub fn humanize(&mut self, converter: &Converter) {
+        for igr in &mut self.ingredients {
+            if let Some(q) = &mut igr.quantity {
+                q.humanize(converter);
+            }
+        }
+
+        // cookware can't have units
+
+        for timer in &mut self.timers {
+            if let Some(q) = &mut timer.quantity {
+                q.humanize(converter);
+            }
+        }
+
+        for q in &mut self.inline_quantities {
+            q.humanize(converter);
+        }
+    }
+}
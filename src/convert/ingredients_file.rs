@@ -0,0 +1,122 @@
+//! Ingredient conversions used in [`ConverterBuilder`](super::ConverterBuilder)
+//!
+//! A [`Converter`](super::Converter) can only convert between units of the
+//! same [`PhysicalQuantity`]. With per ingredient data, it can also convert
+//! between volume and mass, and give a mass to units it doesn't know, like
+//! `clove` or `stick`.
+//!
+//! ```toml
+//! [[ingredient]]
+//! names = ["flour", "all-purpose flour"]
+//! density = 0.53
+//! measure = { metric = "mass" }
+//!
+//! [[ingredient]]
+//! names = ["butter"]
+//! density = 0.911
+//! units = { stick = 113 }
+//!
+//! [[ingredient]]
+//! names = ["garlic"]
+//! units = { clove = 5, head = 50 }
+//!
+//! [[ingredient]]
+//! names = ["egg", "eggs"]
+//! piece = 50
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{PhysicalQuantity, System};
+
+/// Configuration struct for ingredient conversions used in
+/// [`ConverterBuilder`](super::ConverterBuilder)
+///
+/// This structure is designed for deserializing [TOML](https://toml.io/en/),
+/// but you can try other formats supported by serde.
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct IngredientsFile {
+    /// Ingredients data
+    #[serde(default)]
+    pub ingredient: Vec<IngredientEntry>,
+}
+
+/// Conversion data of an ingredient
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct IngredientEntry {
+    /// Names of the ingredient
+    ///
+    /// The first one is the main name, the rest are synonyms. Matching is case
+    /// insensitive.
+    pub names: Vec<String>,
+    /// Density in g/ml
+    ///
+    /// Used to convert between volume and mass.
+    #[serde(default)]
+    pub density: Option<f64>,
+    /// Mass in grams of a single piece
+    ///
+    /// Used for quantities without unit, like `@eggs{2}`.
+    #[serde(default)]
+    pub piece: Option<f64>,
+    /// Mass in grams of one of each unit
+    ///
+    /// Mostly for units the converter doesn't know, like `clove`, `stick` or
+    /// `can`. It can also contain known units, like `cup`, and then it takes
+    /// precedence over [`Self::density`] when converting that unit to mass.
+    #[serde(default)]
+    pub units: HashMap<String, f64>,
+    /// Preferred physical quantity to measure the ingredient
+    ///
+    /// When converting to the best unit of a system, the quantity is first
+    /// converted to this physical quantity if possible. For example, measure
+    /// flour by mass in the metric system.
+    #[serde(default)]
+    pub measure: Option<Measure>,
+}
+
+/// Preferred physical quantity of an [`IngredientEntry`]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum Measure {
+    /// Same for all systems
+    Unified(PhysicalQuantity),
+    /// Different for each system
+    BySystem {
+        #[serde(default)]
+        metric: Option<PhysicalQuantity>,
+        #[serde(default)]
+        imperial: Option<PhysicalQuantity>,
+    },
+}
+
+impl Measure {
+    /// Preferred physical quantity for a system
+    pub fn get(&self, system: System) -> Option<PhysicalQuantity> {
+        match *self {
+            Measure::Unified(q) => Some(q),
+            Measure::BySystem { metric, imperial } => match system {
+                System::Metric => metric,
+                System::Imperial => imperial,
+            },
+        }
+    }
+}
+
+impl IngredientEntry {
+    /// Mass in grams of one `unit`, if known
+    ///
+    /// The unit is matched exactly first and then case insensitive.
+    pub fn unit_mass(&self, unit: &str) -> Option<f64> {
+        self.units.get(unit).copied().or_else(|| {
+            self.units
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(unit))
+                .map(|(_, v)| *v)
+        })
+    }
+}
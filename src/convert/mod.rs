@@ -7,19 +7,24 @@
 
 use std::{collections::HashMap, ops::RangeInclusive, sync::Arc};
 
-use enum_map::EnumMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    quantity::{Number, Quantity, Value},
+    quantity::{CompoundQuantity, Number, Quantity, Value},
     Recipe,
 };
 
+use humanize::Humanize;
+use ingredients_file::IngredientEntry;
+
 pub use builder::{ConverterBuilder, ConverterBuilderError};
+pub use ingredients_file::IngredientsFile;
 pub use units_file::UnitsFile;
 
 mod builder;
+mod humanize;
+pub mod ingredients_file;
 pub mod units_file;
 
 /// Main struct to perform conversions
@@ -31,14 +36,23 @@ pub mod units_file;
 /// [`Converter::default`] changes with the feature `bundled_units`:
 /// - When enabled, [`Converter::bundled`].
 /// - When disabled, [`Converter::empty`].
-#[derive(Debug, Clone)]
+///
+/// A built converter can be (de)serialized with serde, so it can be cached
+/// instead of built again. With the `converter_cache` feature, there is also
+/// a compact binary form, see [`Converter::to_bytes`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Converter {
     all_units: Vec<Arc<Unit>>,
     unit_index: UnitIndex,
     quantity_index: UnitQuantityIndex,
-    best: EnumMap<PhysicalQuantity, BestConversionsStore>,
+    best: QuantityMap<BestConversionsStore>,
+    custom_quantities: Vec<Arc<str>>,
     fractions: Fractions,
+    humanize: Humanize,
     default_system: System,
+    unitless: Option<usize>,
+    regions: Vec<Region>,
+    ingredients: IngredientIndex,
 }
 
 impl Converter {
@@ -61,8 +75,13 @@ impl Converter {
             unit_index: Default::default(),
             quantity_index: Default::default(),
             best: Default::default(),
+            custom_quantities: Default::default(),
             default_system: Default::default(),
             fractions: Default::default(),
+            humanize: Default::default(),
+            unitless: None,
+            regions: Default::default(),
+            ingredients: Default::default(),
         }
     }
 
@@ -75,13 +94,133 @@ impl Converter {
     /// This is only available when the `bundled_units` feature is enabled.
     ///
     /// This is the default when the `bundled_units` feature is enabled.
+    ///
+    /// The bundled units file is generated at compile time in `build.rs`, so
+    /// no TOML is parsed at runtime. The converter is then built once per
+    /// program and the following calls just clone it.
+    ///
+    /// The finished converter is not precomputed in `build.rs`: the build
+    /// script can't use the builder of this crate, and building it is faster
+    /// than loading it with `Converter::from_bytes` anyway, so it would
+    /// make cold starts slower.
     #[cfg(feature = "bundled_units")]
     pub fn bundled() -> Self {
-        ConverterBuilder::new()
-            .with_units_file(UnitsFile::bundled())
-            .unwrap()
-            .finish()
-            .unwrap()
+        static BUNDLED: std::sync::LazyLock<Converter> = std::sync::LazyLock::new(|| {
+            ConverterBuilder::new()
+                .with_units_file(UnitsFile::bundled())
+                .unwrap()
+                .finish()
+                .unwrap()
+        });
+        BUNDLED.clone()
+    }
+
+    /// Compact binary form of the converter
+    ///
+    /// Parsing units files and building a converter from them takes time. It
+    /// can be built once, stored with this and loaded with
+    /// [`Converter::from_bytes`] much faster. The bytes can only be loaded by
+    /// the same version of this crate.
+    ///
+    /// This is for converters with custom units files. For
+    /// [`Converter::bundled`] it's slower than building it, see its docs.
+    ///
+    /// This is only available when the `converter_cache` feature is enabled.
+    ///
+    /// ```
+    /// # use cooklang::Converter;
+    /// let converter = Converter::bundled();
+    /// let bytes = converter.to_bytes();
+    /// assert_eq!(Converter::from_bytes(&bytes).unwrap(), converter);
+    /// ```
+    #[cfg(feature = "converter_cache")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        // structs as maps, untagged enums like `Measure` need the field names
+        let mut ser = rmp_serde::Serializer::new(&mut buf).with_struct_map();
+        // writing to a vec can't fail and every part of the converter can be
+        // serialized, so this never panics
+        CACHE_VERSION
+            .serialize(&mut ser)
+            .and_then(|_| self.serialize(&mut ser))
+            .expect("converter serialization");
+        buf
+    }
+
+    /// Load a converter from [`Converter::to_bytes`]
+    ///
+    /// This is only available when the `converter_cache` feature is enabled.
+    #[cfg(feature = "converter_cache")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ConverterCacheError> {
+        let mut de = rmp_serde::Deserializer::new(bytes);
+        let version = String::deserialize(&mut de)?;
+        if version != CACHE_VERSION {
+            return Err(ConverterCacheError::Version(version));
+        }
+        let converter = Self::deserialize(&mut de)?;
+        converter.check().map_err(ConverterCacheError::Invalid)?;
+        Ok(converter)
+    }
+
+    /// Checks that the ids in the converter are valid
+    ///
+    /// The builder always makes valid converters, but not a corrupted cache.
+    #[cfg(feature = "converter_cache")]
+    fn check(&self) -> Result<(), &'static str> {
+        let unit = |id: &usize| *id < self.all_units.len();
+        let best = |b: &BestConversions| b.0.iter().all(|(_, id)| unit(id));
+
+        if !self.unit_index.0.values().all(unit) {
+            return Err("unit index out of range");
+        }
+        let quantities_ok = self.quantity_index.values().flatten().all(unit)
+            && self.best.values().all(|store| match store {
+                BestConversionsStore::Unified(b) => best(b),
+                BestConversionsStore::BySystem { metric, imperial } => {
+                    best(metric) && best(imperial)
+                }
+            });
+        if !quantities_ok {
+            return Err("unit of a quantity out of range");
+        }
+        if !self
+            .physical_quantities()
+            .all(|q| self.quantity_name(q).is_some())
+        {
+            return Err("undeclared custom quantity");
+        }
+        let known_quantity = |q| self.quantity_index.contains(q) && self.best.contains(q);
+        if !self
+            .all_units
+            .iter()
+            .all(|u| known_quantity(u.physical_quantity))
+        {
+            return Err("unit with an unknown quantity");
+        }
+        if !(self.fractions.unit.keys().all(unit)
+            && self.humanize.unit.keys().all(unit)
+            && self.unitless.iter().all(unit))
+        {
+            return Err("unit settings out of range");
+        }
+        let regions_ok = self.regions.iter().all(|r| {
+            r.units
+                .iter()
+                .all(|(base, variant)| unit(base) && unit(variant))
+                && r.best.values().flatten().all(best)
+        });
+        if !regions_ok {
+            return Err("region unit out of range");
+        }
+        let ingredients = &self.ingredients;
+        if !ingredients
+            .index
+            .values()
+            .all(|&id| id < ingredients.entries.len())
+        {
+            return Err("ingredient index out of range");
+        }
+        Ok(())
     }
 
     /// Get the default unit [System]
@@ -93,12 +232,27 @@ impl Converter {
     ///
     /// This is **not** all the known unit names, just **different units**.
     pub fn unit_count(&self) -> usize {
-        self.all_units.len()
+        self.base_units().len()
     }
 
     /// Get an iterator of all the known units.
+    ///
+    /// Regional variants are not included, see [`Region`].
     pub fn all_units(&self) -> impl Iterator<Item = &Unit> {
-        self.all_units.iter().map(|u| u.as_ref())
+        self.base_units().iter().map(|u| u.as_ref())
+    }
+
+    /// Units without the regional variants
+    fn base_units(&self) -> &[Arc<Unit>] {
+        // variants are added after every other unit when building
+        let end = self
+            .regions
+            .iter()
+            .flat_map(|r| r.units.values())
+            .min()
+            .copied()
+            .unwrap_or(self.all_units.len());
+        &self.all_units[..end]
     }
 
     /// Check if a unit is one of the possible conversions in it's units system.
@@ -116,15 +270,57 @@ impl Converter {
         let Some(system) = unit.system else {
             return false;
         };
-        let conversions = self.best[unit.physical_quantity].conversions(system);
-        conversions.0.iter().any(|&(_, id)| id == unit_id)
+        self.best.get(unit.physical_quantity).is_some_and(|best| {
+            best.conversions(system)
+                .0
+                .iter()
+                .any(|&(_, id)| id == unit_id)
+        })
+    }
+
+    /// Get all the physical quantities of the converter
+    ///
+    /// These are the built-in ones and the custom ones declared in the units
+    /// files.
+    pub fn physical_quantities(&self) -> impl Iterator<Item = PhysicalQuantity> + '_ {
+        self.best.iter().map(|(q, _)| q)
+    }
+
+    /// Name of a physical quantity
+    ///
+    /// Returns `None` if it's a custom quantity not declared in this converter.
+    pub fn quantity_name(&self, quantity: PhysicalQuantity) -> Option<&str> {
+        match quantity {
+            PhysicalQuantity::Volume => Some("volume"),
+            PhysicalQuantity::Mass => Some("mass"),
+            PhysicalQuantity::Length => Some("length"),
+            PhysicalQuantity::Temperature => Some("temperature"),
+            PhysicalQuantity::Time => Some("time"),
+            PhysicalQuantity::Custom(c) => self.custom_quantities.get(c.id()).map(|n| n.as_ref()),
+        }
+    }
+
+    /// Find a physical quantity by its name
+    ///
+    /// This also finds the custom quantities declared in this converter.
+    pub fn find_quantity(&self, name: &str) -> Option<PhysicalQuantity> {
+        PhysicalQuantity::builtin(name).or_else(|| {
+            let id = self
+                .custom_quantities
+                .iter()
+                .position(|n| n.as_ref() == name)?;
+            CustomQuantity::new(id).map(PhysicalQuantity::Custom)
+        })
     }
 
     /// Get the (marked) best units for a quantity and a system.
     ///
     /// If system is None, returns for all the systems.
     pub fn best_units(&self, quantity: PhysicalQuantity, system: Option<System>) -> Vec<Arc<Unit>> {
-        match &self.best[quantity] {
+        let Some(best) = self.best.get(quantity) else {
+            return Vec::new();
+        };
+        match best {
             BestConversionsStore::Unified(u) => u.all_units(self).cloned().collect(),
             BestConversionsStore::BySystem { metric, imperial } => match system {
                 Some(System::Metric) => metric.all_units(self).cloned().collect(),
@@ -144,6 +340,69 @@ impl Converter {
         Some(self.all_units[uid].clone())
     }
 
+    /// Find a known unit key similar to `unit`, for "did you mean" hints
+    ///
+    /// Useful when [`Converter::find_unit`] fails because of a typo. The
+    /// result can be any name, symbol or alias of a unit.
+    ///
+    /// ```
+    /// # use cooklang::Converter;
+    /// let converter = Converter::bundled();
+    /// assert!(converter.find_unit("teaspon").is_none());
+    /// assert_eq!(converter.suggest_unit("teaspon"), Some("teaspoon"));
+    /// ```
+    pub fn suggest_unit(&self, unit: &str) -> Option<&str> {
+        crate::suggest::closest(unit, self.unit_index.0.keys().map(|k| k.as_ref()))
+    }
+
+    /// Find a unit as written in a region
+    ///
+    /// Like [`Converter::find_unit`], but if the region has a variant of the
+    /// unit, the variant is returned.
+    pub fn find_unit_in(&self, unit: &str, region: Option<&Region>) -> Option<Arc<Unit>> {
+        let mut uid = self.unit_index.get_unit_id(unit).ok()?;
+        if let Some(variant) = region.and_then(|r| r.units.get(&uid)) {
+            uid = *variant;
+        }
+        Some(self.all_units[uid].clone())
+    }
+
+    /// Get all the regions
+    pub fn regions(&self) -> impl Iterator<Item = &Region> {
+        self.regions.iter()
+    }
+
+    /// Find a region by name
+    ///
+    /// Matching is case insensitive.
+    pub fn region(&self, name: &str) -> Option<&Region> {
+        self.regions
+            .iter()
+            .find(|r| r.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Find the region of a locale
+    ///
+    /// The locale is in the format of [`Metadata::locale`](crate::Metadata::locale).
+    /// A region with the full locale is preferred over one with only the
+    /// language.
+    pub fn region_for_locale(&self, language: &str, dialect: Option<&str>) -> Option<&Region> {
+        let full = dialect.map(|d| format!("{language}_{d}"));
+        let find = |locale: &str| {
+            self.regions
+                .iter()
+                .find(|r| r.locales.iter().any(|l| l.eq_ignore_ascii_case(locale)))
+        };
+        full.as_deref().and_then(find).or_else(|| find(language))
+    }
+
+    /// Unit of quantities without unit, if any
+    ///
+    /// See [`UnitsFile::unitless`].
+    pub fn unitless_unit(&self) -> Option<Arc<Unit>> {
+        self.unitless.map(|id| Arc::clone(&self.all_units[id]))
+    }
+
     /// Gets the fractions configuration for the given unit
     ///
     /// # Panics
@@ -165,6 +424,13 @@ impl Converter {
     pub(crate) fn should_fit_fraction(&self, unit: &Unit) -> bool {
         self.fractions_config(unit).enabled
     }
+
+    /// Find the conversion data of an ingredient by any of its names
+    ///
+    /// Matching is case insensitive.
+    pub fn ingredient_conversions(&self, ingredient: &str) -> Option<&IngredientEntry> {
+        self.ingredients.get(ingredient)
+    }
 }
 
 #[cfg(not(feature = "bundled_units"))]
@@ -187,13 +453,18 @@ impl PartialEq for Converter {
             && self.unit_index == other.unit_index
             && self.quantity_index == other.quantity_index
             && self.best == other.best
+            && self.custom_quantities == other.custom_quantities
+            && self.humanize == other.humanize
             && self.default_system == other.default_system
+            && self.unitless == other.unitless
+            && self.regions == other.regions
+            && self.ingredients == other.ingredients
         // temperature_regex ignored, it should be the same if the rest is the
         // the same
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct Fractions {
     all: Option<FractionsConfig>,
     metric: Option<FractionsConfig>,
@@ -224,7 +495,7 @@ impl Fractions {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub(crate) struct FractionsConfig {
     pub enabled: bool,
     pub accuracy: f32,
@@ -243,7 +514,7 @@ impl Default for FractionsConfig {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct UnitIndex(HashMap<Arc<str>, usize>);
 
 impl UnitIndex {
@@ -255,15 +526,78 @@ impl UnitIndex {
     }
 }
 
-pub(crate) type UnitQuantityIndex = EnumMap<PhysicalQuantity, Vec<usize>>;
+pub(crate) type UnitQuantityIndex = QuantityMap<Vec<usize>>;
+
+/// Ingredient conversions by lowercase name
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct IngredientIndex {
+    entries: Vec<IngredientEntry>,
+    index: HashMap<String, usize>,
+}
+
+impl IngredientIndex {
+    fn get(&self, ingredient: &str) -> Option<&IngredientEntry> {
+        self.index
+            .get(&ingredient.trim().to_lowercase())
+            .map(|&id| &self.entries[id])
+    }
+}
+
+/// A regional variant of a [`System`]
+///
+/// See [`RegionEntry`](units_file::RegionEntry).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    name: String,
+    system: System,
+    locales: Vec<String>,
+    /// base unit id -> regional variant id
+    units: HashMap<usize, usize>,
+    /// only the quantities with different best units than the system
+    best: QuantityMap<Option<BestConversions>>,
+}
+
+impl Region {
+    /// Name of the region
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// System the region is based on
+    pub fn system(&self) -> System {
+        self.system
+    }
+
+    /// Locales of the region
+    pub fn locales(&self) -> &[String] {
+        &self.locales
+    }
+}
+
+/// Where to look for the best units
+#[derive(Debug, Clone, Copy)]
+enum BestTarget<'a> {
+    System(System),
+    Region(&'a Region),
+}
+
+impl BestTarget<'_> {
+    fn system(&self) -> System {
+        match self {
+            BestTarget::System(s) => *s,
+            BestTarget::Region(r) => r.system,
+        }
+    }
+}
 
 /// A unit
 ///
-/// Conversion will be `val * [Self::ratio] + [Self::difference]`
+/// Conversion will be `val * [Self::ratio] + [Self::difference]`, after
+/// interpolating in [`Self::table`] if it's not empty.
 ///
 /// It implements [Display](std::fmt::Display). It will use [`Self::symbol`] or,
 /// if alternate (`#`) is given, it will try the first name.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Unit {
     /// All the names that may be used to format the unit
     pub names: Vec<Arc<str>>,
@@ -275,6 +609,18 @@ pub struct Unit {
     pub ratio: f64,
     /// Difference offset to the conversion ratio
     pub difference: f64,
+    /// Piecewise linear conversion table, see [`UnitEntry::table`]
+    ///
+    /// [`UnitEntry::table`]: units_file::UnitEntry::table
+    pub table: Vec<(f64, f64)>,
+    /// Named values, see [`UnitEntry::presets`]
+    ///
+    /// [`UnitEntry::presets`]: units_file::UnitEntry::presets
+    pub presets: HashMap<Arc<str>, f64>,
+    /// Can be written before the number, see [`UnitEntry::before_value`]
+    ///
+    /// [`UnitEntry::before_value`]: units_file::UnitEntry::before_value
+    pub before_value: bool,
     /// The [`PhysicalQuantity`] this unit belongs to
     pub physical_quantity: PhysicalQuantity,
     /// The unit [System] this unit belongs to, if any
@@ -298,6 +644,59 @@ impl Unit {
             .or_else(|| self.aliases.first())
             .expect("symbol, name or alias in unit")
     }
+
+    /// Get the value of a preset by name, ignoring ASCII case
+    pub fn preset(&self, name: &str) -> Option<f64> {
+        self.presets
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, &v)| v)
+    }
+
+    /// Value to the common scale of the physical quantity
+    fn normalize(&self, value: f64) -> f64 {
+        let value = interpolate(&self.table, value, false);
+        (value + self.difference) * self.ratio
+    }
+
+    /// Value from the common scale of the physical quantity
+    fn denormalize(&self, norm: f64) -> f64 {
+        let value = (norm / self.ratio) - self.difference;
+        interpolate(&self.table, value, true)
+    }
+}
+
+/// Maps a value with a conversion table, from the left column to the right
+/// one or the other way when `inverse`
+///
+/// Values outside the table are clamped to the first or last point, a gas
+/// mark can't go below the lowest one. The table has at least 2 points or is
+/// empty, then the value is not changed.
+fn interpolate(table: &[(f64, f64)], value: f64, inverse: bool) -> f64 {
+    if table.len() < 2 {
+        return value;
+    }
+    let point = |i: usize| {
+        let (a, b) = table[i];
+        if inverse {
+            (b, a)
+        } else {
+            (a, b)
+        }
+    };
+    let (first, last) = (point(0), point(table.len() - 1));
+    if value <= first.0 {
+        return first.1;
+    }
+    if value >= last.0 {
+        return last.1;
+    }
+    let i = (1..table.len() - 1)
+        .find(|&i| value < point(i).0)
+        .unwrap_or(table.len() - 1);
+    let (x0, y0) = point(i - 1);
+    let (x1, y1) = point(i);
+    y0 + (value - x0) * (y1 - y0) / (x1 - x0)
 }
 
 impl PartialEq for Unit {
@@ -307,6 +706,9 @@ impl PartialEq for Unit {
             && self.aliases == other.aliases
             && self.ratio == other.ratio
             && self.difference == other.difference
+            && self.table == other.table
+            && self.presets == other.presets
+            && self.before_value == other.before_value
             && self.physical_quantity == other.physical_quantity
             && self.system == other.system
         // expand_si and expanded_units ignored
@@ -323,7 +725,7 @@ impl std::fmt::Display for Unit {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 enum BestConversionsStore {
     Unified(BestConversions),
     BySystem {
@@ -350,7 +752,7 @@ impl Default for BestConversionsStore {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 struct BestConversions(Vec<(f64, usize)>);
 
 impl BestConversions {
@@ -387,6 +789,14 @@ impl BestConversions {
     }
 }
 
+/// Physical quantity of a [`Unit`]
+///
+/// Besides the built-in ones, a [`UnitsFile`] can declare new quantities, like
+/// `energy` or `count`, in [`UnitsFile::custom_quantities`]. These are the
+/// [`PhysicalQuantity::Custom`] variant.
+///
+/// Only the built-in quantities are parsed from their names. The names of the
+/// custom ones are in the [`Converter`], see [`Converter::find_quantity`].
 #[derive(
     Debug,
     Clone,
@@ -410,6 +820,233 @@ pub enum PhysicalQuantity {
     Length,
     Temperature,
     Time,
+    /// A quantity declared in a [`UnitsFile`]
+    ///
+    /// The name is in the converter that declared it, see
+    /// [`Converter::quantity_name`].
+    #[serde(untagged)]
+    #[strum(to_string = "custom#{0}")]
+    Custom(CustomQuantity),
+}
+
+impl PhysicalQuantity {
+    /// All the built-in quantities
+    pub(crate) const BUILTIN: [PhysicalQuantity; 5] = [
+        PhysicalQuantity::Volume,
+        PhysicalQuantity::Mass,
+        PhysicalQuantity::Length,
+        PhysicalQuantity::Temperature,
+        PhysicalQuantity::Time,
+    ];
+
+    /// Check if the quantity is not a built-in one
+    pub fn is_custom(&self) -> bool {
+        matches!(self, PhysicalQuantity::Custom(_))
+    }
+
+    /// Finds a built-in quantity by its name
+    ///
+    /// Unlike [`str::parse`], this never gives a custom quantity.
+    pub(crate) fn builtin(name: &str) -> Option<Self> {
+        Self::BUILTIN.into_iter().find(|q| q.to_string() == name)
+    }
+
+    fn builtin_index(&self) -> Option<usize> {
+        match self {
+            PhysicalQuantity::Volume => Some(0),
+            PhysicalQuantity::Mass => Some(1),
+            PhysicalQuantity::Length => Some(2),
+            PhysicalQuantity::Temperature => Some(3),
+            PhysicalQuantity::Time => Some(4),
+            PhysicalQuantity::Custom(_) => None,
+        }
+    }
+}
+
+/// Id of a custom [`PhysicalQuantity`] in a [`Converter`]
+///
+/// Ids are given in the order the quantities are declared, so they are only
+/// meaningful in the converter that declared them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, enum_map::Enum)]
+pub struct CustomQuantity(u8);
+
+impl CustomQuantity {
+    pub(crate) fn new(id: usize) -> Option<Self> {
+        u8::try_from(id).ok().map(Self)
+    }
+
+    pub(crate) fn id(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl std::fmt::Display for CustomQuantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for CustomQuantity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomQuantity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct IdVisitor;
+
+        impl serde::de::Visitor<'_> for IdVisitor {
+            type Value = CustomQuantity;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a custom quantity id")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                u8::try_from(v)
+                    .map(CustomQuantity)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+            }
+
+            // map keys in text formats
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse()
+                    .map(CustomQuantity)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_any(IdVisitor)
+    }
+}
+
+/// Map from every [`PhysicalQuantity`] to a value
+///
+/// Built-in quantities are always present and are a direct index. Custom
+/// quantities are only present after [`QuantityMap::entry`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct QuantityMap<T> {
+    builtin: [T; 5],
+    custom: Vec<(CustomQuantity, T)>,
+}
+
+impl<T: Default> Default for QuantityMap<T> {
+    fn default() -> Self {
+        Self {
+            builtin: Default::default(),
+            custom: Vec::new(),
+        }
+    }
+}
+
+impl<T> QuantityMap<T> {
+    pub fn get(&self, quantity: PhysicalQuantity) -> Option<&T> {
+        match quantity {
+            PhysicalQuantity::Custom(c) => self.custom.iter().find(|e| e.0 == c).map(|e| &e.1),
+            q => Some(&self.builtin[q.builtin_index().unwrap()]),
+        }
+    }
+
+    pub fn get_mut(&mut self, quantity: PhysicalQuantity) -> Option<&mut T> {
+        match quantity {
+            PhysicalQuantity::Custom(c) => {
+                self.custom.iter_mut().find(|e| e.0 == c).map(|e| &mut e.1)
+            }
+            q => Some(&mut self.builtin[q.builtin_index().unwrap()]),
+        }
+    }
+
+    pub fn contains(&self, quantity: PhysicalQuantity) -> bool {
+        self.get(quantity).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PhysicalQuantity, &T)> {
+        PhysicalQuantity::BUILTIN
+            .into_iter()
+            .zip(&self.builtin)
+            .chain(
+                self.custom
+                    .iter()
+                    .map(|(c, v)| (PhysicalQuantity::Custom(*c), v)),
+            )
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.builtin.iter().chain(self.custom.iter().map(|e| &e.1))
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.builtin
+            .iter_mut()
+            .chain(self.custom.iter_mut().map(|e| &mut e.1))
+    }
+
+    pub fn into_values(self) -> impl Iterator<Item = T> {
+        self.builtin
+            .into_iter()
+            .chain(self.custom.into_iter().map(|e| e.1))
+    }
+
+    pub fn try_map<U, E>(
+        self,
+        mut f: impl FnMut(PhysicalQuantity, T) -> Result<U, E>,
+    ) -> Result<QuantityMap<U>, E> {
+        let mut builtin = Vec::with_capacity(5);
+        for (q, v) in PhysicalQuantity::BUILTIN.into_iter().zip(self.builtin) {
+            builtin.push(f(q, v)?);
+        }
+        let Ok(builtin) = builtin.try_into() else {
+            unreachable!()
+        };
+        let custom = self
+            .custom
+            .into_iter()
+            .map(|(c, v)| Ok((c, f(PhysicalQuantity::Custom(c), v)?)))
+            .collect::<Result<_, E>>()?;
+        Ok(QuantityMap { builtin, custom })
+    }
+}
+
+impl<T: Default> QuantityMap<T> {
+    /// Get the value of a quantity, inserting the default if not present
+    pub fn entry(&mut self, quantity: PhysicalQuantity) -> &mut T {
+        if let PhysicalQuantity::Custom(c) = quantity {
+            if !self.contains(quantity) {
+                self.custom.push((c, T::default()));
+            }
+        }
+        self.get_mut(quantity).unwrap()
+    }
+}
+
+impl<T: Serialize> Serialize for QuantityMap<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Default> Deserialize<'de> for QuantityMap<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let entries = indexmap::IndexMap::<PhysicalQuantity, T>::deserialize(deserializer)?;
+        let mut map = Self::default();
+        for (q, v) in entries {
+            *map.entry(q) = v;
+        }
+        Ok(map)
+    }
 }
 
 impl Recipe {
@@ -419,20 +1056,43 @@ impl Recipe {
     ///
     /// Returns all the errors while converting. These usually are missing units,
     /// unknown units or text values.
+    ///
+    /// If the recipe [`locale`](crate::Metadata::locale) belongs to a
+    /// [region](Converter::region_for_locale), the units are read as the
+    /// regional variants.
     pub fn convert(&mut self, to: System, converter: &Converter) -> Vec<ConvertError> {
+        self.convert_impl(ConvertTo::from(to), converter)
+    }
+
+    /// Convert a recipe to the best units of a [`Region`] in place.
+    ///
+    /// Like [`Recipe::convert`], but the units will be the regional variants.
+    /// If the region is unknown, the only error is
+    /// [`ConvertError::UnknownRegion`] and nothing is converted.
+    pub fn convert_to_region(&mut self, region: &str, converter: &Converter) -> Vec<ConvertError> {
+        if converter.region(region).is_none() {
+            return vec![ConvertError::UnknownRegion(region.to_string())];
+        }
+        self.convert_impl(ConvertTo::Region(region), converter)
+    }
+
+    fn convert_impl(&mut self, to: ConvertTo, converter: &Converter) -> Vec<ConvertError> {
         let mut errors = Vec::new();
 
-        let to = ConvertTo::from(to);
+        let from = self
+            .metadata
+            .locale()
+            .and_then(|(lang, dialect)| converter.region_for_locale(lang, dialect));
 
-        let mut conv = |q: &mut Quantity| {
-            if let Err(e) = q.convert(to, converter) {
+        let mut conv = |q: &mut Quantity, ingredient: Option<&str>| {
+            if let Err(e) = q.convert_impl(to, ingredient, from, converter) {
                 errors.push(e)
             }
         };
 
         for igr in &mut self.ingredients {
             if let Some(q) = &mut igr.quantity {
-                conv(q);
+                conv(q, Some(&igr.name));
             }
         }
 
@@ -440,12 +1100,12 @@ impl Recipe {
 
         for timer in &mut self.timers {
             if let Some(q) = &mut timer.quantity {
-                conv(q);
+                conv(q, None);
             }
         }
 
         for q in &mut self.inline_quantities {
-            conv(q);
+            conv(q, None);
         }
 
         errors
@@ -453,56 +1113,97 @@ impl Recipe {
 }
 
 impl Quantity {
+    /// Converts the quantity
+    ///
+    /// With the name of the `ingredient`, the ingredient conversions of the
+    /// converter are also used. See [`Converter::ingredient_conversions`].
     pub fn convert<'a>(
         &mut self,
         to: impl Into<ConvertTo<'a>>,
+        ingredient: Option<&str>,
         converter: &Converter,
     ) -> Result<(), ConvertError> {
-        self.convert_impl(to.into(), converter)
+        self.convert_impl(to.into(), ingredient, None, converter)
     }
 
     #[tracing::instrument(level = "trace", name = "convert", skip_all)]
-    fn convert_impl(&mut self, to: ConvertTo, converter: &Converter) -> Result<(), ConvertError> {
-        if self.unit().is_none() {
-            return Err(ConvertError::NoUnit(self.clone()));
+    fn convert_impl(
+        &mut self,
+        to: ConvertTo,
+        ingredient: Option<&str>,
+        from: Option<&Region>,
+        converter: &Converter,
+    ) -> Result<(), ConvertError> {
+        let unit_info = self.unit().and_then(|u| converter.find_unit_in(u, from));
+        // presets, like `medium heat`, are kept as written unless a unit is asked
+        if !matches!(to, ConvertTo::Unit(_)) && self.is_preset(unit_info.as_deref()) {
+            return Ok(());
         }
-
-        let unit_info = self.unit_info(converter);
-        let original_system;
-        let unit = match unit_info {
-            Some(ref u) => {
-                original_system = u.system;
-                ConvertUnit::Unit(u)
-            }
-            None => {
-                return Err(ConvertError::UnknownUnit(UnknownUnit(
-                    self.unit().unwrap().to_string(),
-                )))
+        let (new_value, new_unit, original_system) = if let Some(ingredient) = ingredient {
+            let value = ConvertValue::resolve(self.exact_value(), unit_info.as_deref())?;
+            let original_system = unit_info.as_ref().and_then(|u| u.system);
+            let (new_value, new_unit) =
+                converter.convert_ingredient(ingredient, value, self.unit(), from, to)?;
+            (new_value, new_unit, original_system)
+        } else {
+            if self.unit().is_none() {
+                return Err(ConvertError::NoUnit(self.clone()));
             }
-        };
-        let value = ConvertValue::try_from(self.value())?;
 
-        let (new_value, new_unit) = converter.convert(value, unit, to)?;
+            let original_system;
+            let unit = match unit_info {
+                Some(ref u) => {
+                    original_system = u.system;
+                    ConvertUnit::Unit(u)
+                }
+                None => {
+                    return Err(ConvertError::UnknownUnit(UnknownUnit(
+                        self.unit().unwrap().to_string(),
+                    )))
+                }
+            };
+            let value = ConvertValue::resolve(self.exact_value(), unit_info.as_deref())?;
+
+            let (new_value, new_unit) = converter.convert(value, unit, to)?;
+            (new_value, new_unit, original_system)
+        };
         *self = Quantity::new(new_value.into(), Some(new_unit.symbol().to_string()));
         match to {
             ConvertTo::Unit(_) => {
                 self.try_fraction(converter);
             }
             ConvertTo::Best(target_system) => {
-                self.fit_fraction(&new_unit, Some(target_system), converter)?;
+                self.fit_fraction(
+                    &new_unit,
+                    Some(BestTarget::System(target_system)),
+                    converter,
+                )?;
+            }
+            ConvertTo::Region(region) => {
+                let target = converter.region(region).map(BestTarget::Region);
+                self.fit_fraction(&new_unit, target, converter)?;
             }
             ConvertTo::SameSystem => {
-                self.fit_fraction(&new_unit, original_system, converter)?;
+                let target = original_system.map(BestTarget::System);
+                self.fit_fraction(&new_unit, target, converter)?;
             }
         }
         Ok(())
     }
 
+    fn is_preset(&self, unit: Option<&Unit>) -> bool {
+        match (self.value(), unit) {
+            (Value::Text(t), Some(unit)) => unit.preset(t).is_some(),
+            _ => false,
+        }
+    }
+
     /// Converts the unit to the best possible match in the same unit system.
     ///
     /// For example, `1000 ml` would be converted to `1 l`.
     #[tracing::instrument(level = "trace", skip_all)]
     pub fn fit(&mut self, converter: &Converter) -> Result<(), ConvertError> {
+        self.restore_exact();
         // only known units can be fitted
         let Some(unit) = self.unit_info(converter) else {
             return Ok(());
@@ -510,13 +1211,13 @@ impl Quantity {
 
         // If configured, try fitting as a fraction
         if converter.should_fit_fraction(&unit)
-            && self.fit_fraction(&unit, unit.system, converter)?
+            && self.fit_fraction(&unit, unit.system.map(BestTarget::System), converter)?
         {
             return Ok(());
         }
 
         // convert to the best in the same system
-        self.convert(ConvertTo::SameSystem, converter)?;
+        self.convert(ConvertTo::SameSystem, None, converter)?;
 
         Ok(())
     }
@@ -531,14 +1232,14 @@ impl Quantity {
     fn fit_fraction(
         &mut self,
         unit: &Arc<Unit>,
-        target_system: Option<System>,
+        target: Option<BestTarget>,
         converter: &Converter,
     ) -> Result<bool, ConvertError> {
         let approx = |val: f64, cfg: FractionsConfig| {
             Number::new_approx(val, cfg.accuracy, cfg.max_denominator, cfg.max_whole)
         };
 
-        let Some(system) = target_system else {
+        let Some(target) = target else {
             return Ok(self.try_fraction(converter)); // no system, just keep the same unit
         };
 
@@ -548,24 +1249,23 @@ impl Quantity {
             Value::Text(ref t) => return Err(ConvertError::TextValue(t.clone())),
         };
 
-        let possible_conversions = converter.best[unit.physical_quantity]
-            .conversions(system)
-            .0
-            .iter()
-            .filter_map(|&(_, new_unit_id)| {
-                let new_unit = &converter.all_units[new_unit_id];
-                let cfg = converter.fractions.config(
-                    new_unit.system,
-                    new_unit.physical_quantity,
-                    new_unit_id,
-                );
-                if !cfg.enabled {
-                    return None;
-                }
-                let new_value = converter.convert_f64(value, unit, new_unit);
-                let new_value = approx(new_value, cfg)?;
-                Some((new_value, new_unit))
-            });
+        let Some(best) = converter.best_conversions(unit.physical_quantity, target) else {
+            return Ok(false);
+        };
+        let possible_conversions = best.0.iter().filter_map(|&(_, new_unit_id)| {
+            let new_unit = &converter.all_units[new_unit_id];
+            let cfg = converter.fractions.config(
+                new_unit.system,
+                new_unit.physical_quantity,
+                new_unit_id,
+            );
+            if !cfg.enabled {
+                return None;
+            }
+            let new_value = converter.convert_f64(value, unit, new_unit);
+            let new_value = approx(new_value, cfg)?;
+            Some((new_value, new_unit))
+        });
 
         let selected = possible_conversions.min_by(|(a, _), (b, _)| {
             let key = |v| match v {
@@ -600,11 +1300,70 @@ impl Quantity {
         Ok(true)
     }
 
+    /// Splits the quantity in many units of the same system
+    ///
+    /// The units are the best units of the system, and all the parts but
+    /// the last one are whole numbers. For example, `1.5 h` is `1 h 30 min`
+    /// and `1.25 lb` is `1 lb 4 oz`. The last part is a fraction if they are
+    /// enabled for its unit.
+    ///
+    /// Ranges and temperatures can't be compounds.
+    pub fn to_compound(&self, converter: &Converter) -> Result<CompoundQuantity, ConvertError> {
+        let Some(unit_text) = self.unit() else {
+            return Err(ConvertError::NoUnit(self.clone()));
+        };
+        let unit = converter
+            .find_unit(unit_text)
+            .ok_or_else(|| UnknownUnit(unit_text.to_string()))?;
+        let value = match self.exact_value() {
+            Value::Number(n) => n.value(),
+            Value::Range { .. } => return Err(ConvertError::InvalidCompound("value is a range")),
+            Value::Text(t) => return Err(ConvertError::TextValue(t.clone())),
+        };
+        if unit.physical_quantity == PhysicalQuantity::Temperature {
+            return Err(ConvertError::InvalidCompound("temperatures can't be added"));
+        }
+
+        let system = unit.system.unwrap_or(converter.default_system);
+        let best = converter
+            .best_conversions(unit.physical_quantity, BestTarget::System(system))
+            .filter(|best| !best.0.is_empty())
+            .ok_or(ConvertError::BestUnitNotFound {
+                physical_quantity: unit.physical_quantity,
+                system: Some(system),
+            })?;
+
+        // all in the smallest unit
+        let smallest = &converter.all_units[best.base().unwrap()];
+        let total = converter.convert_f64(value, &unit, smallest);
+        let mut rest = total;
+        let mut parts = Vec::new();
+        for &(_, id) in best.0.iter().skip(1).rev() {
+            let part_unit = &converter.all_units[id];
+            let factor = converter.convert_f64(1.0, part_unit, smallest);
+            let n = (rest / factor + 1e-9).floor();
+            if n >= 1.0 {
+                parts.push(Quantity::new(
+                    n.into(),
+                    Some(part_unit.symbol().to_string()),
+                ));
+                rest = (rest - n * factor).max(0.0);
+            }
+        }
+        if parts.is_empty() || rest > total.abs() * 1e-9 {
+            let mut last = Quantity::new(rest.into(), Some(smallest.symbol().to_string()));
+            last.try_fraction(converter);
+            parts.push(last);
+        }
+        Ok(CompoundQuantity::new(parts))
+    }
+
     /// Tries to convert the value to a fraction, keeping the same unit
     ///
     /// It respects the converter configuration for the unit.
     #[tracing::instrument(level = "trace", skip_all)]
     pub fn try_fraction(&mut self, converter: &Converter) -> bool {
+        self.restore_exact();
         // only known units can be fitted
         let Some(unit) = self.unit_info(converter) else {
             return false;
@@ -642,14 +1401,162 @@ impl Converter {
                 let val = self.convert_to_unit(value, unit, to.as_ref())?;
                 (val, Arc::clone(to))
             }
-            ConvertTo::Best(system) => self.convert_to_best(value, unit, system)?,
+            ConvertTo::Best(system) => {
+                self.convert_to_best(value, unit, BestTarget::System(system))?
+            }
+            ConvertTo::Region(name) => {
+                let region = self
+                    .region(name)
+                    .ok_or_else(|| ConvertError::UnknownRegion(name.to_string()))?;
+                self.convert_to_best(value, unit, BestTarget::Region(region))?
+            }
             ConvertTo::SameSystem => {
-                self.convert_to_best(value, unit, unit.system.unwrap_or(self.default_system))?
+                let system = unit.system.unwrap_or(self.default_system);
+                self.convert_to_best(value, unit, BestTarget::System(system))?
             }
         };
         Ok((value, unit))
     }
 
+    /// Perform a conversion of an ingredient quantity
+    ///
+    /// Like [`Converter::convert`], but using the
+    /// [ingredient conversions](Converter::ingredient_conversions) to:
+    /// - Convert between volume and mass.
+    /// - Give a mass to units the converter doesn't know, like `clove`, and to
+    ///   quantities without unit.
+    /// - Prefer the [`measure`](IngredientEntry::measure) of the ingredient
+    ///   when converting to the best unit.
+    ///
+    /// Densities are in g/ml, so the converter needs to know the `g` and `ml`
+    /// units to use them.
+    ///
+    /// If the ingredient has no conversion data, this is the same as
+    /// [`Converter::convert`].
+    fn convert_ingredient(
+        &self,
+        ingredient: &str,
+        value: ConvertValue,
+        unit: Option<&str>,
+        from: Option<&Region>,
+        to: ConvertTo,
+    ) -> Result<(ConvertValue, Arc<Unit>), ConvertError> {
+        let entry = self.ingredient_conversions(ingredient);
+        let known = unit.and_then(|u| self.find_unit_in(u, from));
+        let target_unit = match &to {
+            ConvertTo::Unit(u) => Some(Arc::clone(self.get_unit(u)?)),
+            _ => None,
+        };
+
+        let target = match to {
+            ConvertTo::Unit(_) => target_unit.as_ref().map(|u| u.physical_quantity),
+            ConvertTo::Best(system) => entry.and_then(|e| e.measure).and_then(|m| m.get(system)),
+            ConvertTo::Region(name) => {
+                let region = self
+                    .region(name)
+                    .ok_or_else(|| ConvertError::UnknownRegion(name.to_string()))?;
+                entry
+                    .and_then(|e| e.measure)
+                    .and_then(|m| m.get(region.system))
+            }
+            ConvertTo::SameSystem => {
+                let system = known
+                    .as_ref()
+                    .and_then(|u| u.system)
+                    .unwrap_or(self.default_system);
+                entry.and_then(|e| e.measure).and_then(|m| m.get(system))
+            }
+        };
+
+        let (value, from) = match (known, entry, target) {
+            (Some(unit), Some(entry), Some(target)) if unit.physical_quantity != target => {
+                self.convert_physical_quantity(entry, value, &unit, target, target_unit.as_ref())?
+            }
+            (Some(unit), _, _) => (value, unit),
+            (None, Some(entry), Some(target)) => {
+                let grams = match unit {
+                    Some(u) => entry
+                        .unit_mass(u)
+                        .ok_or_else(|| UnknownUnit(u.to_string()))?,
+                    None => entry.piece.ok_or_else(|| {
+                        ConvertError::NoUnit(Quantity::new(value.clone().into(), None))
+                    })?,
+                };
+                let g = self.find_unit("g").ok_or_else(|| UnknownUnit("g".into()))?;
+                let value = value.map(|v| v * grams);
+                if target == g.physical_quantity {
+                    (value, g)
+                } else {
+                    self.convert_physical_quantity(entry, value, &g, target, target_unit.as_ref())?
+                }
+            }
+            (None, _, _) => {
+                return Err(match unit {
+                    Some(u) => UnknownUnit(u.to_string()).into(),
+                    None => ConvertError::NoUnit(Quantity::new(value.into(), None)),
+                })
+            }
+        };
+
+        self.convert(value, ConvertUnit::Unit(&from), to)
+    }
+
+    /// Converts between volume and mass with the ingredient data
+    ///
+    /// The mass of a volume unit in [`IngredientEntry::units`] takes
+    /// precedence over the density both ways. From mass, the `target_unit` is
+    /// preferred if it has one, then the first unit by name.
+    fn convert_physical_quantity(
+        &self,
+        entry: &IngredientEntry,
+        value: ConvertValue,
+        unit: &Unit,
+        target: PhysicalQuantity,
+        target_unit: Option<&Arc<Unit>>,
+    ) -> Result<(ConvertValue, Arc<Unit>), ConvertError> {
+        let mixed = ConvertError::MixedQuantities {
+            from: unit.physical_quantity,
+            to: target,
+        };
+        let find = |key: &str| self.find_unit(key).ok_or_else(|| UnknownUnit(key.into()));
+        let unit_mass = |u: &Unit| u.all_keys().find_map(|k| entry.unit_mass(k));
+        match (unit.physical_quantity, target) {
+            (PhysicalQuantity::Volume, PhysicalQuantity::Mass) => {
+                let g = find("g")?;
+                if let Some(grams) = unit_mass(unit) {
+                    return Ok((value.map(|v| v * grams), g));
+                }
+                let density = entry.density.ok_or(mixed)?;
+                let ml = find("ml")?;
+                let value = self.convert_value(value, unit, &ml);
+                Ok((value.map(|v| v * density), g))
+            }
+            (PhysicalQuantity::Mass, PhysicalQuantity::Volume) => {
+                let g = find("g")?;
+                let value = self.convert_value(value, unit, &g);
+                let mut keys = entry.units.keys().collect::<Vec<_>>();
+                keys.sort();
+                let by_unit = target_unit
+                    .and_then(|u| Some((Arc::clone(u), unit_mass(u)?)))
+                    .or_else(|| {
+                        keys.into_iter().find_map(|k| {
+                            let u = self
+                                .find_unit(k)
+                                .filter(|u| u.physical_quantity == PhysicalQuantity::Volume)?;
+                            Some((u, entry.units[k]))
+                        })
+                    });
+                if let Some((volume_unit, grams)) = by_unit {
+                    return Ok((value.map(|v| v / grams), volume_unit));
+                }
+                let density = entry.density.ok_or(mixed)?;
+                let ml = find("ml")?;
+                Ok((value.map(|v| v / density), ml))
+            }
+            _ => Err(mixed),
+        }
+    }
+
     fn convert_to_unit(
         &self,
         value: ConvertValue,
@@ -669,21 +1576,37 @@ impl Converter {
         &self,
         value: ConvertValue,
         unit: &Unit,
-        system: System,
+        target: BestTarget,
     ) -> Result<(ConvertValue, Arc<Unit>), ConvertError> {
-        let conversions = self.best[unit.physical_quantity].conversions(system);
-
-        let best_unit = conversions.best_unit(self, &value, unit).ok_or({
-            ConvertError::BestUnitNotFound {
-                physical_quantity: unit.physical_quantity,
-                system: unit.system,
-            }
-        })?;
+        let best_unit = self
+            .best_conversions(unit.physical_quantity, target)
+            .and_then(|best| best.best_unit(self, &value, unit))
+            .ok_or({
+                ConvertError::BestUnitNotFound {
+                    physical_quantity: unit.physical_quantity,
+                    system: unit.system,
+                }
+            })?;
         let converted = self.convert_value(value, unit, best_unit.as_ref());
 
         Ok((converted, best_unit))
     }
 
+    fn best_conversions<'a>(
+        &'a self,
+        quantity: PhysicalQuantity,
+        target: BestTarget<'a>,
+    ) -> Option<&'a BestConversions> {
+        if let BestTarget::Region(region) = target {
+            if let Some(Some(best)) = region.best.get(quantity) {
+                return Some(best);
+            }
+        }
+        self.best
+            .get(quantity)
+            .map(|best| best.conversions(target.system()))
+    }
+
     fn convert_value(&self, value: ConvertValue, from: &Unit, to: &Unit) -> ConvertValue {
         match value {
             ConvertValue::Number(n) => ConvertValue::Number(self.convert_f64(n, from, to)),
@@ -720,8 +1643,7 @@ impl Converter {
 pub(crate) fn convert_f64(value: f64, from: &Unit, to: &Unit) -> f64 {
     assert_eq!(from.physical_quantity, to.physical_quantity);
 
-    let norm = (value + from.difference) * from.ratio;
-    (norm / to.ratio) - to.difference
+    to.denormalize(from.normalize(value))
 }
 
 /// Error when try to convert an unknown unit
@@ -756,6 +1678,8 @@ pub enum ConvertUnit<'a> {
 pub enum ConvertTo<'a> {
     SameSystem,
     Best(System),
+    /// Like [`ConvertTo::Best`], but with the units of a [`Region`] by name
+    Region(&'a str),
     Unit(ConvertUnit<'a>),
 }
 
@@ -836,6 +1760,25 @@ impl TryFrom<&Value> for ConvertValue {
     }
 }
 
+impl ConvertValue {
+    /// Like [`TryFrom`], but a text value can be a preset of the unit
+    fn resolve(value: &Value, unit: Option<&Unit>) -> Result<Self, ConvertError> {
+        if let (Value::Text(t), Some(unit)) = (value, unit) {
+            if let Some(n) = unit.preset(t) {
+                return Ok(ConvertValue::Number(n));
+            }
+        }
+        Self::try_from(value)
+    }
+
+    fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        match self {
+            ConvertValue::Number(n) => ConvertValue::Number(f(n)),
+            ConvertValue::Range(r) => ConvertValue::Range(f(*r.start())..=f(*r.end())),
+        }
+    }
+}
+
 impl From<f64> for ConvertValue {
     fn from(value: f64) -> Self {
         Self::Number(value)
@@ -862,6 +1805,22 @@ impl PartialOrd<Self> for ConvertValue {
     }
 }
 
+/// Version of the binary form of a [`Converter`]
+#[cfg(feature = "converter_cache")]
+const CACHE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Error loading a [`Converter`] from its binary form
+#[cfg(feature = "converter_cache")]
+#[derive(Debug, Error)]
+pub enum ConverterCacheError {
+    #[error("Converter cache from another version: {0}")]
+    Version(String),
+    #[error(transparent)]
+    Decode(#[from] rmp_serde::decode::Error),
+    #[error("Invalid converter cache: {0}")]
+    Invalid(&'static str),
+}
+
 /// Errors from converting
 #[derive(Debug, Error)]
 pub enum ConvertError {
@@ -883,6 +1842,12 @@ pub enum ConvertError {
         system: Option<System>,
     },
 
+    #[error("Unknown region: '{0}'")]
+    UnknownRegion(String),
+
+    #[error("Can't make a compound quantity: {0}")]
+    InvalidCompound(&'static str),
+
     #[error(transparent)]
     UnknownUnit(#[from] UnknownUnit),
 }
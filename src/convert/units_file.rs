@@ -29,11 +29,74 @@ pub struct UnitsFile {
     ///
     /// If enabled, a decimal value will be converted to a fraction if possible.
     pub fractions: Option<Fractions>,
+    /// Rounding of [humanized](crate::Quantity::humanize) quantities
+    pub humanize: Option<Humanize>,
     /// Extend and/or edit units from other layers before
     pub extend: Option<Extend>,
+    /// Unit of quantities without unit
+    ///
+    /// Any name, symbol or alias of a unit. With this, a quantity without unit
+    /// can be added to others in the same [`PhysicalQuantity`]. For example,
+    /// with `piece`, `6` and `1 dozen` eggs can be added together.
+    ///
+    /// The last layer that sets it wins.
+    pub unitless: Option<String>,
+    /// Declare new physical quantities, like `energy` or `count`
+    ///
+    /// Their names can then be used like the built-in ones in
+    /// [`QuantityGroup::quantity`] and the other settings by quantity.
+    /// Declaring a name again in a later layer is allowed and refers to the
+    /// same quantity.
+    ///
+    /// See [`PhysicalQuantity::Custom`].
+    #[serde(default)]
+    pub custom_quantities: Vec<String>,
     /// Declare new units
     #[serde(default)]
     pub quantity: Vec<QuantityGroup>,
+    /// Regional variants of the units
+    ///
+    /// The keys are the names of the regions. If a region with the same name
+    /// exists in a layer before, they are joined.
+    #[serde(default)]
+    pub region: HashMap<String, RegionEntry>,
+}
+
+/// Regional variant of a [`System`] used in [`UnitsFile`]
+///
+/// The same unit can be different depending on the region. For example, a
+/// cup is 236 ml in the US but 250 ml in Australia, and an imperial pint is
+/// bigger than a US one.
+///
+/// ```toml
+/// [region.au]
+/// system = "metric"
+/// locales = ["en_AU"]
+/// units = { cup = 0.25, tbsp = 0.02 }
+/// ```
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegionEntry {
+    /// System the region is based on
+    ///
+    /// The best units of the region are the ones of this system, with the
+    /// regional variants. Defaults to [`UnitsFile::default_system`].
+    pub system: Option<System>,
+    /// Locales of recipes written in this region
+    ///
+    /// In the same format as the `locale` metadata key, like `en_AU`, or only
+    /// the language, like `en`. Used to know what units a recipe means.
+    pub locales: Vec<String>,
+    /// Units that are different in this region
+    ///
+    /// The keys are any name, symbol or alias of the unit and the values the
+    /// [conversion ratio](UnitEntry::ratio) in this region.
+    pub units: HashMap<String, f64>,
+    /// Best units for this region, if different from the ones of the system
+    ///
+    /// The keys are any name, symbol or alias of the unit, and it uses the
+    /// regional variants.
+    pub best: HashMap<QuantityName, Vec<String>>,
 }
 
 /// [SI] configuration used in [`UnitsFile`]
@@ -110,7 +173,7 @@ pub struct Fractions {
     /// For imperial units
     pub imperial: Option<FractionsConfigWrapper>,
     /// For each [`PhysicalQuantity`]
-    pub quantity: HashMap<PhysicalQuantity, FractionsConfigWrapper>,
+    pub quantity: HashMap<QuantityName, FractionsConfigWrapper>,
     /// For specific units. The keys are any unit name, symbol, or alias.
     pub unit: HashMap<String, FractionsConfigWrapper>,
 }
@@ -178,6 +241,68 @@ impl FractionsConfigHelper {
     }
 }
 
+/// Configuration for [humanized](crate::Quantity::humanize) quantities
+///
+/// Like with [`Fractions`], a unit can have more than one layer, but only the
+/// last one applies, in the order:
+/// - `all`
+/// - `metric` / `imperial`
+/// - `quantity`
+/// - `unit`
+///
+/// `unitless` is used for quantities without unit.
+///
+/// ```toml
+/// [humanize.quantity]
+/// mass = [{ step = 1 }, { above = 100, step = 5 }]
+/// time = false
+///
+/// [humanize.unit]
+/// tsp = { step = 0.125 }
+/// ```
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Humanize {
+    /// The base rule
+    pub all: Option<RoundingRule>,
+    /// For metric units
+    pub metric: Option<RoundingRule>,
+    /// For imperial units
+    pub imperial: Option<RoundingRule>,
+    /// For each [`PhysicalQuantity`]
+    pub quantity: HashMap<QuantityName, RoundingRule>,
+    /// For specific units. The keys are any unit name, symbol, or alias.
+    pub unit: HashMap<String, RoundingRule>,
+    /// For quantities without unit
+    pub unitless: Option<RoundingRule>,
+}
+
+/// How to round a value when humanizing it
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum RoundingRule {
+    /// `false` disables the rounding and `true` rounds to whole numbers
+    Toggle(bool),
+    /// Round to multiples of a step
+    Step(RoundingStep),
+    /// Different steps depending on the value
+    ///
+    /// Each step is used from its [`RoundingStep::above`] value. Values below
+    /// all of them are not rounded.
+    Tiers(Vec<RoundingStep>),
+}
+
+/// A step of a [`RoundingRule`]
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RoundingStep {
+    /// Minimum value (inclusive) where this step is used. Defaults to `0`.
+    #[serde(default)]
+    pub above: f64,
+    /// The value is rounded to the nearest multiple of this
+    pub step: f64,
+}
+
 /// Extend units from other layers config used in [`UnitsFile`]
 ///
 /// The maps's keys are any name, symbol or alias of the unit you want to extend.
@@ -215,6 +340,9 @@ pub enum Precedence {
 pub struct ExtendUnitEntry {
     pub ratio: Option<f64>,
     pub difference: Option<f64>,
+    pub table: Option<Vec<(f64, f64)>>,
+    pub presets: Option<HashMap<Arc<str>, f64>>,
+    pub before_value: Option<bool>,
     #[serde(alias = "name")]
     pub names: Option<Vec<Arc<str>>>,
     #[serde(alias = "symbol")]
@@ -231,8 +359,11 @@ pub struct ExtendUnitEntry {
 pub struct QuantityGroup {
     /// Quantity of the group
     ///
-    /// All units in this struct will be belong to this quantity
-    pub quantity: PhysicalQuantity,
+    /// All units in this struct will be belong to this quantity.
+    ///
+    /// Custom quantities have to be declared in
+    /// [`UnitsFile::custom_quantities`] and need best units too.
+    pub quantity: QuantityName,
     /// List of best units
     ///
     /// This is optional by at least one [`QuantityGroup`] of the quantity in
@@ -246,6 +377,39 @@ pub struct QuantityGroup {
     pub units: Option<Units>,
 }
 
+/// A [`PhysicalQuantity`] in a [`UnitsFile`]
+///
+/// It's deserialized from the name of the quantity. Names that are not
+/// built-in quantities are custom ones, which have to be declared in
+/// [`UnitsFile::custom_quantities`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QuantityName {
+    Builtin(PhysicalQuantity),
+    Custom(String),
+}
+
+impl From<PhysicalQuantity> for QuantityName {
+    fn from(value: PhysicalQuantity) -> Self {
+        Self::Builtin(value)
+    }
+}
+
+impl<'de> Deserialize<'de> for QuantityName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        if name.trim().is_empty() {
+            return Err(serde::de::Error::custom("empty physical quantity"));
+        }
+        Ok(match PhysicalQuantity::builtin(&name) {
+            Some(q) => Self::Builtin(q),
+            None => Self::Custom(name),
+        })
+    }
+}
+
 /// List of best units
 ///
 /// The *best* units are the one elegible for automatic unit convertion to a
@@ -294,7 +458,8 @@ pub enum Units {
 /// This does not carry the [`System`] information, see [`Units`] and/or
 /// [`BestUnits`].
 ///
-/// Conversions will be `val * [Self::ratio] + [Self::difference]`.
+/// Conversions will be `val * [Self::ratio] + [Self::difference]`, unless the
+/// unit has a [`Self::table`].
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct UnitEntry {
@@ -328,8 +493,38 @@ pub struct UnitEntry {
     /// (namely celsius to fahrenheit).
     #[serde(default)]
     pub difference: f64,
+    /// Conversion table for units that are not linear, like oven gas marks
+    ///
+    /// Pairs of `[value, reference]` where `reference` is the value in a
+    /// scale given by [`Self::ratio`] and [`Self::difference`]. Values
+    /// between the points are interpolated, so the table is a piecewise
+    /// linear mapping. For example, a gas mark with the `ratio` and
+    /// `difference` of celsius can have `[[1, 140], [2, 150], [3, 170]]`.
+    ///
+    /// Both columns must be strictly increasing and at least 2 points are
+    /// needed. Values outside the table are clamped to the first or last
+    /// point. Empty means a regular linear unit.
+    #[serde(default)]
+    pub table: Vec<(f64, f64)>,
+    /// Named values of the unit, like the `low`, `medium` and `high` heat
+    ///
+    /// A quantity with one of these names as its text value converts like
+    /// the number when converting to a unit. For example, with
+    /// `{ medium = 2 }`, `medium heat` is the same as `2 heat`. Converting it
+    /// to a system or region keeps it as written. Names are matched ignoring
+    /// ASCII case.
+    #[serde(default)]
+    pub presets: HashMap<Arc<str>, f64>,
+    /// The unit can be written before the number, like `Gas Mark 4`
+    ///
+    /// Only used to find inline quantities in the text of steps, so a
+    /// sentence like `use the cup 2 times` is not a quantity.
+    #[serde(default)]
+    pub before_value: bool,
     /// Mark this unit to expand with [`SI`] configuration.
     ///
+    /// Units with a [`Self::table`] can't be expanded.
+    ///
     /// For example, if this unit is `gram` and is marked with `expand_si`, it
     /// will generate `kilogram`, `hectogram`, `decagram`, `decigram`,
     /// `centigram` and `milligram` automatically so you don't have to.
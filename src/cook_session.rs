@@ -0,0 +1,440 @@
+//! State of a recipe while it's being cooked
+//!
+//! A [`CookSession`] tracks the current step, the checked ingredients, the
+//! timers and the scale. It's just the state, the recipe is given to the
+//! methods that need it, so the session can be saved and restored cheaply.
+//!
+//! ```
+//! # use cooklang::{CooklangParser, Extensions, Converter};
+//! # use cooklang::cook_session::{CookSession, CookEvent, TimerState};
+//! let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+//! let recipe = parser
+//!     .parse("Mix @flour{500%g} and @water.\n\nBake for ~{30%min}.")
+//!     .into_output()
+//!     .unwrap();
+//! let converter = parser.converter();
+//!
+//! let mut session = CookSession::new(&recipe);
+//! session.apply(&recipe, converter, CookEvent::Check { ingredient: 0 })?;
+//! session.apply(&recipe, converter, CookEvent::Next)?;
+//! session.apply(&recipe, converter, CookEvent::StartTimer { timer: 0 })?;
+//!
+//! let finished = session.apply(&recipe, converter, CookEvent::Tick { seconds: 1800.0 })?;
+//! assert_eq!(finished, [0]);
+//! assert_eq!(session.timers()[0].state, TimerState::Finished);
+//! # Ok::<(), cooklang::cook_session::CookSessionError>(())
+//! ```
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[cfg(feature = "ts")]
+use tsify::Tsify;
+
+use crate::{
+    convert::Converter,
+    metadata::time_to_minutes,
+    model::{Content, Item},
+    quantity::Value,
+    step_graph::StepNode,
+    Recipe,
+};
+
+/// State of a recipe being cooked
+///
+/// See the [module level docs](self).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(Tsify))]
+#[cfg_attr(feature = "ts", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct CookSession {
+    /// Current step, [`None`] when finished
+    current: Option<StepNode>,
+    /// Indices in [`Recipe::ingredients`]
+    checked: BTreeSet<usize>,
+    timers: Vec<SessionTimer>,
+    scale: f64,
+}
+
+/// A timer started in a [`CookSession`]
+///
+/// All times are in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(Tsify))]
+pub struct SessionTimer {
+    /// Index in [`Recipe::timers`]
+    pub timer: usize,
+    /// Duration of the timer
+    pub duration: f64,
+    /// Time left
+    pub remaining: f64,
+    /// State of the timer
+    pub state: TimerState,
+}
+
+/// State of a [`SessionTimer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub enum TimerState {
+    /// Counting down
+    Running,
+    /// Stopped, but it can be resumed
+    Paused,
+    /// No time left
+    Finished,
+}
+
+/// Something that happens while cooking
+///
+/// See [`CookSession::apply`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(Tsify))]
+#[cfg_attr(feature = "ts", tsify(into_wasm_abi, from_wasm_abi))]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CookEvent {
+    /// Go to the next step, see [`CookSession::next`]
+    Next,
+    /// Go to the previous step, see [`CookSession::back`]
+    Back,
+    /// Go to a step, see [`CookSession::go_to`]
+    GoTo { section: usize, content: usize },
+    /// Check an ingredient, see [`CookSession::check`]
+    Check { ingredient: usize },
+    /// Uncheck an ingredient, see [`CookSession::uncheck`]
+    Uncheck { ingredient: usize },
+    /// Start a timer, see [`CookSession::start_timer`]
+    StartTimer { timer: usize },
+    /// Pause a timer, see [`CookSession::pause_timer`]
+    PauseTimer { timer: usize },
+    /// Resume a timer, see [`CookSession::resume_timer`]
+    ResumeTimer { timer: usize },
+    /// Remove a timer, see [`CookSession::cancel_timer`]
+    CancelTimer { timer: usize },
+    /// Time passes, see [`CookSession::tick`]
+    Tick { seconds: f64 },
+    /// Change the scale, see [`CookSession::set_scale`]
+    SetScale { factor: f64 },
+}
+
+/// Error from a [`CookEvent`]
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum CookSessionError {
+    #[error("Step {content} in section {section} does not exist")]
+    UnknownStep { section: usize, content: usize },
+    #[error("Ingredient {0} does not exist")]
+    UnknownIngredient(usize),
+    #[error("Timer {0} does not exist")]
+    UnknownTimer(usize),
+    #[error("Timer {0} has no known duration")]
+    TimerWithoutDuration(usize),
+    #[error("Timer {0} has not been started")]
+    TimerNotStarted(usize),
+    #[error("Invalid scale factor: {0}")]
+    InvalidScale(f64),
+}
+
+impl CookSession {
+    /// Starts cooking a recipe at the first step
+    ///
+    /// The recipe has to be the same in all the calls to the session
+    /// methods. If the recipe has no steps, the session starts finished.
+    pub fn new(recipe: &Recipe) -> Self {
+        Self {
+            current: steps(recipe).next(),
+            checked: BTreeSet::new(),
+            timers: Vec::new(),
+            scale: 1.0,
+        }
+    }
+
+    /// Current step
+    ///
+    /// It's always a [`StepNode::Step`], [`None`] if the session is
+    /// finished.
+    pub fn current(&self) -> Option<StepNode> {
+        self.current
+    }
+
+    /// Checks if there are no more steps
+    pub fn is_finished(&self) -> bool {
+        self.current.is_none()
+    }
+
+    /// Goes to the next step
+    ///
+    /// After the last step the session is finished. Returns `false` if it
+    /// was already finished.
+    pub fn next(&mut self, recipe: &Recipe) -> bool {
+        let Some(current) = self.current else {
+            return false;
+        };
+        self.current = steps(recipe).skip_while(|s| *s != current).nth(1);
+        true
+    }
+
+    /// Goes to the previous step
+    ///
+    /// When finished, goes back to the last step. Returns `false` if it
+    /// already was in the first step.
+    pub fn back(&mut self, recipe: &Recipe) -> bool {
+        let prev = match self.current {
+            Some(current) => steps(recipe).take_while(|s| *s != current).last(),
+            None => steps(recipe).last(),
+        };
+        if prev.is_some() {
+            self.current = prev;
+        }
+        prev.is_some()
+    }
+
+    /// Goes to a step
+    ///
+    /// `content` is an index in [`Section::content`](crate::model::Section::content)
+    /// and it has to be a step.
+    pub fn go_to(
+        &mut self,
+        recipe: &Recipe,
+        section: usize,
+        content: usize,
+    ) -> Result<(), CookSessionError> {
+        let node = StepNode::Step { section, content };
+        if !steps(recipe).any(|s| s == node) {
+            return Err(CookSessionError::UnknownStep { section, content });
+        }
+        self.current = Some(node);
+        Ok(())
+    }
+
+    /// Marks an ingredient as checked
+    ///
+    /// `ingredient` is an index in [`Recipe::ingredients`].
+    pub fn check(&mut self, recipe: &Recipe, ingredient: usize) -> Result<(), CookSessionError> {
+        if ingredient >= recipe.ingredients.len() {
+            return Err(CookSessionError::UnknownIngredient(ingredient));
+        }
+        self.checked.insert(ingredient);
+        Ok(())
+    }
+
+    /// Unmarks a checked ingredient
+    pub fn uncheck(&mut self, ingredient: usize) {
+        self.checked.remove(&ingredient);
+    }
+
+    /// Checks if an ingredient is checked
+    pub fn is_checked(&self, ingredient: usize) -> bool {
+        self.checked.contains(&ingredient)
+    }
+
+    /// Ingredients of a step and if they are checked
+    ///
+    /// The ingredients are indices in [`Recipe::ingredients`], in the order
+    /// they are in the step.
+    pub fn step_ingredients(&self, recipe: &Recipe, step: StepNode) -> Vec<(usize, bool)> {
+        let StepNode::Step { section, content } = step else {
+            return Vec::new();
+        };
+        let Some(Content::Step(step)) = recipe
+            .sections
+            .get(section)
+            .and_then(|s| s.content.get(content))
+        else {
+            return Vec::new();
+        };
+        step.items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Ingredient { index } => Some((*index, self.is_checked(*index))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Starts a timer
+    ///
+    /// `timer` is an index in [`Recipe::timers`]. The duration is converted
+    /// to seconds with the converter. When the timer is a range, the shortest
+    /// time is used, so it's time to check if it's done.
+    ///
+    /// Starting a paused timer resumes it and starting a finished one
+    /// restarts it.
+    pub fn start_timer(
+        &mut self,
+        recipe: &Recipe,
+        converter: &Converter,
+        timer: usize,
+    ) -> Result<(), CookSessionError> {
+        if let Some(t) = self.timer_mut(timer) {
+            match t.state {
+                TimerState::Running => {}
+                TimerState::Paused => t.state = TimerState::Running,
+                TimerState::Finished => {
+                    t.remaining = t.duration;
+                    t.state = TimerState::Running;
+                }
+            }
+            return Ok(());
+        }
+
+        let duration = timer_seconds(recipe, converter, timer)?;
+        self.timers.push(SessionTimer {
+            timer,
+            duration,
+            remaining: duration,
+            state: TimerState::Running,
+        });
+        Ok(())
+    }
+
+    /// Pauses a running timer
+    pub fn pause_timer(&mut self, timer: usize) -> Result<(), CookSessionError> {
+        let t = self
+            .timer_mut(timer)
+            .ok_or(CookSessionError::TimerNotStarted(timer))?;
+        if t.state == TimerState::Running {
+            t.state = TimerState::Paused;
+        }
+        Ok(())
+    }
+
+    /// Resumes a paused timer
+    pub fn resume_timer(&mut self, timer: usize) -> Result<(), CookSessionError> {
+        let t = self
+            .timer_mut(timer)
+            .ok_or(CookSessionError::TimerNotStarted(timer))?;
+        if t.state == TimerState::Paused {
+            t.state = TimerState::Running;
+        }
+        Ok(())
+    }
+
+    /// Removes a timer
+    pub fn cancel_timer(&mut self, timer: usize) {
+        self.timers.retain(|t| t.timer != timer);
+    }
+
+    /// Started timers, in the order they were started
+    pub fn timers(&self) -> &[SessionTimer] {
+        &self.timers
+    }
+
+    fn timer_mut(&mut self, timer: usize) -> Option<&mut SessionTimer> {
+        self.timers.iter_mut().find(|t| t.timer == timer)
+    }
+
+    /// Advances the running timers
+    ///
+    /// Returns the indices in [`Recipe::timers`] of the timers that finished
+    /// with this tick.
+    pub fn tick(&mut self, seconds: f64) -> Vec<usize> {
+        let mut finished = Vec::new();
+        for t in &mut self.timers {
+            if t.state != TimerState::Running {
+                continue;
+            }
+            t.remaining -= seconds;
+            if t.remaining <= 0.0 {
+                t.remaining = 0.0;
+                t.state = TimerState::Finished;
+                finished.push(t.timer);
+            }
+        }
+        finished
+    }
+
+    /// Scale factor applied to the recipe
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Changes the scale factor
+    ///
+    /// The session state does not depend on the scale, use
+    /// [`CookSession::scaled_recipe`] to get the recipe to show.
+    pub fn set_scale(&mut self, factor: f64) -> Result<(), CookSessionError> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err(CookSessionError::InvalidScale(factor));
+        }
+        self.scale = factor;
+        Ok(())
+    }
+
+    /// Scales the recipe with the factor of the session
+    ///
+    /// `recipe` has to be the unscaled recipe.
+    pub fn scaled_recipe(&self, recipe: &Recipe, converter: &Converter) -> Recipe {
+        let mut recipe = recipe.clone();
+        if self.scale != 1.0 {
+            recipe.scale(self.scale, converter);
+        }
+        recipe
+    }
+
+    /// Applies an event to the session
+    ///
+    /// This calls the method of the event. Returns the timers that finished
+    /// because of the event, only [`CookEvent::Tick`] finishes timers.
+    pub fn apply(
+        &mut self,
+        recipe: &Recipe,
+        converter: &Converter,
+        event: CookEvent,
+    ) -> Result<Vec<usize>, CookSessionError> {
+        match event {
+            CookEvent::Next => {
+                self.next(recipe);
+            }
+            CookEvent::Back => {
+                self.back(recipe);
+            }
+            CookEvent::GoTo { section, content } => self.go_to(recipe, section, content)?,
+            CookEvent::Check { ingredient } => self.check(recipe, ingredient)?,
+            CookEvent::Uncheck { ingredient } => self.uncheck(ingredient),
+            CookEvent::StartTimer { timer } => self.start_timer(recipe, converter, timer)?,
+            CookEvent::PauseTimer { timer } => self.pause_timer(timer)?,
+            CookEvent::ResumeTimer { timer } => self.resume_timer(timer)?,
+            CookEvent::CancelTimer { timer } => self.cancel_timer(timer),
+            CookEvent::Tick { seconds } => return Ok(self.tick(seconds)),
+            CookEvent::SetScale { factor } => self.set_scale(factor)?,
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// All the steps of a recipe, in order
+fn steps(recipe: &Recipe) -> impl Iterator<Item = StepNode> + '_ {
+    recipe.sections.iter().enumerate().flat_map(|(section, s)| {
+        s.content
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.is_step())
+            .map(move |(content, _)| StepNode::Step { section, content })
+    })
+}
+
+/// Duration of a timer in seconds
+fn timer_seconds(
+    recipe: &Recipe,
+    converter: &Converter,
+    timer: usize,
+) -> Result<f64, CookSessionError> {
+    let quantity = recipe
+        .timers
+        .get(timer)
+        .ok_or(CookSessionError::UnknownTimer(timer))?
+        .quantity
+        .as_ref()
+        .ok_or(CookSessionError::TimerWithoutDuration(timer))?;
+    let value = match quantity.value() {
+        Value::Number(n) => n.value(),
+        Value::Range { start, .. } => start.value(),
+        Value::Text(_) => return Err(CookSessionError::TimerWithoutDuration(timer)),
+    };
+    quantity
+        .unit()
+        .and_then(|unit| time_to_minutes(value, unit, converter).ok())
+        .map(|minutes| minutes * 60.0)
+        .ok_or(CookSessionError::TimerWithoutDuration(timer))
+}
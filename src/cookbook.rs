@@ -0,0 +1,879 @@
+//! Cookbook generation from a collection of recipes
+//!
+//! Generates the source of a [Typst](https://typst.app) or LaTeX document from
+//! many recipes. The document has a title page, a table of contents grouped by
+//! the `course` metadata key, one recipe per page and an ingredient index at
+//! the back. Ingredients that reference other recipes of the cookbook (like
+//! `@@tomato sauce{}` or `@./sauces/tomato sauce{}`) are cross-referenced.
+//!
+//! Only the source is generated, compiling it is up to the caller.
+//!
+//! Quantities are formatted with the [options](FormatOptions) of each recipe
+//! locale, unless others are set with [`Cookbook::with_format`].
+//!
+//! ```
+//! # use cooklang::{CooklangParser, Converter};
+//! # use cooklang::cookbook::Cookbook;
+//! let parser = CooklangParser::default();
+//! let sauce = parser.parse("Simmer @tomatoes{400%g}.").into_output().unwrap();
+//! let pasta = parser.parse("Mix @@tomato sauce{} with @pasta{200%g}.").into_output().unwrap();
+//!
+//! let mut book = Cookbook::new("Family cookbook");
+//! book.add_recipe("tomato sauce", &sauce);
+//! book.add_recipe("pasta", &pasta);
+//!
+//! let mut out = Vec::new();
+//! book.write_typst(parser.converter(), &mut out).unwrap();
+//! let typst = String::from_utf8(out).unwrap();
+//! assert!(typst.contains("#link(<recipe-0>)[tomato sauce]"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::io;
+
+use indexmap::IndexMap;
+
+use crate::format::FormatOptions;
+use crate::metadata::{CooklangValueExt, RecipeTime, StdKey};
+use crate::{Content, Converter, Ingredient, Item, Modifiers, Recipe};
+
+/// Course name used for recipes without a `course` metadata entry
+const NO_COURSE: &str = "Other";
+
+/// A collection of recipes to export as a single document
+///
+/// Recipes are laid out grouped by course, in the order the courses first
+/// appear. Inside a course, recipes keep the order they were added in.
+#[derive(Debug, Clone)]
+pub struct Cookbook<'a> {
+    title: String,
+    author: Option<String>,
+    format: Option<FormatOptions>,
+    recipes: Vec<CookbookRecipe<'a>>,
+}
+
+/// A recipe in a [`Cookbook`]
+#[derive(Debug, Clone)]
+pub struct CookbookRecipe<'a> {
+    /// Name of the recipe, usually the file stem
+    ///
+    /// Recipe references are resolved against this name and the recipe title.
+    pub name: String,
+    /// The recipe
+    pub recipe: &'a Recipe,
+}
+
+impl CookbookRecipe<'_> {
+    /// Title shown in the document, `title` metadata key or [`Self::name`]
+    pub fn title(&self) -> &str {
+        self.recipe.metadata.title().unwrap_or(&self.name)
+    }
+
+    /// Value of the `course` metadata key, if any
+    pub fn course(&self) -> Option<String> {
+        self.recipe
+            .metadata
+            .get(StdKey::Course)
+            .and_then(CooklangValueExt::as_str_like)
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+    }
+}
+
+impl<'a> Cookbook<'a> {
+    /// Creates a new empty cookbook
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            author: None,
+            format: None,
+            recipes: Vec::new(),
+        }
+    }
+
+    /// Sets the author shown in the title page
+    pub fn with_author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Sets the options to format the quantities of all the recipes
+    ///
+    /// By default, each recipe uses [`Metadata::format_options`](crate::Metadata::format_options).
+    pub fn with_format(mut self, format: FormatOptions) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Adds a recipe
+    ///
+    /// `name` is usually the file stem of the recipe.
+    pub fn add_recipe(&mut self, name: impl Into<String>, recipe: &'a Recipe) {
+        self.recipes.push(CookbookRecipe {
+            name: name.into(),
+            recipe,
+        });
+    }
+
+    /// Recipes in the cookbook, in the order they were added
+    pub fn recipes(&self) -> &[CookbookRecipe<'a>] {
+        &self.recipes
+    }
+
+    /// Recipes grouped by course
+    ///
+    /// Courses are in order of first appearance, except recipes without
+    /// course that always go last. Values are indices into [`Self::recipes`].
+    pub fn courses(&self) -> IndexMap<String, Vec<usize>> {
+        let mut courses: IndexMap<String, Vec<usize>> = IndexMap::new();
+        let mut no_course = Vec::new();
+        for (index, entry) in self.recipes.iter().enumerate() {
+            match entry.course() {
+                Some(course) => courses.entry(course).or_default().push(index),
+                None => no_course.push(index),
+            }
+        }
+        if !no_course.is_empty() {
+            courses
+                .entry(NO_COURSE.to_string())
+                .or_default()
+                .extend(no_course);
+        }
+        courses
+    }
+
+    /// Finds the recipe an ingredient references, if it's in the cookbook
+    ///
+    /// Returns the index into [`Self::recipes`].
+    pub fn resolve_reference(&self, ingredient: &Ingredient) -> Option<usize> {
+        let name = if let Some(reference) = &ingredient.reference {
+            reference.name.as_str()
+        } else if ingredient.modifiers().contains(Modifiers::RECIPE) {
+            std::path::Path::new(&ingredient.name)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(&ingredient.name)
+        } else {
+            return None;
+        };
+        let name = name.trim();
+        self.recipes
+            .iter()
+            .position(|r| r.name.eq_ignore_ascii_case(name))
+            .or_else(|| {
+                self.recipes
+                    .iter()
+                    .position(|r| r.title().eq_ignore_ascii_case(name))
+            })
+    }
+
+    /// Ingredient index
+    ///
+    /// Maps the ingredient names (lowercase and sorted) to the recipes that
+    /// use them. Ingredients that reference other recipes are not included.
+    pub fn ingredient_index(&self) -> BTreeMap<String, Vec<usize>> {
+        let mut index: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (recipe_index, entry) in self.recipes.iter().enumerate() {
+            for igr in &entry.recipe.ingredients {
+                if !igr.relation.is_definition()
+                    || igr.reference.is_some()
+                    || igr.modifiers().contains(Modifiers::RECIPE)
+                {
+                    continue;
+                }
+                let recipes = index.entry(igr.name.to_lowercase()).or_default();
+                if !recipes.contains(&recipe_index) {
+                    recipes.push(recipe_index);
+                }
+            }
+        }
+        index
+    }
+
+    /// Writes the cookbook as a Typst document
+    pub fn write_typst(&self, converter: &Converter, w: impl io::Write) -> io::Result<()> {
+        self.write_with(&Typst, converter, w)
+    }
+
+    /// Writes the cookbook as a LaTeX document
+    ///
+    /// The document only uses standard packages (`hyperref`) and is meant to
+    /// be compiled with any modern LaTeX engine.
+    pub fn write_latex(&self, converter: &Converter, w: impl io::Write) -> io::Result<()> {
+        self.write_with(&Latex, converter, w)
+    }
+
+    fn write_with(
+        &self,
+        backend: &dyn Backend,
+        converter: &Converter,
+        mut w: impl io::Write,
+    ) -> io::Result<()> {
+        let w: &mut dyn io::Write = &mut w;
+        backend.begin(w, &self.title, self.author.as_deref())?;
+        for (course, recipes) in self.courses() {
+            for (n, &index) in recipes.iter().enumerate() {
+                backend.new_page(w)?;
+                if n == 0 {
+                    backend.course(w, &course)?;
+                }
+                self.write_recipe(backend, converter, index, w)?;
+            }
+        }
+
+        let index = self.ingredient_index();
+        if !index.is_empty() {
+            backend.new_page(w)?;
+            backend.index_heading(w)?;
+            for (ingredient, recipes) in &index {
+                let refs = recipes
+                    .iter()
+                    .map(|&r| backend.reference(self.recipes[r].title(), r))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                backend.index_entry(w, &backend.escape(ingredient), &refs)?;
+            }
+            backend.end_index(w)?;
+        }
+        backend.end(w)
+    }
+
+    fn write_recipe(
+        &self,
+        backend: &dyn Backend,
+        converter: &Converter,
+        index: usize,
+        w: &mut dyn io::Write,
+    ) -> io::Result<()> {
+        let entry = &self.recipes[index];
+        let recipe = entry.recipe;
+        let format = match &self.format {
+            Some(format) => format.clone(),
+            None => recipe.metadata.format_options(),
+        };
+        backend.recipe_heading(w, &backend.escape(entry.title()), index)?;
+
+        if let Some(description) = recipe.metadata.description() {
+            backend.paragraph(w, &backend.escape(description))?;
+        }
+        let mut details = Vec::new();
+        if let Some(servings) = recipe.metadata.servings() {
+            details.push(format!("Servings: {servings}"));
+        }
+        if let Some(time) = recipe.metadata.time(converter) {
+            details.push(format_time(time));
+        }
+        if !details.is_empty() {
+            backend.paragraph(w, &backend.escape(&details.join(" · ")))?;
+        }
+
+        let ingredients = recipe
+            .group_ingredients(converter)
+            .into_iter()
+            .filter(|g| !g.ingredient.modifiers().contains(Modifiers::HIDDEN))
+            .collect::<Vec<_>>();
+        if !ingredients.is_empty() {
+            backend.subheading(w, "Ingredients")?;
+            backend.begin_list(w)?;
+            for g in ingredients {
+                let mut line = String::new();
+                if !g.quantity.is_empty() {
+                    line += &backend.escape(&g.quantity.format(&format, converter));
+                    line.push(' ');
+                }
+                line += &self.ingredient_name(backend, g.ingredient);
+                if g.ingredient.modifiers().contains(Modifiers::OPT) {
+                    line += &backend.escape(" (optional)");
+                }
+                if let Some(note) = &g.ingredient.note {
+                    line += &backend.escape(&format!(" ({note})"));
+                }
+                backend.list_item(w, &line)?;
+            }
+            backend.end_list(w)?;
+        }
+
+        backend.subheading(w, "Steps")?;
+        for section in &recipe.sections {
+            if let Some(name) = &section.name {
+                backend.section_heading(w, &backend.escape(name))?;
+            }
+            let mut in_steps = false;
+            for content in &section.content {
+                match content {
+                    Content::Step(step) => {
+                        if !in_steps {
+                            backend.begin_steps(w)?;
+                            in_steps = true;
+                        }
+                        let text = step
+                            .items
+                            .iter()
+                            .map(|item| self.item(backend, recipe, item, &format, converter))
+                            .collect::<String>();
+                        backend.step(w, step.number, &text)?;
+                    }
+                    Content::Text(text) => {
+                        if in_steps {
+                            backend.end_steps(w)?;
+                            in_steps = false;
+                        }
+                        backend.paragraph(w, &backend.escape(text))?;
+                    }
+                }
+            }
+            if in_steps {
+                backend.end_steps(w)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn ingredient_name(&self, backend: &dyn Backend, ingredient: &Ingredient) -> String {
+        let name = ingredient.display_name();
+        match self.resolve_reference(ingredient) {
+            Some(target) => backend.reference(&name, target),
+            None => backend.escape(&name),
+        }
+    }
+
+    fn item(
+        &self,
+        backend: &dyn Backend,
+        recipe: &Recipe,
+        item: &Item,
+        format: &FormatOptions,
+        converter: &Converter,
+    ) -> String {
+        match item {
+            Item::Text { value } => backend.escape(value),
+            Item::Ingredient { index } => {
+                self.ingredient_name(backend, &recipe.ingredients[*index])
+            }
+            Item::Cookware { index } => backend.escape(recipe.cookware[*index].display_name()),
+            Item::Timer { index } => {
+                let timer = &recipe.timers[*index];
+                let text = match (&timer.quantity, &timer.name) {
+                    (Some(q), _) => q.format(format, converter),
+                    (None, Some(name)) => name.clone(),
+                    (None, None) => String::new(),
+                };
+                backend.escape(&text)
+            }
+            Item::InlineQuantity { index } => {
+                backend.escape(&recipe.inline_quantities[*index].format(format, converter))
+            }
+        }
+    }
+}
+
+fn format_time(time: RecipeTime) -> String {
+    fn minutes(m: u32) -> String {
+        match (m / 60, m % 60) {
+            (0, m) => format!("{m} min"),
+            (h, 0) => format!("{h} h"),
+            (h, m) => format!("{h} h {m} min"),
+        }
+    }
+    match time {
+        RecipeTime::Total(t) => format!("Time: {}", minutes(t)),
+        RecipeTime::Composed {
+            prep_time,
+            cook_time,
+        } => {
+            let mut parts = Vec::new();
+            if let Some(p) = prep_time {
+                parts.push(format!("Prep: {}", minutes(p)));
+            }
+            if let Some(c) = cook_time {
+                parts.push(format!("Cook: {}", minutes(c)));
+            }
+            parts.join(" · ")
+        }
+    }
+}
+
+/// Output format specific markup
+///
+/// Arguments named `text` or `markup` are already escaped. Other string
+/// arguments are plain text and must be escaped by the backend.
+trait Backend {
+    fn escape(&self, text: &str) -> String;
+    fn reference(&self, text: &str, recipe: usize) -> String;
+
+    fn begin(&self, w: &mut dyn io::Write, title: &str, author: Option<&str>) -> io::Result<()>;
+    fn end(&self, w: &mut dyn io::Write) -> io::Result<()>;
+    fn new_page(&self, w: &mut dyn io::Write) -> io::Result<()>;
+    fn course(&self, w: &mut dyn io::Write, course: &str) -> io::Result<()>;
+    fn recipe_heading(&self, w: &mut dyn io::Write, text: &str, recipe: usize) -> io::Result<()>;
+    fn subheading(&self, w: &mut dyn io::Write, title: &str) -> io::Result<()>;
+    fn section_heading(&self, w: &mut dyn io::Write, text: &str) -> io::Result<()>;
+    fn paragraph(&self, w: &mut dyn io::Write, text: &str) -> io::Result<()>;
+    fn begin_list(&self, w: &mut dyn io::Write) -> io::Result<()>;
+    fn end_list(&self, w: &mut dyn io::Write) -> io::Result<()>;
+    fn begin_steps(&self, w: &mut dyn io::Write) -> io::Result<()>;
+    fn end_steps(&self, w: &mut dyn io::Write) -> io::Result<()>;
+    fn list_item(&self, w: &mut dyn io::Write, markup: &str) -> io::Result<()>;
+    fn step(&self, w: &mut dyn io::Write, number: u32, markup: &str) -> io::Result<()>;
+    fn index_heading(&self, w: &mut dyn io::Write) -> io::Result<()>;
+    fn index_entry(&self, w: &mut dyn io::Write, text: &str, markup: &str) -> io::Result<()>;
+    fn end_index(&self, w: &mut dyn io::Write) -> io::Result<()>;
+}
+
+fn label(recipe: usize) -> String {
+    format!("recipe-{recipe}")
+}
+
+struct Typst;
+
+impl Typst {
+    fn string(text: &str) -> String {
+        let mut s = String::with_capacity(text.len() + 2);
+        s.push('"');
+        for c in text.chars() {
+            match c {
+                '"' => s.push_str("\\\""),
+                '\\' => s.push_str("\\\\"),
+                '\n' => s.push_str("\\n"),
+                c => s.push(c),
+            }
+        }
+        s.push('"');
+        s
+    }
+}
+
+impl Backend for Typst {
+    fn escape(&self, text: &str) -> String {
+        let mut s = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\\' | '#' | '*' | '_' | '`' | '$' | '<' | '>' | '@' | '[' | ']' | '~' | '/'
+                | '=' | '-' | '+' | '"' | '\'' | ':' => {
+                    s.push('\\');
+                    s.push(c);
+                }
+                '\n' | '\r' => s.push(' '),
+                c => s.push(c),
+            }
+        }
+        s
+    }
+
+    fn reference(&self, text: &str, recipe: usize) -> String {
+        let label = label(recipe);
+        format!(
+            "#link(<{label}>)[{}] (p.~#context counter(page).at(<{label}>).first())",
+            self.escape(text)
+        )
+    }
+
+    fn begin(&self, w: &mut dyn io::Write, title: &str, author: Option<&str>) -> io::Result<()> {
+        match author {
+            Some(author) => writeln!(
+                w,
+                "#set document(title: {}, author: {})",
+                Typst::string(title),
+                Typst::string(author)
+            )?,
+            None => writeln!(w, "#set document(title: {})", Typst::string(title))?,
+        }
+        writeln!(w, "#set page(paper: \"a4\")")?;
+        writeln!(w, "#set heading(numbering: none)")?;
+        writeln!(w)?;
+        writeln!(w, "#page(numbering: none)[")?;
+        writeln!(w, "  #align(center + horizon)[")?;
+        writeln!(
+            w,
+            "    #text(size: 32pt, weight: \"bold\")[{}]",
+            self.escape(title)
+        )?;
+        if let Some(author) = author {
+            writeln!(w, "    #v(1em)")?;
+            writeln!(w, "    #text(size: 16pt)[{}]", self.escape(author))?;
+        }
+        writeln!(w, "  ]")?;
+        writeln!(w, "]")?;
+        writeln!(w)?;
+        writeln!(w, "#set page(numbering: \"1\")")?;
+        writeln!(w, "#outline(title: [Contents], depth: 2)")?;
+        Ok(())
+    }
+
+    fn end(&self, _w: &mut dyn io::Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn new_page(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(w, "#pagebreak(weak: true)")
+    }
+
+    fn course(&self, w: &mut dyn io::Write, course: &str) -> io::Result<()> {
+        writeln!(w, "= {}", self.escape(course))
+    }
+
+    fn recipe_heading(&self, w: &mut dyn io::Write, text: &str, recipe: usize) -> io::Result<()> {
+        writeln!(w, "== {text} <{}>", label(recipe))
+    }
+
+    fn subheading(&self, w: &mut dyn io::Write, title: &str) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(
+            w,
+            "#heading(level: 3, outlined: false)[{}]",
+            self.escape(title)
+        )
+    }
+
+    fn section_heading(&self, w: &mut dyn io::Write, text: &str) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(w, "#heading(level: 4, outlined: false)[{text}]")
+    }
+
+    fn paragraph(&self, w: &mut dyn io::Write, text: &str) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(w, "{text}")
+    }
+
+    fn begin_list(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w)
+    }
+
+    fn end_list(&self, _w: &mut dyn io::Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn begin_steps(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w)
+    }
+
+    fn end_steps(&self, _w: &mut dyn io::Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn list_item(&self, w: &mut dyn io::Write, markup: &str) -> io::Result<()> {
+        writeln!(w, "- {markup}")
+    }
+
+    fn step(&self, w: &mut dyn io::Write, number: u32, markup: &str) -> io::Result<()> {
+        writeln!(w, "{number}. {markup}")
+    }
+
+    fn index_heading(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "= Ingredient index")?;
+        writeln!(w)?;
+        writeln!(w, "#columns(2)[")
+    }
+
+    fn index_entry(&self, w: &mut dyn io::Write, text: &str, markup: &str) -> io::Result<()> {
+        writeln!(w, "/ {text}: {markup}")
+    }
+
+    fn end_index(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "]")
+    }
+}
+
+struct Latex;
+
+impl Backend for Latex {
+    fn escape(&self, text: &str) -> String {
+        let mut s = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\\' => s.push_str("\\textbackslash{}"),
+                '~' => s.push_str("\\textasciitilde{}"),
+                '^' => s.push_str("\\textasciicircum{}"),
+                '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                    s.push('\\');
+                    s.push(c);
+                }
+                '\n' | '\r' => s.push(' '),
+                c => s.push(c),
+            }
+        }
+        s
+    }
+
+    fn reference(&self, text: &str, recipe: usize) -> String {
+        let label = label(recipe);
+        format!(
+            "\\hyperref[{label}]{{{}}} (p.~\\pageref{{{label}}})",
+            self.escape(text)
+        )
+    }
+
+    fn begin(&self, w: &mut dyn io::Write, title: &str, author: Option<&str>) -> io::Result<()> {
+        writeln!(w, "\\documentclass[a4paper]{{article}}")?;
+        writeln!(w, "\\usepackage[T1]{{fontenc}}")?;
+        writeln!(w, "\\usepackage{{multicol}}")?;
+        writeln!(w, "\\usepackage{{hyperref}}")?;
+        writeln!(w)?;
+        writeln!(w, "\\title{{{}}}", self.escape(title))?;
+        writeln!(w, "\\author{{{}}}", self.escape(author.unwrap_or_default()))?;
+        writeln!(w, "\\date{{}}")?;
+        writeln!(w)?;
+        writeln!(w, "\\begin{{document}}")?;
+        writeln!(w, "\\begin{{titlepage}}")?;
+        writeln!(w, "\\maketitle")?;
+        writeln!(w, "\\thispagestyle{{empty}}")?;
+        writeln!(w, "\\end{{titlepage}}")?;
+        writeln!(w)?;
+        writeln!(w, "\\setcounter{{tocdepth}}{{2}}")?;
+        writeln!(w, "\\tableofcontents")?;
+        Ok(())
+    }
+
+    fn end(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(w, "\\end{{document}}")
+    }
+
+    fn new_page(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(w, "\\clearpage")
+    }
+
+    fn course(&self, w: &mut dyn io::Write, course: &str) -> io::Result<()> {
+        let course = self.escape(course);
+        writeln!(w, "\\section*{{{course}}}")?;
+        // anchor for the toc entry, starred headings don't have one
+        writeln!(w, "\\phantomsection")?;
+        writeln!(w, "\\addcontentsline{{toc}}{{section}}{{{course}}}")
+    }
+
+    fn recipe_heading(&self, w: &mut dyn io::Write, text: &str, recipe: usize) -> io::Result<()> {
+        writeln!(w, "\\subsection*{{{text}}}")?;
+        writeln!(w, "\\phantomsection")?;
+        writeln!(w, "\\addcontentsline{{toc}}{{subsection}}{{{text}}}")?;
+        writeln!(w, "\\label{{{}}}", label(recipe))
+    }
+
+    fn subheading(&self, w: &mut dyn io::Write, title: &str) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(w, "\\subsubsection*{{{}}}", self.escape(title))
+    }
+
+    fn section_heading(&self, w: &mut dyn io::Write, text: &str) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(w, "\\paragraph{{{text}}}")
+    }
+
+    fn paragraph(&self, w: &mut dyn io::Write, text: &str) -> io::Result<()> {
+        writeln!(w)?;
+        writeln!(w, "{text}")
+    }
+
+    fn begin_list(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "\\begin{{itemize}}")
+    }
+
+    fn end_list(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "\\end{{itemize}}")
+    }
+
+    fn begin_steps(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "\\begin{{enumerate}}")
+    }
+
+    fn end_steps(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "\\end{{enumerate}}")
+    }
+
+    fn list_item(&self, w: &mut dyn io::Write, markup: &str) -> io::Result<()> {
+        writeln!(w, "  \\item {markup}")
+    }
+
+    fn step(&self, w: &mut dyn io::Write, number: u32, markup: &str) -> io::Result<()> {
+        writeln!(w, "  \\item[{number}.] {markup}")
+    }
+
+    fn index_heading(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "\\section*{{Ingredient index}}")?;
+        writeln!(w, "\\phantomsection")?;
+        writeln!(w, "\\addcontentsline{{toc}}{{section}}{{Ingredient index}}")?;
+        writeln!(w, "\\begin{{multicols}}{{2}}")?;
+        writeln!(w, "\\begin{{description}}")
+    }
+
+    fn index_entry(&self, w: &mut dyn io::Write, text: &str, markup: &str) -> io::Result<()> {
+        // braces so a `]` in the text doesn't end the optional argument
+        writeln!(w, "  \\item[{{{text}}}] {markup}")
+    }
+
+    fn end_index(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "\\end{{description}}")?;
+        writeln!(w, "\\end{{multicols}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CooklangParser, Extensions};
+
+    fn parse(input: &str) -> Recipe {
+        CooklangParser::new(Extensions::all(), Converter::bundled())
+            .parse(input)
+            .into_output()
+            .unwrap()
+    }
+
+    fn typst(book: &Cookbook) -> String {
+        let mut out = Vec::new();
+        book.write_typst(&Converter::bundled(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn latex(book: &Cookbook) -> String {
+        let mut out = Vec::new();
+        book.write_latex(&Converter::bundled(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn courses_in_order() {
+        let a = parse("---\ncourse: dessert\n---\nA");
+        let b = parse("B");
+        let c = parse("---\ncourse: main\n---\nC");
+        let d = parse("---\ncourse: dessert\n---\nD");
+        let mut book = Cookbook::new("Book");
+        book.add_recipe("a", &a);
+        book.add_recipe("b", &b);
+        book.add_recipe("c", &c);
+        book.add_recipe("d", &d);
+
+        let courses = book.courses();
+        assert_eq!(
+            courses.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("dessert".to_string(), vec![0, 3]),
+                ("main".to_string(), vec![2]),
+                (NO_COURSE.to_string(), vec![1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_references() {
+        let sauce = parse("---\ntitle: Tomato Sauce\n---\nSimmer @tomatoes{}.");
+        let pasta = parse("Add @@tomato sauce{} and @./sauces/pesto{} and @cheese{}.");
+        let mut book = Cookbook::new("Book");
+        book.add_recipe("tomato sauce", &sauce);
+        book.add_recipe("pasta", &pasta);
+
+        let igrs = &pasta.ingredients;
+        assert_eq!(book.resolve_reference(&igrs[0]), Some(0));
+        assert_eq!(book.resolve_reference(&igrs[1]), None);
+        assert_eq!(book.resolve_reference(&igrs[2]), None);
+
+        let by_title = parse("Add @@Tomato Sauce{}.");
+        let mut book = Cookbook::new("Book");
+        book.add_recipe("sauce", &sauce);
+        assert_eq!(book.resolve_reference(&by_title.ingredients[0]), Some(0));
+    }
+
+    #[test]
+    fn ingredient_index() {
+        let a = parse("@Flour{100%g} @eggs{2} @&flour{10%g} @@sauce{}");
+        let b = parse("@eggs{1} @salt{}");
+        let mut book = Cookbook::new("Book");
+        book.add_recipe("a", &a);
+        book.add_recipe("b", &b);
+
+        let index = book.ingredient_index();
+        assert_eq!(
+            index.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("eggs".to_string(), vec![0, 1]),
+                ("flour".to_string(), vec![0]),
+                ("salt".to_string(), vec![1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn typst_document() {
+        let sauce = parse("---\ncourse: sauces\n---\nSimmer @tomatoes{400%g} for ~{20%min}.");
+        let pasta = parse(
+            "---\ndescription: Quick *weeknight* dinner\nservings: 2\n---\nBoil @pasta{200%g}.\n\nMix with @@tomato sauce{}.",
+        );
+        let mut book = Cookbook::new("Family cookbook").with_author("The family");
+        book.add_recipe("tomato sauce", &sauce);
+        book.add_recipe("pasta", &pasta);
+        let out = typst(&book);
+
+        assert!(out.contains("#set document(title: \"Family cookbook\", author: \"The family\")"));
+        assert!(out.contains("#outline("));
+        assert!(out.contains("= sauces\n== tomato sauce <recipe-0>"));
+        assert!(out.contains("= Other\n== pasta <recipe-1>"));
+        assert!(out.contains("- 400 g tomatoes"));
+        assert!(out.contains("1. Simmer tomatoes for 20 min."));
+        assert!(out.contains("Quick \\*weeknight\\* dinner"));
+        assert!(out.contains("2. Mix with #link(<recipe-0>)[tomato sauce]"));
+        assert!(out.contains("= Ingredient index"));
+        assert!(out.contains("/ pasta: #link(<recipe-1>)[pasta]"));
+        assert_eq!(out.matches("#pagebreak(weak: true)").count(), 3);
+
+        let recipe = parse("Add @salt: coarse{1%tsp}.");
+        let mut book = Cookbook::new("Colons");
+        book.add_recipe("salt", &recipe);
+        assert!(typst(&book).contains("/ salt\\: coarse: #link(<recipe-0>)[salt]"));
+    }
+
+    #[test]
+    fn latex_document() {
+        let recipe = parse("---\ntitle: Mac & cheese\n---\nMix @pasta{200%g} with @cheese{50%g}.");
+        let other = parse("Serve with @@Mac & cheese{}.");
+        let mut book = Cookbook::new("100% home made");
+        book.add_recipe("mac", &recipe);
+        book.add_recipe("salad", &other);
+        let out = latex(&book);
+
+        assert!(out.starts_with("\\documentclass"));
+        assert!(out.contains("\\title{100\\% home made}"));
+        assert!(out.contains(
+            "\\subsection*{Mac \\& cheese}\n\\phantomsection\n\\addcontentsline{toc}{subsection}{Mac \\& cheese}\n\\label{recipe-0}"
+        ));
+        assert!(out.contains("\\section*{Other}\n\\phantomsection\n\\addcontentsline"));
+        assert!(out.contains("  \\item 200 g pasta"));
+        assert!(out.contains("  \\item[1.] Mix pasta with cheese."));
+        assert!(out.contains("\\hyperref[recipe-0]{Mac \\& cheese} (p.~\\pageref{recipe-0})"));
+        assert!(out.contains("  \\item[{cheese}] \\hyperref[recipe-0]{Mac \\& cheese}"));
+        assert!(out.trim_end().ends_with("\\end{document}"));
+
+        let recipe = parse("Add @chili [hot]{1}.");
+        let mut book = Cookbook::new("Spicy");
+        book.add_recipe("chili", &recipe);
+        assert!(latex(&book).contains("  \\item[{chili [hot]}] \\hyperref"));
+    }
+
+    #[test]
+    fn recipe_locale_format() {
+        let de = parse("---\nlocale: de_DE\n---\n@milk{1.5%l} @rice{1250%grains}");
+        let en = parse("@milk{1.5%l}");
+        let mut book = Cookbook::new("Book");
+        book.add_recipe("de", &de);
+        book.add_recipe("en", &en);
+        let out = typst(&book);
+        assert!(out.contains("- 1,5 l milk"));
+        assert!(out.contains("- 1.250 grains rice"));
+        assert!(out.contains("- 1.5 l milk"));
+
+        let book = book.with_format(FormatOptions::default());
+        assert!(!typst(&book).contains("1,5 l"));
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(Typst.escape("a_b *c* #d @e"), "a\\_b \\*c\\* \\#d \\@e");
+        assert_eq!(Typst.escape("Tip: salt"), "Tip\\: salt");
+        assert_eq!(Typst::string("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(Latex.escape("50% & $5_{x}"), "50\\% \\& \\$5\\_\\{x\\}");
+        assert_eq!(
+            Latex.escape("a~b\\c"),
+            "a\\textasciitilde{}b\\textbackslash{}c"
+        );
+    }
+}
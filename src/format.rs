@@ -0,0 +1,410 @@
+//! Locale aware formatting of numbers and quantities
+//!
+//! The [`Display`](std::fmt::Display) impls of [`Number`], [`Value`],
+//! [`Quantity`] and [`GroupedQuantity`] always use `.` as decimal separator
+//! and ASCII fractions. Their `format` methods take [`FormatOptions`] instead.
+//!
+//! ```
+//! # use cooklang::{format::FormatOptions, Converter, Quantity, Value};
+//! let converter = Converter::bundled();
+//! let q = Quantity::new(Value::from(0.5), Some("l".into()));
+//! assert_eq!(q.to_string(), "0.5 l");
+//! let de = FormatOptions::for_locale("de", None);
+//! assert_eq!(q.format(&de, &converter), "0,5 l");
+//! ```
+
+use crate::{
+    quantity::{GroupedQuantity, Number, Quantity, Value},
+    Converter, Metadata,
+};
+
+/// Options to format numbers and quantities
+///
+/// The default is the same output as the [`Display`](std::fmt::Display)
+/// impls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    /// Decimal separator. Defaults to `.`
+    pub decimal_separator: char,
+    /// Thousands separator, if any. Defaults to none
+    pub thousands_separator: Option<char>,
+    /// Use Unicode vulgar fractions like `½` when possible. Defaults to
+    /// `false`
+    pub unicode_fractions: bool,
+    /// How to write the units. Defaults to [`UnitStyle::AsWritten`]
+    pub unit_style: UnitStyle,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            thousands_separator: None,
+            unicode_fractions: false,
+            unit_style: UnitStyle::AsWritten,
+        }
+    }
+}
+
+/// How to write the units of a [`Quantity`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnitStyle {
+    /// Keep the unit text as is
+    #[default]
+    AsWritten,
+    /// Use the [symbol](crate::convert::Unit::symbol) of known units
+    Symbol,
+    /// Use the name of known units
+    ///
+    /// The first name is used as singular and the second one, if any, as
+    /// plural, like in the bundled units.
+    Name,
+}
+
+/// (language, decimal separator, thousands separator)
+const LANGUAGES: &[(&str, char, char)] = &[
+    ("bg", ',', '\u{a0}'),
+    ("ca", ',', '.'),
+    ("cs", ',', '\u{a0}'),
+    ("da", ',', '.'),
+    ("de", ',', '.'),
+    ("el", ',', '.'),
+    ("en", '.', ','),
+    ("es", ',', '.'),
+    ("et", ',', '\u{a0}'),
+    ("eu", ',', '.'),
+    ("fi", ',', '\u{a0}'),
+    ("fr", ',', '\u{202f}'),
+    ("gl", ',', '.'),
+    ("hr", ',', '.'),
+    ("hu", ',', '\u{a0}'),
+    ("id", ',', '.'),
+    ("it", ',', '.'),
+    ("ja", '.', ','),
+    ("ko", '.', ','),
+    ("lt", ',', '\u{a0}'),
+    ("lv", ',', '\u{a0}'),
+    ("nb", ',', '\u{a0}'),
+    ("nl", ',', '.'),
+    ("nn", ',', '\u{a0}'),
+    ("no", ',', '\u{a0}'),
+    ("pl", ',', '\u{a0}'),
+    ("pt", ',', '.'),
+    ("ro", ',', '.'),
+    ("ru", ',', '\u{a0}'),
+    ("sk", ',', '\u{a0}'),
+    ("sl", ',', '.'),
+    ("sr", ',', '.'),
+    ("sv", ',', '\u{a0}'),
+    ("tr", ',', '.'),
+    ("uk", ',', '\u{a0}'),
+    ("vi", ',', '.'),
+    ("zh", '.', ','),
+];
+
+/// (language, dialect, decimal separator, thousands separator)
+const DIALECTS: &[(&str, &str, char, char)] = &[
+    ("de", "CH", '.', '’'),
+    ("de", "LI", '.', '’'),
+    ("es", "MX", '.', ','),
+    ("es", "US", '.', ','),
+    ("fr", "CH", '.', '\u{202f}'),
+    ("it", "CH", '.', '’'),
+];
+
+impl FormatOptions {
+    /// Options with the number separators of a locale
+    ///
+    /// The arguments are the same as the ones returned by
+    /// [`Metadata::locale`]. Unknown locales get the default options. Only
+    /// the separators depend on the locale.
+    pub fn for_locale(language: &str, dialect: Option<&str>) -> Self {
+        let separators = dialect
+            .and_then(|d| {
+                DIALECTS
+                    .iter()
+                    .find(|(l, r, _, _)| {
+                        l.eq_ignore_ascii_case(language) && r.eq_ignore_ascii_case(d)
+                    })
+                    .map(|&(_, _, dec, th)| (dec, th))
+            })
+            .or_else(|| {
+                LANGUAGES
+                    .iter()
+                    .find(|(l, _, _)| l.eq_ignore_ascii_case(language))
+                    .map(|&(_, dec, th)| (dec, th))
+            });
+        match separators {
+            Some((decimal_separator, thousands_separator)) => Self {
+                decimal_separator,
+                thousands_separator: Some(thousands_separator),
+                ..Default::default()
+            },
+            None => Self::default(),
+        }
+    }
+}
+
+impl Metadata {
+    /// Format options for the recipe [locale](Metadata::locale)
+    ///
+    /// See [`FormatOptions::for_locale`]. Without locale, the default
+    /// options.
+    pub fn format_options(&self) -> FormatOptions {
+        self.locale()
+            .map(|(lang, dialect)| FormatOptions::for_locale(lang, dialect))
+            .unwrap_or_default()
+    }
+}
+
+impl Number {
+    /// Formats the number with the given options
+    ///
+    /// Like the [`Display`](std::fmt::Display) impl, the error of fractions
+    /// and rounded numbers is not included and regular numbers are rounded
+    /// to 3 decimal places.
+    ///
+    /// ```
+    /// # use cooklang::{format::FormatOptions, quantity::Number};
+    /// let opts = FormatOptions {
+    ///     decimal_separator: ',',
+    ///     thousands_separator: Some('.'),
+    ///     unicode_fractions: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(Number::Regular(1234.5).format(&opts), "1.234,5");
+    /// let half = Number::Fraction { whole: 1, num: 1, den: 2, err: 0.0 };
+    /// assert_eq!(half.format(&opts), "1½");
+    /// ```
+    pub fn format(&self, opts: &FormatOptions) -> String {
+        match *self {
+            Number::Regular(n) => format_decimal(n, opts),
+            Number::Fraction {
+                whole, num, den, ..
+            } => {
+                if self.value() == 0.0 || (whole == 0 && num == 0) {
+                    return "0".to_string();
+                }
+                let whole_str = format_integer(&whole.to_string(), opts);
+                if num == 0 {
+                    return whole_str;
+                }
+                match (opts.unicode_fractions, vulgar_fraction(num, den)) {
+                    (true, Some(c)) if whole == 0 => c.to_string(),
+                    (true, Some(c)) => format!("{whole_str}{c}"),
+                    _ if whole == 0 => format!("{num}/{den}"),
+                    _ => format!("{whole_str} {num}/{den}"),
+                }
+            }
+        }
+    }
+}
+
+impl Value {
+    /// Formats the value with the given options
+    ///
+    /// See [`Number::format`]. Text values are not changed.
+    pub fn format(&self, opts: &FormatOptions) -> String {
+        match self {
+            Value::Number(n) => n.format(opts),
+            Value::Range { start, end } => format!("{}-{}", start.format(opts), end.format(opts)),
+            Value::Text(t) => t.clone(),
+        }
+    }
+}
+
+impl Quantity {
+    /// Formats the quantity with the given options
+    ///
+    /// The converter is used to find the unit for [`UnitStyle::Symbol`] and
+    /// [`UnitStyle::Name`]. Unknown units are kept as written.
+    pub fn format(&self, opts: &FormatOptions, converter: &Converter) -> String {
+        let mut s = self.value().format(opts);
+        if let Some(unit) = self.unit() {
+            s.push(' ');
+            s += &self.format_unit(unit, opts, converter);
+        }
+        s
+    }
+
+    fn format_unit(&self, text: &str, opts: &FormatOptions, converter: &Converter) -> String {
+        if opts.unit_style == UnitStyle::AsWritten {
+            return text.to_string();
+        }
+        let Some(unit) = self.unit_info(converter) else {
+            return text.to_string();
+        };
+        if opts.unit_style == UnitStyle::Name {
+            let plural = match self.value() {
+                Value::Number(n) => n.value() > 1.0,
+                Value::Range { .. } => true,
+                Value::Text(_) => false,
+            };
+            let name = if plural {
+                unit.names.get(1).or(unit.names.first())
+            } else {
+                unit.names.first()
+            };
+            if let Some(name) = name {
+                return name.to_string();
+            }
+        }
+        unit.symbol().to_string()
+    }
+}
+
+impl GroupedQuantity {
+    /// Formats all the quantities with the given options
+    ///
+    /// See [`Quantity::format`]. Like the [`Display`](std::fmt::Display)
+    /// impl, the quantities are separated by commas.
+    pub fn format(&self, opts: &FormatOptions, converter: &Converter) -> String {
+        self.iter()
+            .map(|q| q.format(opts, converter))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn format_decimal(n: f64, opts: &FormatOptions) -> String {
+    let s = ((n * 1000.0).round() / 1000.0).to_string();
+    let (int, frac) = match s.split_once('.') {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (s.as_str(), None),
+    };
+    let mut out = format_integer(int, opts);
+    if let Some(frac) = frac {
+        out.push(opts.decimal_separator);
+        out += frac;
+    }
+    out
+}
+
+/// Inserts the thousands separator in a string of digits, maybe with a sign
+fn format_integer(digits: &str, opts: &FormatOptions) -> String {
+    let Some(sep) = opts.thousands_separator else {
+        return digits.to_string();
+    };
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(d) => ("-", d),
+        None => ("", digits),
+    };
+    let mut out = String::from(sign);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(sep);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Unicode vulgar fractions with their numerator and denominator
+pub(crate) const VULGAR_FRACTIONS: &[(char, u32, u32)] = &[
+    ('½', 1, 2),
+    ('⅓', 1, 3),
+    ('⅔', 2, 3),
+    ('¼', 1, 4),
+    ('¾', 3, 4),
+    ('⅕', 1, 5),
+    ('⅖', 2, 5),
+    ('⅗', 3, 5),
+    ('⅘', 4, 5),
+    ('⅙', 1, 6),
+    ('⅚', 5, 6),
+    ('⅐', 1, 7),
+    ('⅛', 1, 8),
+    ('⅜', 3, 8),
+    ('⅝', 5, 8),
+    ('⅞', 7, 8),
+    ('⅑', 1, 9),
+    ('⅒', 1, 10),
+];
+
+fn vulgar_fraction(num: u32, den: u32) -> Option<char> {
+    VULGAR_FRACTIONS
+        .iter()
+        .find(|&&(_, n, d)| n == num && d == den)
+        .map(|&(c, _, _)| c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0.5 => "0,5")]
+    #[test_case(1234.5 => "1.234,5")]
+    #[test_case(1234567.0 => "1.234.567")]
+    #[test_case(-1234.0 => "-1.234")]
+    #[test_case(123.0 => "123")]
+    #[test_case(0.33333 => "0,333")]
+    fn decimal_comma(n: f64) -> String {
+        Number::Regular(n).format(&FormatOptions::for_locale("de", Some("DE")))
+    }
+
+    #[test_case(0, 1, 2, false => "1/2"; "ascii")]
+    #[test_case(2, 3, 4, false => "2 3/4"; "ascii whole")]
+    #[test_case(0, 1, 2, true => "½"; "unicode")]
+    #[test_case(2, 3, 4, true => "2¾"; "unicode whole")]
+    #[test_case(1, 3, 16, true => "1 3/16"; "no unicode char")]
+    #[test_case(3, 0, 1, true => "3"; "only whole")]
+    #[test_case(1200, 1, 2, true => "1,200½"; "thousands")]
+    fn fractions(whole: u32, num: u32, den: u32, unicode: bool) -> String {
+        let opts = FormatOptions {
+            unicode_fractions: unicode,
+            ..FormatOptions::for_locale("en", None)
+        };
+        Number::Fraction {
+            whole,
+            num,
+            den,
+            err: 0.0,
+        }
+        .format(&opts)
+    }
+
+    #[test_case("de", Some("CH") => ('.', Some('’')))]
+    #[test_case("es", Some("ES") => (',', Some('.')))]
+    #[test_case("ES", Some("mx") => ('.', Some(',')))]
+    #[test_case("fr", None => (',', Some('\u{202f}')))]
+    #[test_case("xx", None => ('.', None))]
+    fn locales(lang: &str, dialect: Option<&str>) -> (char, Option<char>) {
+        let opts = FormatOptions::for_locale(lang, dialect);
+        (opts.decimal_separator, opts.thousands_separator)
+    }
+
+    #[test]
+    fn default_is_display() {
+        let opts = FormatOptions::default();
+        for n in [0.5, 1234.5678, 2.0, 0.0] {
+            assert_eq!(
+                Number::Regular(n).format(&opts),
+                Number::Regular(n).to_string()
+            );
+        }
+        let frac = Number::Fraction {
+            whole: 1,
+            num: 1,
+            den: 2,
+            err: 0.01,
+        };
+        assert_eq!(frac.format(&opts), frac.to_string());
+    }
+
+    #[cfg(feature = "bundled_units")]
+    #[test_case(0.5, Some("l"), UnitStyle::AsWritten => "0,5 l")]
+    #[test_case(1.0, Some("tablespoons"), UnitStyle::Symbol => "1 tbsp")]
+    #[test_case(1.0, Some("tbsp"), UnitStyle::Name => "1 tablespoon")]
+    #[test_case(2.5, Some("tbsp"), UnitStyle::Name => "2,5 tablespoons")]
+    #[test_case(2.0, Some("handful"), UnitStyle::Name => "2 handful"; "unknown unit")]
+    #[test_case(3.0, None, UnitStyle::Name => "3"; "no unit")]
+    fn quantity(value: f64, unit: Option<&str>, unit_style: UnitStyle) -> String {
+        let opts = FormatOptions {
+            unit_style,
+            ..FormatOptions::for_locale("es", None)
+        };
+        Quantity::new(Value::from(value), unit.map(String::from))
+            .format(&opts, &Converter::bundled())
+    }
+}
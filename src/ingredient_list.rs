@@ -1,11 +1,13 @@
 //! Generate ingredients lists from recipes
 
+use std::collections::HashMap;
+
 use indexmap::IndexMap;
 use serde::Serialize;
 
 use crate::{
     aisle::AisleConf, convert::Converter, model::Ingredient, quantity::GroupedQuantity, Cookware,
-    Recipe,
+    Modifiers, Recipe,
 };
 
 /// Ingredient with all quantities from it's references and itself grouped.
@@ -103,8 +105,75 @@ impl Recipe {
 /// This will only store the ingredient name and quantity. This is used to
 /// combine multiple recipes into a single list. For ingredients of a single
 /// recipe, check [`ScaledRecipe::group_ingredients`].
+///
+/// The list also keeps where every quantity comes from, see
+/// [`IngredientList::breakdown`].
 #[derive(Debug, Default)]
-pub struct IngredientList(IndexMap<String, GroupedQuantity>);
+pub struct IngredientList {
+    quantities: IndexMap<String, GroupedQuantity>,
+    contributions: HashMap<String, Vec<Contribution>>,
+}
+
+/// Quantity of an ingredient added to an [`IngredientList`]
+#[derive(Debug, Clone, Serialize)]
+pub struct Contribution {
+    /// Name or path of the recipe, `None` if the ingredient was added by itself
+    pub recipe: Option<String>,
+    /// Scale factor of the recipe
+    pub multiplier: f64,
+    /// Index of the ingredient definition in the recipe
+    /// [`ingredients`](crate::model::Recipe::ingredients)
+    pub index: Option<usize>,
+    /// Name the ingredient was added with
+    pub name: String,
+    /// Quantity as it was added, before merging it or subtracting the pantry
+    pub quantity: GroupedQuantity,
+    /// Modifiers of the ingredient in the recipe, like
+    /// [`OPT`](Modifiers::OPT)
+    pub modifiers: Modifiers,
+}
+
+impl std::fmt::Display for Contribution {
+    /// Quantity and recipe, like `300 g (Pancakes ×2)`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let quantity = self.quantity.to_string();
+        f.write_str(&quantity)?;
+        if let Some(recipe) = &self.recipe {
+            if !quantity.is_empty() {
+                f.write_str(" ")?;
+            }
+            write!(f, "({recipe}")?;
+            if self.multiplier != 1.0 {
+                write!(f, " ×{}", self.multiplier)?;
+            }
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+/// An ingredient of an [`IngredientList`] and where it comes from
+///
+/// Obtained from [`IngredientList::breakdown`].
+#[derive(Debug, Clone, Serialize)]
+pub struct IngredientBreakdown<'a> {
+    pub name: &'a str,
+    /// Total quantity in the list
+    pub quantity: &'a GroupedQuantity,
+    pub contributions: &'a [Contribution],
+}
+
+impl std::fmt::Display for IngredientBreakdown<'_> {
+    /// Name and contributions, like `flour: 300 g (Pancakes ×2), 500 g (Bread)`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (i, c) in self.contributions.iter().enumerate() {
+            f.write_str(if i == 0 { ": " } else { ", " })?;
+            c.fmt(f)?;
+        }
+        Ok(())
+    }
+}
 
 impl IngredientList {
     /// Empty list
@@ -122,7 +191,8 @@ impl IngredientList {
     /// Subtract pantry quantities from the ingredient list.
     ///
     /// For each ingredient in the list, if it exists in the pantry with a valid quantity,
-    /// subtract that quantity from the required amount. Only subtracts when units match.
+    /// subtract that quantity from the required amount. Only subtracts when units are
+    /// compatible, converting between them if needed.
     /// Returns a new `IngredientList` with the remaining quantities needed.
     ///
     /// # Arguments
@@ -161,95 +231,88 @@ impl IngredientList {
                     if let Some((pantry_value, pantry_unit)) = pantry_item.parsed_quantity() {
                         // If pantry has 0, keep everything in the shopping list
                         if pantry_value <= 0.0 {
-                            result.add_ingredient(
-                                ingredient_name.clone(),
-                                required_quantity,
-                                converter,
-                            );
+                            result.add_from(self, ingredient_name, required_quantity, converter);
                             continue;
                         }
 
                         // Try to subtract from each quantity variant
                         let mut remaining_quantities = crate::quantity::GroupedQuantity::empty();
-                        let mut any_subtracted = false;
-                        let mut unit_mismatch = false;
 
                         for req_qty in required_quantity.iter() {
-                            let req_unit =
-                                req_qty.unit().map(|u| u.to_lowercase()).unwrap_or_default();
-
-                            if req_unit == pantry_unit {
-                                // Units match, we can subtract
-                                if let crate::quantity::Value::Number(req_num) = req_qty.value() {
-                                    let req_value: f64 = req_num.value();
-                                    let remaining_value = req_value - pantry_value;
-
-                                    if remaining_value > 0.0 {
-                                        let remaining_qty = crate::quantity::Quantity::new(
-                                            crate::quantity::Value::Number(
-                                                crate::quantity::Number::Regular(remaining_value),
-                                            ),
-                                            req_qty.unit().map(|s| s.to_string()),
-                                        );
-                                        remaining_quantities.add(&remaining_qty, converter);
-                                        let unit_display =
-                                            if req_unit.is_empty() { "" } else { &req_unit };
-                                        tracing::info!(
-                                            "Reduced '{}' from {} {} to {} {} (pantry has {} {})",
-                                            ingredient_name,
-                                            req_value,
-                                            unit_display,
-                                            remaining_value,
-                                            unit_display,
-                                            pantry_value,
-                                            unit_display
-                                        );
-                                    } else {
-                                        let unit_display = if pantry_unit.is_empty() {
-                                            ""
-                                        } else {
-                                            &pantry_unit
-                                        };
-                                        tracing::info!(
-                                            "Removing '{}' from shopping list (sufficient in pantry: {} {})",
-                                            ingredient_name,
-                                            pantry_value,
-                                            unit_display
-                                        );
+                            // pantry units are lowercase, but unknown units
+                            // must match exactly
+                            let unit = match req_qty.unit() {
+                                Some(u) if u.to_lowercase() == pantry_unit => Some(u.to_string()),
+                                _ if pantry_unit.is_empty() => None,
+                                _ => Some(pantry_unit.clone()),
+                            };
+                            let pantry_qty =
+                                crate::quantity::Quantity::new(pantry_value.into(), unit);
+
+                            let subtracted =
+                                req_qty.try_cmp(&pantry_qty, converter).and_then(|ord| {
+                                    Ok((ord, req_qty.try_sub(&pantry_qty, converter)?))
+                                });
+                            match subtracted {
+                                Ok((
+                                    Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal),
+                                    _,
+                                )) => {
+                                    tracing::info!(
+                                        "Removing '{}' from shopping list (sufficient in pantry: {})",
+                                        ingredient_name,
+                                        pantry_qty
+                                    );
+                                }
+                                Ok((_, mut remaining)) => {
+                                    // the start of a range may be below 0
+                                    if let crate::quantity::Value::Range { start, .. } =
+                                        remaining.value_mut()
+                                    {
+                                        if start.value() < 0.0 {
+                                            *start = 0.0.into();
+                                        }
                                     }
-                                    any_subtracted = true;
-                                } else {
-                                    remaining_quantities.add(req_qty, converter);
+                                    tracing::info!(
+                                        "Reduced '{}' from {} to {} (pantry has {})",
+                                        ingredient_name,
+                                        req_qty,
+                                        remaining,
+                                        pantry_qty
+                                    );
+                                    remaining_quantities.add(
+                                        &remaining,
+                                        Some(ingredient_name),
+                                        converter,
+                                    );
+                                }
+                                Err(err) => {
+                                    remaining_quantities.add(
+                                        req_qty,
+                                        Some(ingredient_name),
+                                        converter,
+                                    );
+                                    tracing::warn!(
+                                        "Can't subtract pantry quantity of '{}': recipe needs '{}', pantry has '{}': {}",
+                                        ingredient_name,
+                                        req_qty,
+                                        pantry_qty,
+                                        err
+                                    );
                                 }
-                            } else {
-                                // Units don't match
-                                remaining_quantities.add(req_qty, converter);
-                                unit_mismatch = true;
-                                tracing::warn!(
-                                    "Unit mismatch for '{}': recipe needs '{}', pantry has '{}'",
-                                    ingredient_name,
-                                    req_unit,
-                                    pantry_unit
-                                );
                             }
                         }
 
-                        if unit_mismatch && !any_subtracted {
-                            // Keep full amount due to unit mismatch
-                            result.add_ingredient(
-                                ingredient_name.clone(),
-                                required_quantity,
-                                converter,
-                            );
-                        } else if !remaining_quantities.is_empty() {
+                        if !remaining_quantities.is_empty() {
                             // Add the remaining quantities
-                            result.add_ingredient(
-                                ingredient_name.clone(),
+                            result.add_from(
+                                self,
+                                ingredient_name,
                                 &remaining_quantities,
                                 converter,
                             );
                         }
-                        // If remaining_quantities is empty and no unit mismatch, item is fully covered
+                        // If remaining_quantities is empty, item is fully covered
                     } else {
                         // Can't parse pantry quantity, keep original
                         tracing::warn!(
@@ -257,11 +320,7 @@ impl IngredientList {
                             ingredient_name,
                             pantry_qty_str
                         );
-                        result.add_ingredient(
-                            ingredient_name.clone(),
-                            required_quantity,
-                            converter,
-                        );
+                        result.add_from(self, ingredient_name, required_quantity, converter);
                     }
                 } else {
                     // No quantity specified in pantry, assume we have it (backward compatibility)
@@ -272,7 +331,7 @@ impl IngredientList {
                 }
             } else {
                 // Not in pantry, keep it in the list
-                result.add_ingredient(ingredient_name.clone(), required_quantity, converter);
+                result.add_from(self, ingredient_name, required_quantity, converter);
             }
         }
 
@@ -291,11 +350,40 @@ impl IngredientList {
     /// error.
     ///
     /// Ingredients are listed based on their [`display_name`](crate::model::Ingredient::display_name).
+    ///
+    /// The [`Contribution`]s have no recipe name, to give one use
+    /// [`IngredientList::add_recipe_from`].
     pub fn add_recipe(
         &mut self,
         recipe: &Recipe,
         converter: &Converter,
         list_references: bool,
+    ) -> Vec<usize> {
+        self.add_recipe_inner(recipe, None, 1.0, converter, list_references)
+    }
+
+    /// Same as [`IngredientList::add_recipe`] but records the recipe name and
+    /// the factor it was scaled by in the [`Contribution`]s.
+    ///
+    /// `recipe` must be already scaled.
+    pub fn add_recipe_from(
+        &mut self,
+        name: &str,
+        multiplier: f64,
+        recipe: &Recipe,
+        converter: &Converter,
+        list_references: bool,
+    ) -> Vec<usize> {
+        self.add_recipe_inner(recipe, Some(name), multiplier, converter, list_references)
+    }
+
+    fn add_recipe_inner(
+        &mut self,
+        recipe: &Recipe,
+        name: Option<&str>,
+        multiplier: f64,
+        converter: &Converter,
+        list_references: bool,
     ) -> Vec<usize> {
         let mut references = Vec::new();
 
@@ -318,7 +406,16 @@ impl IngredientList {
                 continue;
             }
 
-            self.add_ingredient(ingredient.display_name().into_owned(), &quantity, converter);
+            let display_name = ingredient.display_name().into_owned();
+            let contribution = Contribution {
+                recipe: name.map(str::to_string),
+                multiplier,
+                index: Some(index),
+                name: display_name.clone(),
+                quantity: quantity.clone(),
+                modifiers: ingredient.modifiers(),
+            };
+            self.add(display_name, &quantity, vec![contribution], converter);
         }
 
         references
@@ -333,12 +430,156 @@ impl IngredientList {
         quantity: &GroupedQuantity,
         converter: &Converter,
     ) {
-        self.0.entry(name).or_default().merge(quantity, converter)
+        let contribution = Contribution {
+            recipe: None,
+            multiplier: 1.0,
+            index: None,
+            name: name.clone(),
+            quantity: quantity.clone(),
+            modifiers: Modifiers::empty(),
+        };
+        self.add(name, quantity, vec![contribution], converter)
+    }
+
+    pub(crate) fn add(
+        &mut self,
+        name: String,
+        quantity: &GroupedQuantity,
+        contributions: Vec<Contribution>,
+        converter: &Converter,
+    ) {
+        self.quantities
+            .entry(name.clone())
+            .or_default()
+            .merge(quantity, Some(&name), converter);
+        self.contributions
+            .entry(name)
+            .or_default()
+            .extend(contributions);
+    }
+
+    /// Add an ingredient of `other` with a different quantity, keeping its
+    /// contributions
+    #[cfg(feature = "pantry")]
+    fn add_from(
+        &mut self,
+        other: &Self,
+        name: &str,
+        quantity: &GroupedQuantity,
+        converter: &Converter,
+    ) {
+        let contributions = other.contributions(name).to_vec();
+        self.add(name.to_string(), quantity, contributions, converter)
+    }
+
+    /// Insert an ingredient without merging, replacing any previous one
+    fn insert(
+        &mut self,
+        name: String,
+        quantity: GroupedQuantity,
+        contributions: Vec<Contribution>,
+    ) {
+        self.contributions.insert(name.clone(), contributions);
+        self.quantities.insert(name, quantity);
     }
 
     /// Cheks if the list is empty
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.quantities.is_empty()
+    }
+
+    /// Where the quantity of an ingredient comes from
+    ///
+    /// Empty if the ingredient is not in the list.
+    pub fn contributions(&self, name: &str) -> &[Contribution] {
+        self.contributions
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Iterate over all ingredients in insertion order with where their
+    /// quantities come from
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Extensions, Converter, ingredient_list::IngredientList};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let pancakes = parser.parse("@flour{150%g}").into_output().unwrap();
+    /// let bread = parser.parse("@flour{500%g}").into_output().unwrap();
+    ///
+    /// let mut list = IngredientList::new();
+    /// let mut scaled = pancakes.clone();
+    /// scaled.scale(2.0, parser.converter());
+    /// list.add_recipe_from("Pancakes", 2.0, &scaled, parser.converter(), false);
+    /// list.add_recipe_from("Bread", 1.0, &bread, parser.converter(), false);
+    ///
+    /// let flour = list.breakdown().next().unwrap();
+    /// assert_eq!(flour.to_string(), "flour: 300 g (Pancakes ×2), 500 g (Bread)");
+    /// assert_eq!(flour.quantity.to_string(), "800 g");
+    /// ```
+    pub fn breakdown(&self) -> impl Iterator<Item = IngredientBreakdown<'_>> {
+        self.quantities
+            .iter()
+            .map(|(name, quantity)| IngredientBreakdown {
+                name,
+                quantity,
+                contributions: self.contributions(name),
+            })
+    }
+
+    /// Remove everything a recipe added to the list
+    ///
+    /// `recipe` is the name given in [`IngredientList::add_recipe_from`].
+    /// Quantities of the affected ingredients are added again from the
+    /// remaining contributions, so if the pantry was already subtracted, it
+    /// has to be subtracted again.
+    pub fn remove_recipe(&mut self, recipe: &str, converter: &Converter) {
+        let mut emptied = Vec::new();
+        for (name, contributions) in self.contributions.iter_mut() {
+            let before = contributions.len();
+            contributions.retain(|c| c.recipe.as_deref() != Some(recipe));
+            if contributions.len() == before {
+                continue;
+            }
+            if contributions.is_empty() {
+                emptied.push(name.clone());
+                continue;
+            }
+            let mut quantity = GroupedQuantity::empty();
+            for c in contributions.iter() {
+                quantity.merge(&c.quantity, Some(name), converter);
+            }
+            self.quantities.insert(name.clone(), quantity);
+        }
+        for name in emptied {
+            self.contributions.remove(&name);
+            self.quantities.shift_remove(&name);
+        }
+    }
+
+    /// Remove the ingredients for which `f` returns true and return them in
+    /// a new list
+    pub fn extract_if(&mut self, mut f: impl FnMut(&str, &GroupedQuantity) -> bool) -> Self {
+        let mut extracted = Self::new();
+        let quantities = std::mem::take(&mut self.quantities);
+        for (name, quantity) in quantities {
+            let contributions = self.contributions.remove(&name).unwrap_or_default();
+            if f(&name, &quantity) {
+                extracted.insert(name, quantity, contributions);
+            } else {
+                self.insert(name, quantity, contributions);
+            }
+        }
+        extracted
+    }
+
+    /// Add all the ingredients of another list, keeping their contributions
+    pub fn merge(&mut self, other: Self, converter: &Converter) {
+        let mut contributions = other.contributions;
+        for (name, quantity) in other.quantities {
+            let c = contributions.remove(&name).unwrap_or_default();
+            self.add(name, &quantity, c, converter);
+        }
     }
 
     /// Split this list into different categories.
@@ -348,8 +589,9 @@ impl IngredientList {
     /// Ingredients without category will be placed in `"other"`.
     pub fn categorize(self, aisle: &AisleConf) -> CategorizedIngredientList {
         // Build a lookup from the shopping list (lowercase name -> (original_name, quantity))
+        let mut contributions = self.contributions;
         let mut shopping_lookup: IndexMap<String, (String, GroupedQuantity)> = self
-            .0
+            .quantities
             .into_iter()
             .map(|(name, qty)| (name.to_lowercase(), (name, qty)))
             .collect();
@@ -367,10 +609,11 @@ impl IngredientList {
                 // Check each name variant (synonyms) for this ingredient
                 for name in &ingredient.names {
                     let lookup_key = name.to_lowercase();
-                    if let Some((_, quantity)) = shopping_lookup.swap_remove(&lookup_key) {
+                    if let Some((original, quantity)) = shopping_lookup.swap_remove(&lookup_key) {
                         // Use the common name (first name in the ingredient definition)
                         let common_name = ingredient.names.first().unwrap_or(name);
-                        category_list.0.insert(common_name.to_string(), quantity);
+                        let c = contributions.remove(&original).unwrap_or_default();
+                        category_list.insert(common_name.to_string(), quantity, c);
                         break; // Found this ingredient, move to next
                     }
                 }
@@ -385,7 +628,8 @@ impl IngredientList {
 
         // Any remaining items go to "other"
         for (_, (name, quantity)) in shopping_lookup {
-            categorized.other.0.insert(name, quantity);
+            let c = contributions.remove(&name).unwrap_or_default();
+            categorized.other.insert(name, quantity, c);
         }
 
         categorized
@@ -393,22 +637,25 @@ impl IngredientList {
 
     /// Iterate over all ingredients in insertion order
     pub fn iter(&self) -> impl Iterator<Item = (&String, &GroupedQuantity)> {
-        self.0.iter()
+        self.quantities.iter()
     }
 
     /// Replace names of ingredients with common names given by aisle configuration.
     ///
-    /// Matching is case-insensitive.
+    /// Matching is case-insensitive. The [`Contribution`]s keep the original
+    /// names.
     pub fn use_common_names(self, aisle: &AisleConf, converter: &Converter) -> Self {
         let ingredients_info = aisle.ingredients_info();
         let mut normalized = Self::new();
-        for (ingredient_name, quantity) in self.iter() {
+        let mut contributions = self.contributions;
+        for (ingredient_name, quantity) in self.quantities {
             // Use lowercase for case-insensitive lookup
             let common_name = ingredients_info
                 .get(&ingredient_name.to_lowercase())
                 .map(|info| info.common_name.to_string())
                 .unwrap_or(ingredient_name.to_string());
-            normalized.add_ingredient(common_name, quantity, converter);
+            let c = contributions.remove(&ingredient_name).unwrap_or_default();
+            normalized.add(common_name, &quantity, c, converter);
         }
         normalized
     }
@@ -421,7 +668,21 @@ impl IntoIterator for IngredientList {
 
     /// Iterate over all ingredients in insertion order
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.quantities.into_iter()
+    }
+}
+
+impl FromIterator<(String, GroupedQuantity)> for IngredientList {
+    /// Collect ingredients into a list
+    ///
+    /// If a name is repeated, the last quantity is kept. To merge them, use
+    /// [`IngredientList::add_ingredient`].
+    fn from_iter<T: IntoIterator<Item = (String, GroupedQuantity)>>(iter: T) -> Self {
+        let mut list = Self::new();
+        for (name, quantity) in iter {
+            list.insert(name, quantity, Vec::new());
+        }
+        list
     }
 }
 
@@ -508,7 +769,7 @@ impl Iterator for CategorizedIntoIter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{CooklangParser, Extensions};
+    use crate::{CooklangParser, Extensions, Quantity};
 
     #[test]
     fn test_categorize_preserves_aisle_order() {
@@ -666,6 +927,62 @@ apple
             vec!["zucchini", "banana", "carrot", "apple"]
         );
     }
+    #[test]
+    fn breakdown_and_remove_recipe() {
+        let converter = Converter::bundled();
+        let parser = CooklangParser::new(Extensions::all(), converter.clone());
+        let pancakes = parser
+            .parse("@flour{150%g} @milk{300%ml} @?butter{10%g}")
+            .into_output()
+            .unwrap();
+        let bread = parser.parse("@Flour{500%g} @salt").into_output().unwrap();
+
+        let mut list = IngredientList::new();
+        let mut scaled = pancakes.clone();
+        scaled.scale(2.0, &converter);
+        list.add_recipe_from("Pancakes", 2.0, &scaled, &converter, false);
+        list.add_recipe_from("Bread", 1.0, &bread, &converter, false);
+        let mut extra = GroupedQuantity::empty();
+        extra.add(
+            &Quantity::new(1.0.into(), Some("kg".into())),
+            None,
+            &converter,
+        );
+        list.add_ingredient("flour".into(), &extra, &converter);
+
+        let aisle = crate::aisle::parse("[baking]\nflour|Flour\n").unwrap();
+        let list = list.use_common_names(&aisle, &converter);
+        let lines: Vec<_> = list.breakdown().map(|b| b.to_string()).collect();
+        assert_eq!(
+            lines,
+            [
+                "flour: 300 g (Pancakes ×2), 1 kg, 500 g (Bread)",
+                "milk: 600 ml (Pancakes ×2)",
+                "butter: 20 g (Pancakes ×2)",
+                "salt: (Bread)",
+            ]
+        );
+        let flour = list.contributions("flour");
+        assert_eq!(flour[1].recipe, None);
+        assert_eq!(flour[2].name, "Flour");
+        assert_eq!(flour[2].index, Some(0));
+        assert!(list.contributions("butter")[0]
+            .modifiers
+            .contains(Modifiers::OPT));
+
+        let mut categorized = list.categorize(&aisle);
+        let baking = &mut categorized.categories["baking"];
+        assert_eq!(baking.contributions("flour").len(), 3);
+        baking.remove_recipe("Bread", &converter);
+        assert_eq!(baking.iter().next().unwrap().1.to_string(), "1300 g");
+        assert_eq!(baking.contributions("flour").len(), 2);
+
+        let other = &mut categorized.other;
+        other.remove_recipe("Bread", &converter);
+        assert!(other.contributions("salt").is_empty());
+        let names: Vec<_> = other.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["milk", "butter"]);
+    }
 }
 
 #[cfg(all(test, feature = "pantry"))]
@@ -761,6 +1078,39 @@ oil = "1%l"
         assert_eq!(qty.to_string(), "500 g");
     }
 
+    #[test]
+    fn test_subtract_pantry_converts_units() {
+        let converter = Converter::bundled();
+        let parser = CooklangParser::new(Extensions::all(), converter.clone());
+
+        let recipe = parser
+            .parse("@flour{1%kg} and @sugar{200-300%g}")
+            .into_output()
+            .unwrap();
+
+        let mut list = IngredientList::new();
+        list.add_recipe(&recipe, &converter, false);
+
+        let pantry_toml = r#"
+[pantry]
+flour = "250%g"
+sugar = "0.25%kg"
+"#;
+        let pantry = crate::pantry::parse(pantry_toml).unwrap();
+        let result = list.subtract_pantry(&pantry, &converter);
+
+        let (_, flour) = result
+            .iter()
+            .find(|(name, _)| name.as_str() == "flour")
+            .unwrap();
+        assert_eq!(flour.to_string(), "0.75 kg");
+        let (_, sugar) = result
+            .iter()
+            .find(|(name, _)| name.as_str() == "sugar")
+            .unwrap();
+        assert_eq!(sugar.to_string(), "0-50 g");
+    }
+
     #[test]
     fn test_subtract_pantry_zero_quantity() {
         let converter = Converter::bundled();
@@ -868,4 +1218,22 @@ milk = "500%ml"
         let (_, qty) = butter_qty.unwrap();
         assert_eq!(qty.to_string(), "300 g");
     }
+
+    #[test]
+    fn subtract_pantry_keeps_contributions() {
+        let converter = Converter::bundled();
+        let parser = CooklangParser::new(Extensions::all(), converter.clone());
+        let recipe = parser
+            .parse("@flour{500%g} @milk{1%l}")
+            .into_output()
+            .unwrap();
+        let mut list = IngredientList::new();
+        list.add_recipe_from("Bread", 1.0, &recipe, &converter, false);
+
+        let pantry = crate::pantry::parse("[pantry]\nflour = \"200%g\"\n").unwrap();
+        let list = list.subtract_pantry(&pantry, &converter);
+        let lines: Vec<_> = list.breakdown().map(|b| b.to_string()).collect();
+        assert_eq!(lines, ["flour: 500 g (Bread)", "milk: 1 l (Bread)"]);
+        assert_eq!(list.iter().next().unwrap().1.to_string(), "300 g");
+    }
 }
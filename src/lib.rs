@@ -56,7 +56,8 @@ pub mod _extensions {
 
 #[cfg(doc)]
 pub mod _features {
-    //! This lib has 3 features, 2 enabled by default:
+    //! This lib has the following features, `bundled_units`, `aisle` and
+    //! `shopping_list` are enabled by default:
     //! - `bundled_units`. Includes a units file with the most common units for
     //!   recipes in English. These units are available to load when you want
     //!   without the need to read a file. The default
@@ -66,6 +67,27 @@ pub mod _features {
     //! - `aisle`. Enables the [`aisle`](crate::aisle) module.
     //!
     //! - `pantry`. Enables the [`pantry`](crate::pantry) module.
+    //!
+    //! - `shopping_list`. Enables the [`shopping_list`](crate::shopping_list)
+    //!   module.
+    //!
+    //! - `meal_plan`. Enables the [`meal_plan`](crate::meal_plan) module. It
+    //!   also enables `shopping_list`.
+    //!
+    //! - `cookbook`. Enables the [`cookbook`](crate::cookbook) module.
+    //!
+    //! - `nutrition`. Enables the [`nutrition`](crate::nutrition) module.
+    //!
+    //! - `packages`. Enables the [`packages`](crate::packages) module. It also
+    //!   enables `aisle`.
+    //!
+    //! - `converter_cache`. Enables a compact binary form of a
+    //!   [`Converter`](crate::convert::Converter) to cache it, see
+    //!   [`Converter::to_bytes`](crate::convert::Converter::to_bytes).
+    //!
+    //! - `units_diagnostics`. Enables
+    //!   [`ConverterBuilder::parse_units_file_lenient`](crate::convert::ConverterBuilder::parse_units_file_lenient)
+    //!   to report problems of a units file pointing into its TOML source.
 }
 
 #[cfg(feature = "aisle")]
@@ -73,11 +95,21 @@ pub mod aisle;
 pub mod analysis;
 pub mod ast;
 pub mod convert;
+pub mod cook_session;
+#[cfg(feature = "cookbook")]
+pub mod cookbook;
 pub mod error;
+pub mod format;
 pub mod ingredient_list;
 pub mod located;
+#[cfg(feature = "meal_plan")]
+pub mod meal_plan;
 pub mod metadata;
 pub mod model;
+#[cfg(feature = "nutrition")]
+pub mod nutrition;
+#[cfg(feature = "packages")]
+pub mod packages;
 #[cfg(feature = "pantry")]
 pub mod pantry;
 pub mod parser;
@@ -86,9 +118,13 @@ pub mod scale;
 #[cfg(feature = "shopping_list")]
 pub mod shopping_list;
 pub mod span;
+pub mod step_graph;
 pub mod text;
+pub mod timeline;
 
 mod lexer;
+mod rational;
+mod suggest;
 
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
@@ -111,7 +147,8 @@ bitflags! {
     /// This allows to enable or disable the extensions. See [extensions](_extensions)
     /// for a detailed explanation of all of them.
     ///
-    /// [`Extensions::default`] enables all extensions.
+    /// [`Extensions::default`] enables all extensions except
+    /// [`Extensions::LOOSE_NUMBERS`].
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
     pub struct Extensions: u32 {
         /// Enables the [`Modifiers`](crate::ast::Modifiers)
@@ -150,8 +187,18 @@ bitflags! {
     }
 }
 
+impl Extensions {
+    /// Read Unicode fractions, decimal commas and number words like
+    /// `@milk{½%cup}`, `@flour{1,5%kg}` or `@eggs{two}` as numbers
+    ///
+    /// This is not part of [`Extensions::all`] or the default, so it has to be
+    /// enabled explicitly. The words are set in
+    /// [`ParseOptions::number_words`].
+    pub const LOOSE_NUMBERS: Self = Self::from_bits_retain(1 << 12);
+}
+
 impl Default for Extensions {
-    /// Enables all extensions
+    /// Enables all extensions except [`Extensions::LOOSE_NUMBERS`]
     fn default() -> Self {
         Self::all()
     }
@@ -160,7 +207,8 @@ impl Default for Extensions {
 /// A cooklang parser
 ///
 /// Instantiating this takes time and the first parse may take longer. So
-/// you may want to create only one and reuse it.
+/// you may want to create only one and reuse it. Most of the time is building
+/// the [`Converter`], which can be cached because it can be serialized.
 ///
 /// The default parser enables all extensions.
 ///
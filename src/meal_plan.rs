@@ -0,0 +1,762 @@
+//! Meal plan parser and serializer
+//!
+//! Parses a plain text plan where days are headers, meals are slots inside a
+//! day and every meal has recipe references and free-hand ingredients like the
+//! [`shopping_list`](crate::shopping_list) format.
+//!
+//! ```text
+//! -- comments work like in shopping lists
+//! == Monday ==
+//! Breakfast:
+//!   ./Breakfast/Easy Pancakes{2}
+//!   orange juice{1%l}
+//! Dinner:
+//!   ./Pasta/Carbonara{6%servings}
+//!
+//! == 2024-03-19 ==
+//! ./Leftovers
+//! ```
+//!
+//! A day is a day name (`Monday` or `mon`) or a date (`2024-03-19`). A recipe
+//! can be scaled by a multiplier (`{2}`) or to a number of servings
+//! (`{6%servings}`). Items before the first meal of a day go into an unnamed
+//! meal.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::error::{CowStr, Label, RichError};
+use crate::shopping_list::{
+    collect_lines, normalize_whitespace, parse_ingredient_line, strip_block_comments,
+    IngredientItem, ParsedLine, RecipeItem, ScaleTarget, ShoppingList, ShoppingListError,
+    ShoppingListItem,
+};
+use crate::span::Span;
+
+/// A meal plan with the meals of each day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct MealPlan {
+    /// Days in the order they appear
+    pub days: Vec<DayPlan>,
+}
+
+/// The meals of a single day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayPlan {
+    pub day: Day,
+    pub meals: Vec<Meal>,
+}
+
+/// A day of the plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Day {
+    Weekday(Weekday),
+    Date(Date),
+}
+
+/// A meal slot in a day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Meal {
+    /// Name of the slot (e.g. "Breakfast"), `None` for items without a slot
+    pub name: Option<String>,
+    pub items: Vec<MealItem>,
+}
+
+/// An item in a meal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MealItem {
+    /// A recipe reference
+    Recipe(MealRecipe),
+    /// A free-hand ingredient with a name and optional quantity
+    Ingredient(IngredientItem),
+}
+
+/// A recipe reference in a meal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MealRecipe {
+    /// Path to the recipe (e.g. "Pasta/Carbonara")
+    pub path: String,
+    /// How to scale the recipe, `None` to cook it as written
+    pub scale: Option<MealScale>,
+}
+
+/// How a recipe in a meal is scaled
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MealScale {
+    /// Scale factor, `{2}`
+    Multiplier(f64),
+    /// Target servings, `{6%servings}`
+    Servings(u32),
+}
+
+/// Day of the week
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// Full english name of the day
+    pub fn name(self) -> &'static str {
+        match self {
+            Weekday::Monday => "Monday",
+            Weekday::Tuesday => "Tuesday",
+            Weekday::Wednesday => "Wednesday",
+            Weekday::Thursday => "Thursday",
+            Weekday::Friday => "Friday",
+            Weekday::Saturday => "Saturday",
+            Weekday::Sunday => "Sunday",
+        }
+    }
+
+    /// Finds a day by its full name or the first 3 letters, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        Self::ALL.into_iter().find(|d| {
+            let full = d.name().to_lowercase();
+            name == full || name == full[..3]
+        })
+    }
+}
+
+impl std::fmt::Display for Weekday {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A calendar date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Creates a new date, `None` if it does not exist
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Day of the week of the date
+    pub fn weekday(&self) -> Weekday {
+        const T: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let y = if self.month < 3 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let d = y + y.div_euclid(4) - y.div_euclid(100)
+            + y.div_euclid(400)
+            + T[self.month as usize - 1]
+            + self.day as i32;
+        // 0 is sunday
+        Weekday::ALL[(d + 6).rem_euclid(7) as usize]
+    }
+
+    /// Parses a `YYYY-MM-DD` date
+    fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.splitn(3, '-');
+        let year = parts.next().filter(|p| p.len() == 4)?;
+        let month = parts.next().filter(|p| p.len() == 2)?;
+        let day = parts.next().filter(|p| p.len() == 2)?;
+        if ![year, month, day]
+            .iter()
+            .all(|p| p.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return None;
+        }
+        Self::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Day {
+    /// Day of the week, for dates it is calculated
+    pub fn weekday(&self) -> Weekday {
+        match self {
+            Day::Weekday(w) => *w,
+            Day::Date(d) => d.weekday(),
+        }
+    }
+}
+
+impl std::fmt::Display for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Day::Weekday(w) => w.fmt(f),
+            Day::Date(d) => d.fmt(f),
+        }
+    }
+}
+
+/// Error generated by [`parse`]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MealPlanError {
+    #[error("Error parsing meal plan: {message}")]
+    Parse { span: Span, message: String },
+    #[error("Invalid day: '{day}'")]
+    InvalidDay { span: Span, day: String },
+    #[error("Invalid scale: {message}")]
+    InvalidScale { span: Span, message: String },
+    #[error("Invalid indentation")]
+    InvalidIndentation { span: Span },
+}
+
+impl RichError for MealPlanError {
+    fn labels(&self) -> std::borrow::Cow<'_, [Label]> {
+        use crate::error::label;
+        match self {
+            MealPlanError::Parse { span, .. } => vec![label!(span)],
+            MealPlanError::InvalidDay { span, .. } => vec![label!(span, "invalid day here")],
+            MealPlanError::InvalidScale { span, .. } => {
+                vec![label!(span, "invalid scale here")]
+            }
+            MealPlanError::InvalidIndentation { span } => {
+                vec![label!(span, "unexpected indentation")]
+            }
+        }
+        .into()
+    }
+
+    fn hints(&self) -> std::borrow::Cow<'_, [CowStr]> {
+        match self {
+            MealPlanError::InvalidDay { .. } => {
+                vec!["Use a day name like `Monday` or a date like `2024-03-19`".into()]
+            }
+            MealPlanError::InvalidScale { .. } => {
+                vec!["Use a multiplier like {2} or servings like {4%servings}".into()]
+            }
+            MealPlanError::InvalidIndentation { .. } => {
+                vec!["Only the items of a meal can be indented, with 2 spaces".into()]
+            }
+            _ => vec![],
+        }
+        .into()
+    }
+
+    fn severity(&self) -> crate::error::Severity {
+        crate::error::Severity::Error
+    }
+}
+
+impl From<ShoppingListError> for MealPlanError {
+    fn from(err: ShoppingListError) -> Self {
+        match err {
+            ShoppingListError::Parse { span, message } => MealPlanError::Parse { span, message },
+            ShoppingListError::InvalidMultiplier { span, message } => {
+                MealPlanError::InvalidScale { span, message }
+            }
+            ShoppingListError::InvalidIndentation { span } => {
+                MealPlanError::InvalidIndentation { span }
+            }
+            // not generated when parsing
+            ShoppingListError::InvalidTarget { span, message, .. } => MealPlanError::InvalidScale {
+                span: span.unwrap_or(Span::new(0, 0)),
+                message,
+            },
+        }
+    }
+}
+
+/// Parse a [`MealPlan`] from the meal plan format
+pub fn parse(input: &str) -> Result<MealPlan, MealPlanError> {
+    let stripped = strip_block_comments(input)?;
+    let mut days: Vec<DayPlan> = Vec::new();
+
+    for line in collect_lines(&stripped) {
+        let span = Span::new(line.offset, line.offset + line.content.len());
+
+        if line.content.starts_with('=') {
+            if line.indent != 0 {
+                return Err(MealPlanError::InvalidIndentation { span });
+            }
+            let name = normalize_whitespace(line.content.trim_matches('='));
+            if name.is_empty() {
+                return Err(MealPlanError::Parse {
+                    span,
+                    message: "day header without a day".to_string(),
+                });
+            }
+            let day = match Date::parse(&name) {
+                Some(date) => Day::Date(date),
+                None => match Weekday::from_name(&name) {
+                    Some(weekday) => Day::Weekday(weekday),
+                    None => return Err(MealPlanError::InvalidDay { span, day: name }),
+                },
+            };
+            days.push(DayPlan {
+                day,
+                meals: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(day) = days.last_mut() else {
+            return Err(MealPlanError::Parse {
+                span,
+                message: "items must be inside a day, add a day header like `== Monday ==`"
+                    .to_string(),
+            });
+        };
+
+        if let Some(name) = line
+            .content
+            .strip_suffix(':')
+            .filter(|_| !line.content.starts_with("./"))
+        {
+            if line.indent != 0 {
+                return Err(MealPlanError::InvalidIndentation { span });
+            }
+            let name = normalize_whitespace(name);
+            if name.is_empty() {
+                return Err(MealPlanError::Parse {
+                    span,
+                    message: "meal name cannot be empty".to_string(),
+                });
+            }
+            day.meals.push(Meal {
+                name: Some(name),
+                items: Vec::new(),
+            });
+            continue;
+        }
+
+        let in_slot = day.meals.last().is_some_and(|m| m.name.is_some());
+        if !(line.indent == 0 || in_slot && line.indent == 2) {
+            return Err(MealPlanError::InvalidIndentation { span });
+        }
+        let item = if line.content.starts_with("./") {
+            MealItem::Recipe(parse_recipe_line(&line)?)
+        } else {
+            let (name, quantity) = parse_ingredient_line(&line)?;
+            MealItem::Ingredient(IngredientItem { name, quantity })
+        };
+        if day.meals.is_empty() {
+            day.meals.push(Meal {
+                name: None,
+                items: Vec::new(),
+            });
+        }
+        day.meals.last_mut().unwrap().items.push(item);
+    }
+
+    Ok(MealPlan { days })
+}
+
+fn parse_recipe_line(line: &ParsedLine<'_>) -> Result<MealRecipe, MealPlanError> {
+    let content = &line.content[2..];
+
+    let (path, scale) = match content.rfind('{') {
+        Some(brace_start) if content.ends_with('}') => {
+            let scale_str = &content[brace_start + 1..content.len() - 1];
+            let span = Span::new(
+                line.offset + 2 + brace_start + 1,
+                line.offset + 2 + content.len() - 1,
+            );
+            (&content[..brace_start], Some(parse_scale(scale_str, span)?))
+        }
+        _ => (content, None),
+    };
+
+    let path = normalize_whitespace(path);
+    if path.is_empty() {
+        return Err(MealPlanError::Parse {
+            span: Span::new(line.offset, line.offset + line.content.len()),
+            message: "recipe path cannot be empty".to_string(),
+        });
+    }
+    Ok(MealRecipe { path, scale })
+}
+
+fn parse_scale(s: &str, span: Span) -> Result<MealScale, MealPlanError> {
+    let invalid = |message: String| MealPlanError::InvalidScale { span, message };
+
+    if let Some((value, unit)) = s.split_once('%') {
+        let (value, unit) = (value.trim(), unit.trim());
+        if !matches!(unit.to_lowercase().as_str(), "servings" | "serving") {
+            return Err(invalid(format!(
+                "unknown unit '{unit}', only servings are supported"
+            )));
+        }
+        match value.parse::<u32>() {
+            Ok(servings) if servings > 0 => Ok(MealScale::Servings(servings)),
+            _ => Err(invalid(format!(
+                "'{value}' is not a valid number of servings"
+            ))),
+        }
+    } else {
+        match s.trim().parse::<f64>() {
+            Ok(m) if m.is_finite() && m > 0.0 => Ok(MealScale::Multiplier(m)),
+            _ => Err(invalid(format!("'{s}' is not a valid number"))),
+        }
+    }
+}
+
+impl MealPlan {
+    /// Converts the plan into a [`ShoppingList`]
+    ///
+    /// Every recipe appears once, when a recipe is planned more than once the
+    /// multipliers or the servings are added. A recipe planned by servings and
+    /// by multiplier appears twice. Free-hand ingredients are kept as they
+    /// are.
+    pub fn to_shopping_list(&self) -> ShoppingList {
+        let mut items = Vec::new();
+        // path and if it's scaled by servings to the index in items
+        let mut recipe_index = std::collections::HashMap::new();
+
+        for item in self.items() {
+            let recipe = match item {
+                MealItem::Recipe(recipe) => recipe,
+                MealItem::Ingredient(ingredient) => {
+                    items.push(ShoppingListItem::Ingredient(ingredient.clone()));
+                    continue;
+                }
+            };
+            let (multiplier, target) = match recipe.scale {
+                None => (None, None),
+                Some(MealScale::Multiplier(m)) => (Some(m), None),
+                Some(MealScale::Servings(s)) => (None, Some(ScaleTarget::servings(s))),
+            };
+
+            let key = (recipe.path.as_str(), target.is_some());
+            if let Some(&index) = recipe_index.get(&key) {
+                let ShoppingListItem::Recipe(existing) = &mut items[index] else {
+                    unreachable!()
+                };
+                match (&mut existing.target, target) {
+                    (Some(existing), Some(target)) => existing.value += target.value,
+                    _ => {
+                        existing.multiplier =
+                            Some(existing.multiplier.unwrap_or(1.0) + multiplier.unwrap_or(1.0))
+                    }
+                }
+            } else {
+                recipe_index.insert(key, items.len());
+                items.push(ShoppingListItem::Recipe(RecipeItem {
+                    path: recipe.path.clone(),
+                    multiplier,
+                    target,
+                    children: Vec::new(),
+                }));
+            }
+        }
+
+        ShoppingList { items }
+    }
+
+    /// Iterates over the items of all the meals in order
+    pub fn items(&self) -> impl Iterator<Item = &MealItem> {
+        self.days
+            .iter()
+            .flat_map(|d| &d.meals)
+            .flat_map(|m| &m.items)
+    }
+}
+
+/// Write a [`MealPlan`] in the meal plan format
+///
+/// An unnamed meal that is not the first of its day will be part of the
+/// previous meal when parsed again.
+pub fn write(plan: &MealPlan, mut w: impl std::io::Write) -> std::io::Result<()> {
+    for (i, day) in plan.days.iter().enumerate() {
+        if i > 0 {
+            writeln!(w)?;
+        }
+        writeln!(w, "== {} ==", day.day)?;
+        for meal in &day.meals {
+            let indent = match &meal.name {
+                Some(name) => {
+                    writeln!(w, "{name}:")?;
+                    "  "
+                }
+                None => "",
+            };
+            for item in &meal.items {
+                match item {
+                    MealItem::Recipe(recipe) => {
+                        write!(w, "{indent}./{}", recipe.path)?;
+                        match recipe.scale {
+                            Some(MealScale::Multiplier(m)) if m.fract() == 0.0 => {
+                                write!(w, "{{{}}}", m as i64)?
+                            }
+                            Some(MealScale::Multiplier(m)) => write!(w, "{{{m}}}")?,
+                            Some(MealScale::Servings(s)) => write!(w, "{{{s}%servings}}")?,
+                            None => {}
+                        }
+                        writeln!(w)?;
+                    }
+                    MealItem::Ingredient(ingredient) => {
+                        write!(w, "{indent}{}", ingredient.name)?;
+                        if let Some(q) = &ingredient.quantity {
+                            write!(w, "{{{q}}}")?;
+                        }
+                        writeln!(w)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "\
+-- week 12
+== Monday ==
+Breakfast:
+  ./Breakfast/Easy Pancakes{2}
+  orange juice{1%l}
+Dinner:
+  ./Pasta/Carbonara{6%servings}
+
+== 2024-03-19 ==
+./Leftovers
+salt
+";
+
+    fn recipe(path: &str, scale: Option<MealScale>) -> MealItem {
+        MealItem::Recipe(MealRecipe {
+            path: path.to_string(),
+            scale,
+        })
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(parse("").unwrap(), MealPlan::default());
+    }
+
+    #[test]
+    fn full_example() {
+        let plan = parse(PLAN).unwrap();
+        assert_eq!(plan.days.len(), 2);
+
+        let monday = &plan.days[0];
+        assert_eq!(monday.day, Day::Weekday(Weekday::Monday));
+        assert_eq!(monday.meals.len(), 2);
+        assert_eq!(monday.meals[0].name.as_deref(), Some("Breakfast"));
+        assert_eq!(
+            monday.meals[0].items,
+            vec![
+                recipe("Breakfast/Easy Pancakes", Some(MealScale::Multiplier(2.0))),
+                MealItem::Ingredient(IngredientItem {
+                    name: "orange juice".into(),
+                    quantity: Some("1%l".into())
+                })
+            ]
+        );
+        assert_eq!(
+            monday.meals[1].items,
+            vec![recipe("Pasta/Carbonara", Some(MealScale::Servings(6)))]
+        );
+
+        let tuesday = &plan.days[1];
+        assert_eq!(tuesday.day, Day::Date(Date::new(2024, 3, 19).unwrap()));
+        assert_eq!(tuesday.day.weekday(), Weekday::Tuesday);
+        assert_eq!(tuesday.meals.len(), 1);
+        assert_eq!(tuesday.meals[0].name, None);
+        assert_eq!(tuesday.meals[0].items.len(), 2);
+    }
+
+    #[test]
+    fn day_names() {
+        let plan = parse("= sat\n== SUNDAY ==\n=== Wed").unwrap();
+        let days: Vec<_> = plan.days.iter().map(|d| d.day).collect();
+        assert_eq!(
+            days,
+            [
+                Day::Weekday(Weekday::Saturday),
+                Day::Weekday(Weekday::Sunday),
+                Day::Weekday(Weekday::Wednesday)
+            ]
+        );
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(Date::parse("2024-02-29"), Date::new(2024, 2, 29));
+        assert!(Date::new(2023, 2, 29).is_none());
+        assert!(Date::parse("2024-2-01").is_none());
+        assert!(Date::parse("2024-13-01").is_none());
+        assert_eq!(Date::new(2000, 1, 1).unwrap().weekday(), Weekday::Saturday);
+        assert_eq!(Date::new(2024, 12, 31).unwrap().weekday(), Weekday::Tuesday);
+        assert_eq!(Date::new(2024, 3, 9).unwrap().to_string(), "2024-03-09");
+    }
+
+    #[test]
+    fn unindented_meal_items() {
+        let plan = parse("== Monday ==\nLunch:\n./Soup\nbread").unwrap();
+        assert_eq!(plan.days[0].meals.len(), 1);
+        assert_eq!(plan.days[0].meals[0].items.len(), 2);
+    }
+
+    #[test]
+    fn block_comments() {
+        let plan = parse("== Monday ==\n[- nothing\nplanned -]\n./Soup{3}").unwrap();
+        assert_eq!(
+            plan.days[0].meals[0].items,
+            vec![recipe("Soup", Some(MealScale::Multiplier(3.0)))]
+        );
+    }
+
+    #[test]
+    fn error_item_outside_day() {
+        let err = parse("./Soup").unwrap_err();
+        assert!(matches!(err, MealPlanError::Parse { .. }));
+    }
+
+    #[test]
+    fn error_invalid_day() {
+        let input = "== Someday ==";
+        let err = parse(input).unwrap_err();
+        assert_eq!(
+            err,
+            MealPlanError::InvalidDay {
+                span: Span::new(0, input.len()),
+                day: "Someday".into()
+            }
+        );
+        assert!(matches!(
+            parse("== 2023-02-29 ==").unwrap_err(),
+            MealPlanError::InvalidDay { .. }
+        ));
+    }
+
+    #[test]
+    fn error_invalid_scale() {
+        let input = "== Monday ==\n./Soup{2%people}";
+        let err = parse(input).unwrap_err();
+        let MealPlanError::InvalidScale { span, .. } = err else {
+            panic!("expected invalid scale")
+        };
+        assert_eq!(&input[span.range()], "2%people");
+
+        for scale in ["abc", "0", "-1", "1.5%servings", "0%servings"] {
+            let err = parse(&format!("== Monday ==\n./Soup{{{scale}}}")).unwrap_err();
+            assert!(matches!(err, MealPlanError::InvalidScale { .. }), "{scale}");
+        }
+    }
+
+    #[test]
+    fn error_bad_indentation() {
+        for input in [
+            "== Monday ==\n  ./Soup",
+            "== Monday ==\nLunch:\n    ./Soup",
+            "== Monday ==\n  Lunch:",
+            "  == Monday ==",
+        ] {
+            let err = parse(input).unwrap_err();
+            assert!(
+                matches!(err, MealPlanError::InvalidIndentation { .. }),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn write_roundtrip() {
+        let plan = parse(PLAN).unwrap();
+        let mut buf = Vec::new();
+        write(&plan, &mut buf).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert_eq!(
+            output,
+            "\
+== Monday ==
+Breakfast:
+  ./Breakfast/Easy Pancakes{2}
+  orange juice{1%l}
+Dinner:
+  ./Pasta/Carbonara{6%servings}
+
+== 2024-03-19 ==
+./Leftovers
+salt
+"
+        );
+        assert_eq!(parse(&output).unwrap(), plan);
+    }
+
+    #[test]
+    fn write_empty() {
+        let mut buf = Vec::new();
+        write(&MealPlan::default(), &mut buf).unwrap();
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn to_shopping_list() {
+        let plan = parse(
+            "\
+== Monday ==
+./Soup{2}
+./Carbonara{6%servings}
+bread
+== Tuesday ==
+./Soup
+./Salad
+./Carbonara{4%servings}
+./Carbonara{2}
+",
+        )
+        .unwrap();
+        let list = plan.to_shopping_list();
+        let expected = "\
+./Soup{3}
+./Carbonara{10%servings}
+bread
+./Salad
+./Carbonara{2}
+";
+        let mut buf = Vec::new();
+        crate::shopping_list::write(&list, &mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+}
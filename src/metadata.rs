@@ -70,30 +70,49 @@ impl std::fmt::Display for StdKey {
 #[error("Failed to parse '{0}' as a standard key")]
 pub struct StdKeyParseError(String);
 
+impl StdKey {
+    /// All the names that [`StdKey::from_str`] accepts
+    pub(crate) const NAMES: &[(&str, StdKey)] = &[
+        ("title", Self::Title),
+        ("description", Self::Description),
+        ("introduction", Self::Description),
+        ("tags", Self::Tags),
+        ("tag", Self::Tags),
+        ("author", Self::Author),
+        ("source", Self::Source),
+        ("servings", Self::Servings),
+        ("serves", Self::Servings),
+        ("yield", Self::Servings),
+        ("course", Self::Course),
+        ("category", Self::Course),
+        ("locale", Self::Locale),
+        ("time", Self::Time),
+        ("duration", Self::Time),
+        ("time required", Self::Time),
+        ("prep time", Self::PrepTime),
+        ("prep_time", Self::PrepTime),
+        ("cook time", Self::CookTime),
+        ("cook_time", Self::CookTime),
+        ("difficulty", Self::Difficulty),
+        ("cuisine", Self::Cuisine),
+        ("diet", Self::Diet),
+        ("image", Self::Images),
+        ("images", Self::Images),
+        ("picture", Self::Images),
+        ("pictures", Self::Images),
+    ];
+}
+
 impl FromStr for StdKey {
     type Err = StdKeyParseError;
 
     // TODO: error: alternative names are neither used as aliases nor emitted as custom tags
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let k = match s {
-            "title" => Self::Title,
-            "description" | "introduction" => Self::Description,
-            "tags" | "tag" => Self::Tags,
-            "author" => Self::Author,
-            "source" => Self::Source,
-            "servings" | "serves" | "yield" => Self::Servings,
-            "course" | "category" => Self::Course,
-            "locale" => Self::Locale,
-            "time" | "duration" | "time required" => Self::Time,
-            "prep time" | "prep_time" => Self::PrepTime,
-            "cook time" | "cook_time" => Self::CookTime,
-            "difficulty" => Self::Difficulty,
-            "cuisine" => Self::Cuisine,
-            "diet" => Self::Diet,
-            "image" | "images" | "picture" | "pictures" => Self::Images,
-            _ => return Err(StdKeyParseError(s.to_string())),
-        };
-        Ok(k)
+        Self::NAMES
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|&(_, key)| key)
+            .ok_or_else(|| StdKeyParseError(s.to_string()))
     }
 }
 
@@ -747,15 +766,22 @@ fn parse_common_time_format(s: &str) -> Option<u32> {
     Some(total_minutes)
 }
 
-fn parse_time_with_units(s: &str, converter: &Converter) -> Result<u32, ParseTimeError> {
-    let to_minutes = |value, unit| {
-        if converter.unit_count() == 0 {
-            hard_coded_time_units(value, unit)
-        } else {
-            dynamic_time_units(value, unit, converter)
-        }
-    };
+/// Converts a time value to minutes
+///
+/// If the converter has no units, the most common time units are still known.
+pub(crate) fn time_to_minutes(
+    value: f64,
+    unit: &str,
+    converter: &Converter,
+) -> Result<f64, ParseTimeError> {
+    if converter.unit_count() == 0 {
+        hard_coded_time_units(value, unit)
+    } else {
+        dynamic_time_units(value, unit, converter)
+    }
+}
 
+fn parse_time_with_units(s: &str, converter: &Converter) -> Result<u32, ParseTimeError> {
     let mut total = 0.0;
     let mut parts = s.split_whitespace();
     while let Some(part) = parts.next() {
@@ -770,7 +796,7 @@ fn parse_time_with_units(s: &str, converter: &Converter) -> Result<u32, ParseTim
             (part, next)
         };
         let number = number.parse::<f64>()?;
-        total += to_minutes(number, unit)?;
+        total += time_to_minutes(number, unit, converter)?;
     }
     Ok(total.round() as u32)
 }
@@ -882,6 +908,36 @@ impl From<&serde_yaml::Value> for MetaType {
 mod tests {
     use super::*;
 
+    #[test]
+    fn std_key_names() {
+        for &(name, key) in StdKey::NAMES {
+            assert_eq!(StdKey::from_str(name).unwrap(), key, "{name}");
+        }
+        use StdKey::*;
+        let all = [
+            Title,
+            Description,
+            Tags,
+            Author,
+            Source,
+            Course,
+            Time,
+            PrepTime,
+            CookTime,
+            Servings,
+            Difficulty,
+            Cuisine,
+            Diet,
+            Images,
+            Locale,
+        ];
+        for key in all {
+            assert!(StdKey::NAMES.contains(&(key.as_ref(), key)), "{key}");
+        }
+        assert!(StdKey::from_str("Title").is_err());
+        assert!(StdKey::from_str("calories").is_err());
+    }
+
     #[cfg(feature = "bundled_units")]
     #[test]
     fn test_parse_time_with_units() {
@@ -249,7 +249,7 @@ impl Ingredient {
     ) -> GroupedQuantity {
         let mut grouped = GroupedQuantity::default();
         for q in self.all_quantities(all_ingredients) {
-            grouped.add(q, converter);
+            grouped.add(q, Some(&self.name), converter);
         }
         let _ = grouped.fit(converter);
         grouped
@@ -312,7 +312,7 @@ impl Cookware {
     ) -> GroupedQuantity {
         let mut g = GroupedQuantity::empty();
         for q in self.all_quantities(all_cookware) {
-            g.add(q, converter);
+            g.add(q, None, converter);
         }
         let _ = g.fit(converter);
         g
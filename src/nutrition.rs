@@ -0,0 +1,691 @@
+//! Nutrition calculation for recipes
+//!
+//! A [`NutritionDb`] maps ingredient names (and synonyms) to the nutrients in
+//! 100 grams of them. With it, [`Recipe::nutrition`] computes the total and
+//! per serving nutrients of a recipe, reporting the ingredients that could not
+//! be resolved.
+//!
+//! Nutrient names are free form, use the same ones for all the entries of the
+//! database (like `kcal`, `protein`, `fat` and `carbohydrates`).
+//!
+//! ## TOML format
+//!
+//! ```toml
+//! [[food]]
+//! name = "flour"
+//! synonyms = ["all-purpose flour", "plain flour"]
+//! per_100g = { kcal = 364, protein = 10.3, fat = 1.0, carbohydrates = 76.3 }
+//!
+//! [[food]]
+//! name = "butter"
+//! per_100g = { kcal = 717, protein = 0.9, fat = 81.1, carbohydrates = 0.1 }
+//! ```
+//!
+//! ## CSV format
+//!
+//! The header must start with the `name` column, followed by an optional
+//! `synonyms` column (separated by `|`) and then one column per nutrient.
+//!
+//! ```csv
+//! name,synonyms,kcal,protein,fat,carbohydrates
+//! flour,all-purpose flour|plain flour,364,10.3,1.0,76.3
+//! butter,,717,0.9,81.1,0.1
+//! ```
+//!
+//! This module is only available with the `nutrition` [feature](crate::_features).
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::convert::{ConvertError, PhysicalQuantity, UnknownUnit};
+use crate::metadata::Servings;
+use crate::{Converter, Modifiers, Quantity, Recipe, Value};
+
+/// Amount of each nutrient
+///
+/// The meaning of the amounts depends on the context, per 100 grams in a
+/// [`FoodEntry`] and absolute in a [`NutritionReport`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Nutrients(pub IndexMap<String, f64>);
+
+impl Nutrients {
+    /// Amount of a nutrient
+    pub fn get(&self, nutrient: &str) -> Option<f64> {
+        self.0.get(nutrient).copied()
+    }
+
+    /// Iterate over all the nutrients
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), *v))
+    }
+
+    /// Returns a copy with all the amounts multiplied by `factor`
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(k, v)| (k.clone(), v * factor))
+                .collect(),
+        )
+    }
+
+    /// Adds the amounts of `other` multiplied by `factor`
+    pub fn add_scaled(&mut self, other: &Self, factor: f64) {
+        for (k, v) in &other.0 {
+            *self.0.entry(k.clone()).or_default() += v * factor;
+        }
+    }
+}
+
+/// An entry in a [`NutritionDb`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoodEntry {
+    /// Main name
+    pub name: String,
+    /// Other names the ingredient can have
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+    /// Nutrients in 100 grams
+    pub per_100g: Nutrients,
+}
+
+/// Nutrition database
+///
+/// Lookups are case insensitive by name or synonym.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "NutritionDbEntries")]
+pub struct NutritionDb {
+    #[serde(rename = "food")]
+    entries: Vec<FoodEntry>,
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+/// Serialized form of [`NutritionDb`], the index is built when converting
+#[derive(Deserialize)]
+struct NutritionDbEntries {
+    food: Vec<FoodEntry>,
+}
+
+impl TryFrom<NutritionDbEntries> for NutritionDb {
+    type Error = NutritionDbError;
+
+    fn try_from(value: NutritionDbEntries) -> Result<Self, Self::Error> {
+        Self::new(value.food)
+    }
+}
+
+impl NutritionDb {
+    /// Creates a database from a list of entries
+    ///
+    /// Fails if a name or synonym is repeated.
+    pub fn new(entries: Vec<FoodEntry>) -> Result<Self, NutritionDbError> {
+        let mut db = Self {
+            entries,
+            index: HashMap::new(),
+        };
+        db.rebuild_index()?;
+        Ok(db)
+    }
+
+    /// Parses a database in TOML format
+    pub fn from_toml(input: &str) -> Result<Self, NutritionDbError> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct File {
+            #[serde(default)]
+            food: Vec<FoodEntry>,
+        }
+        let file: File = toml::from_str(input).map_err(|e| NutritionDbError::Toml {
+            message: e.message().to_string(),
+        })?;
+        Self::new(file.food)
+    }
+
+    /// Parses a database in CSV format
+    pub fn from_csv(input: &str) -> Result<Self, NutritionDbError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(n, l)| (n + 1, l))
+            .filter(|(_, l)| !l.trim().is_empty());
+
+        let Some((line, header)) = lines.next() else {
+            return Self::new(Vec::new());
+        };
+        let header = split_csv_line(header).map_err(|message| NutritionDbError::Csv {
+            line,
+            message: message.into(),
+        })?;
+        if header.first().map(|s| s.trim()) != Some("name") {
+            return Err(NutritionDbError::Csv {
+                line,
+                message: "the first column must be 'name'".into(),
+            });
+        }
+        let has_synonyms = header.get(1).map(|s| s.trim()) == Some("synonyms");
+        let nutrients_start = if has_synonyms { 2 } else { 1 };
+        let nutrients = header[nutrients_start..]
+            .iter()
+            .map(|s| s.trim().to_string())
+            .collect::<Vec<_>>();
+
+        let mut entries = Vec::new();
+        for (line, text) in lines {
+            let err = |message: String| NutritionDbError::Csv { line, message };
+            let fields = split_csv_line(text).map_err(|m| err(m.into()))?;
+            if fields.len() != header.len() {
+                return Err(err(format!(
+                    "expected {} columns, found {}",
+                    header.len(),
+                    fields.len()
+                )));
+            }
+            let name = fields[0].trim().to_string();
+            if name.is_empty() {
+                return Err(err("empty name".into()));
+            }
+            let synonyms = if has_synonyms {
+                fields[1]
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let mut per_100g = Nutrients::default();
+            for (nutrient, value) in nutrients.iter().zip(&fields[nutrients_start..]) {
+                let value = value.trim();
+                // empty cells are unknown values
+                if value.is_empty() {
+                    continue;
+                }
+                let value = value
+                    .parse::<f64>()
+                    .map_err(|_| err(format!("invalid number for '{nutrient}': '{value}'")))?;
+                per_100g.0.insert(nutrient.clone(), value);
+            }
+            entries.push(FoodEntry {
+                name,
+                synonyms,
+                per_100g,
+            });
+        }
+        Self::new(entries)
+    }
+
+    fn rebuild_index(&mut self) -> Result<(), NutritionDbError> {
+        self.index.clear();
+        for (i, entry) in self.entries.iter().enumerate() {
+            for name in std::iter::once(&entry.name).chain(&entry.synonyms) {
+                let key = name.trim().to_lowercase();
+                if self.index.insert(key, i).is_some() {
+                    return Err(NutritionDbError::DuplicateName { name: name.clone() });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// All the entries
+    pub fn entries(&self) -> &[FoodEntry] {
+        &self.entries
+    }
+
+    /// Finds an entry by name or synonym
+    pub fn find(&self, name: &str) -> Option<&FoodEntry> {
+        self.index
+            .get(&name.trim().to_lowercase())
+            .map(|&i| &self.entries[i])
+    }
+}
+
+/// Splits a CSV line, supporting quoted fields
+fn split_csv_line(line: &str) -> Result<Vec<String>, &'static str> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                current.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if current.trim().is_empty() => {
+                current.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field");
+    }
+    fields.push(current);
+    Ok(fields)
+}
+
+/// Error loading a [`NutritionDb`]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NutritionDbError {
+    #[error("Error parsing nutrition database: {message}")]
+    Toml { message: String },
+    #[error("Error parsing nutrition database at line {line}: {message}")]
+    Csv { line: usize, message: String },
+    #[error("Duplicate name in nutrition database: '{name}'")]
+    DuplicateName { name: String },
+}
+
+/// Result of [`Recipe::nutrition`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NutritionReport {
+    /// Nutrients of the whole recipe
+    pub total: Nutrients,
+    /// Nutrients per serving, if the recipe has numeric servings
+    pub per_serving: Option<Nutrients>,
+    /// Servings used for [`Self::per_serving`]
+    pub servings: Option<u32>,
+    /// Contribution of every resolved ingredient
+    pub ingredients: Vec<IngredientNutrition>,
+    /// Ingredients (or some of their quantities) that could not be resolved
+    ///
+    /// They are not included in the totals.
+    pub unresolved: Vec<UnresolvedIngredient>,
+}
+
+impl NutritionReport {
+    /// Checks if every ingredient was resolved
+    pub fn is_complete(&self) -> bool {
+        self.unresolved.is_empty()
+    }
+}
+
+/// Nutrients of an ingredient in a [`NutritionReport`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IngredientNutrition {
+    /// Index of the ingredient definition in [`Recipe::ingredients`]
+    pub index: usize,
+    /// Name of the matched [`FoodEntry`]
+    pub food: String,
+    /// Total grams of the ingredient
+    pub grams: f64,
+    /// Nutrients of the ingredient
+    pub nutrients: Nutrients,
+}
+
+/// An ingredient that could not be resolved in a [`NutritionReport`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnresolvedIngredient {
+    /// Index of the ingredient definition in [`Recipe::ingredients`]
+    pub index: usize,
+    /// Name of the ingredient
+    pub name: String,
+    /// Why it could not be resolved
+    pub reason: UnresolvedReason,
+}
+
+/// Why an ingredient could not be resolved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum UnresolvedReason {
+    /// The ingredient is not in the database
+    NotInDatabase,
+    /// The ingredient is a reference to another recipe
+    RecipeReference,
+    /// The ingredient has no quantity
+    NoQuantity,
+    /// The quantity is text, like `a pinch`
+    TextValue { value: String },
+    /// The quantity has no unit, like `2`, and the ingredient has no piece
+    /// mass
+    NoUnit { quantity: Quantity },
+    /// The unit is not known by the [`Converter`] and the ingredient has no
+    /// mass for it
+    UnknownUnit { unit: String },
+    /// The unit is not a mass unit and the ingredient has no density to
+    /// convert it to grams
+    NotMass {
+        unit: String,
+        physical_quantity: PhysicalQuantity,
+    },
+    /// The quantity could not be converted to grams for another reason
+    Conversion { error: String },
+}
+
+impl std::fmt::Display for UnresolvedReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnresolvedReason::NotInDatabase => write!(f, "not in the nutrition database"),
+            UnresolvedReason::RecipeReference => write!(f, "references a recipe"),
+            UnresolvedReason::NoQuantity => write!(f, "no quantity"),
+            UnresolvedReason::TextValue { value } => write!(f, "text value: '{value}'"),
+            UnresolvedReason::NoUnit { quantity } => write!(f, "no unit: '{quantity}'"),
+            UnresolvedReason::UnknownUnit { unit } => write!(f, "unknown unit: '{unit}'"),
+            UnresolvedReason::NotMass {
+                unit,
+                physical_quantity,
+            } => write!(f, "'{unit}' is a {physical_quantity} unit, not mass"),
+            UnresolvedReason::Conversion { error } => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Recipe {
+    /// Computes the nutrients of the recipe
+    ///
+    /// All the quantities of an ingredient and its references are converted
+    /// to grams and looked up in the database. Ranges use the midpoint.
+    ///
+    /// Volumes, quantities without unit and units like `clove` can be
+    /// converted to grams with the
+    /// [ingredient conversions](Converter::ingredient_conversions) of the
+    /// converter.
+    ///
+    /// Per serving values are only calculated if the `servings` metadata key
+    /// is a number.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter};
+    /// # use cooklang::nutrition::NutritionDb;
+    /// let db = NutritionDb::from_csv("name,kcal,protein\nflour,364,10\nbutter,717,1").unwrap();
+    /// let parser = CooklangParser::default();
+    /// let recipe = parser
+    ///     .parse("---\nservings: 2\n---\n@flour{200%g} @butter{1%oz} @salt{}")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let report = recipe.nutrition(&db, parser.converter());
+    /// assert_eq!(report.total.get("protein").map(f64::round), Some(20.0));
+    /// assert_eq!(report.per_serving.unwrap().get("protein").map(f64::round), Some(10.0));
+    /// assert_eq!(report.unresolved[0].name, "salt");
+    /// ```
+    pub fn nutrition(&self, db: &NutritionDb, converter: &Converter) -> NutritionReport {
+        let mut report = NutritionReport::default();
+
+        for (index, ingredient) in self.ingredients.iter().enumerate() {
+            if !ingredient.relation.is_definition() {
+                continue;
+            }
+            let mut unresolved = |reason| {
+                report.unresolved.push(UnresolvedIngredient {
+                    index,
+                    name: ingredient.name.clone(),
+                    reason,
+                })
+            };
+
+            if ingredient.reference.is_some() || ingredient.modifiers().contains(Modifiers::RECIPE)
+            {
+                unresolved(UnresolvedReason::RecipeReference);
+                continue;
+            }
+            let food = db
+                .find(&ingredient.name)
+                .or_else(|| ingredient.alias.as_deref().and_then(|alias| db.find(alias)));
+            let Some(food) = food else {
+                unresolved(UnresolvedReason::NotInDatabase);
+                continue;
+            };
+
+            let mut grams = None;
+            let mut has_quantity = false;
+            for q in ingredient.all_quantities(&self.ingredients) {
+                has_quantity = true;
+                match quantity_grams(&ingredient.name, q, converter) {
+                    Ok(g) => *grams.get_or_insert(0.0) += g,
+                    Err(reason) => unresolved(reason),
+                }
+            }
+            if !has_quantity {
+                unresolved(UnresolvedReason::NoQuantity);
+            }
+
+            if let Some(grams) = grams {
+                let nutrients = food.per_100g.scaled(grams / 100.0);
+                report.total.add_scaled(&nutrients, 1.0);
+                report.ingredients.push(IngredientNutrition {
+                    index,
+                    food: food.name.clone(),
+                    grams,
+                    nutrients,
+                });
+            }
+        }
+
+        report.servings = self
+            .metadata
+            .servings()
+            .as_ref()
+            .and_then(Servings::as_number)
+            .filter(|&s| s > 0);
+        report.per_serving = report.servings.map(|s| report.total.scaled(1.0 / s as f64));
+        report
+    }
+}
+
+fn quantity_grams(
+    ingredient: &str,
+    q: &Quantity,
+    converter: &Converter,
+) -> Result<f64, UnresolvedReason> {
+    let value = match q.value() {
+        Value::Number(n) => n.value(),
+        Value::Range { start, end } => (start.value() + end.value()) / 2.0,
+        Value::Text(t) => return Err(UnresolvedReason::TextValue { value: t.clone() }),
+    };
+    let mut grams = Quantity::new(Value::from(value), q.unit().map(String::from));
+    match grams.convert("g", Some(ingredient), converter) {
+        Ok(()) => Ok(match grams.value() {
+            Value::Number(g) => g.value(),
+            _ => unreachable!("converting a number gives a number"),
+        }),
+        Err(ConvertError::NoUnit(_)) => Err(UnresolvedReason::NoUnit {
+            quantity: q.clone(),
+        }),
+        Err(ConvertError::TextValue(value)) => Err(UnresolvedReason::TextValue { value }),
+        Err(ConvertError::MixedQuantities { from, .. }) => Err(UnresolvedReason::NotMass {
+            unit: q.unit().unwrap_or_default().to_string(),
+            physical_quantity: from,
+        }),
+        Err(ConvertError::UnknownUnit(UnknownUnit(unit))) => {
+            Err(UnresolvedReason::UnknownUnit { unit })
+        }
+        Err(
+            e @ (ConvertError::BestUnitNotFound { .. }
+            | ConvertError::UnknownRegion(_)
+            | ConvertError::InvalidCompound(_)),
+        ) => Err(UnresolvedReason::Conversion {
+            error: e.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CooklangParser, Extensions};
+
+    const DB: &str = r#"
+[[food]]
+name = "flour"
+synonyms = ["Plain Flour"]
+per_100g = { kcal = 364, protein = 10 }
+
+[[food]]
+name = "milk"
+per_100g = { kcal = 42, protein = 3.4 }
+
+[[food]]
+name = "egg"
+synonyms = ["eggs"]
+per_100g = { kcal = 143, protein = 12.6 }
+"#;
+
+    fn nutrition(input: &str) -> NutritionReport {
+        let converter = Converter::bundled();
+        let recipe = CooklangParser::new(Extensions::all(), converter.clone())
+            .parse(input)
+            .into_output()
+            .unwrap();
+        let db = NutritionDb::from_toml(DB).unwrap();
+        recipe.nutrition(&db, &converter)
+    }
+
+    #[test]
+    fn toml_db() {
+        let db = NutritionDb::from_toml(DB).unwrap();
+        assert_eq!(db.entries().len(), 3);
+        assert_eq!(db.find("plain flour").unwrap().name, "flour");
+        assert_eq!(db.find(" Eggs ").unwrap().name, "egg");
+        assert!(db.find("sugar").is_none());
+    }
+
+    #[test]
+    fn serde_db() {
+        let db = NutritionDb::from_toml(DB).unwrap();
+        let json = serde_json::to_string(&db).unwrap();
+        let de: NutritionDb = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, db);
+        assert_eq!(de.find("eggs").unwrap().name, "egg");
+
+        let dup =
+            r#"{"food": [{"name": "rice", "per_100g": {}}, {"name": "Rice", "per_100g": {}}]}"#;
+        assert!(serde_json::from_str::<NutritionDb>(dup).is_err());
+    }
+
+    #[test]
+    fn csv_db() {
+        let db = NutritionDb::from_csv(
+            "name,synonyms,kcal,protein\n\"flour, plain\",white flour|ap flour,364,10\n\nsalt,,0,\n",
+        )
+        .unwrap();
+        let flour = db.find("ap flour").unwrap();
+        assert_eq!(flour.name, "flour, plain");
+        assert_eq!(flour.per_100g.get("kcal"), Some(364.0));
+        let salt = db.find("salt").unwrap();
+        assert_eq!(salt.per_100g.get("protein"), None);
+
+        let db = NutritionDb::from_csv("name,kcal\nrice,130").unwrap();
+        assert_eq!(db.find("rice").unwrap().per_100g.get("kcal"), Some(130.0));
+    }
+
+    #[test]
+    fn db_errors() {
+        assert_eq!(
+            NutritionDb::from_csv("name,kcal\nrice,abc"),
+            Err(NutritionDbError::Csv {
+                line: 2,
+                message: "invalid number for 'kcal': 'abc'".into()
+            })
+        );
+        assert!(matches!(
+            NutritionDb::from_csv("kcal,name\n1,rice"),
+            Err(NutritionDbError::Csv { line: 1, .. })
+        ));
+        assert!(matches!(
+            NutritionDb::from_csv("name,kcal\nrice,1,2"),
+            Err(NutritionDbError::Csv { line: 2, .. })
+        ));
+        assert_eq!(
+            NutritionDb::from_csv("name,synonyms,kcal\nrice,Rice,1"),
+            Err(NutritionDbError::DuplicateName {
+                name: "Rice".into()
+            })
+        );
+        assert!(matches!(
+            NutritionDb::from_toml("[[food]]\nname = 1"),
+            Err(NutritionDbError::Toml { .. })
+        ));
+    }
+
+    #[test]
+    fn totals_and_servings() {
+        let report =
+            nutrition("---\nservings: 4\n---\n@flour{1%kg} @&flour{200%g} and @plain flour{4%oz}");
+        assert!(report.is_complete());
+        assert_eq!(report.servings, Some(4));
+        assert_eq!(report.ingredients.len(), 2);
+        assert_eq!(report.ingredients[0].grams, 1200.0);
+        let grams = 1200.0 + 4.0 * 28.349_523_125;
+        let kcal = report.total.get("kcal").unwrap();
+        assert!((kcal - grams * 3.64).abs() < 1e-6);
+        let per_serving = report.per_serving.unwrap().get("kcal").unwrap();
+        assert!((per_serving - kcal / 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ranges_use_midpoint() {
+        let report = nutrition("@flour{100-300%g}");
+        assert_eq!(report.ingredients[0].grams, 200.0);
+        assert_eq!(report.per_serving, None);
+    }
+
+    #[test]
+    fn unresolved() {
+        let report = nutrition(
+            "@flour{a handful} @milk{1%cup} @egg{2} @sugar{100%g} @flour{2%bags} @egg{} @@bread{}",
+        );
+        let reasons = report
+            .unresolved
+            .iter()
+            .map(|u| (u.name.as_str(), u.reason.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reasons,
+            vec![
+                (
+                    "flour",
+                    UnresolvedReason::TextValue {
+                        value: "a handful".into()
+                    }
+                ),
+                (
+                    "milk",
+                    UnresolvedReason::NotMass {
+                        unit: "cup".into(),
+                        physical_quantity: PhysicalQuantity::Volume
+                    }
+                ),
+                (
+                    "egg",
+                    UnresolvedReason::NoUnit {
+                        quantity: Quantity::new(Value::from(2.0), None)
+                    }
+                ),
+                ("sugar", UnresolvedReason::NotInDatabase),
+                (
+                    "flour",
+                    UnresolvedReason::UnknownUnit {
+                        unit: "bags".into()
+                    }
+                ),
+                ("egg", UnresolvedReason::NoQuantity),
+                ("bread", UnresolvedReason::RecipeReference),
+            ]
+        );
+        assert!(report.ingredients.is_empty());
+        assert!(report.total.0.is_empty());
+    }
+    #[test]
+    fn no_grams_unit() {
+        let converter = Converter::empty();
+        let recipe = CooklangParser::new(Extensions::all(), converter.clone())
+            .parse("@flour{100%g}")
+            .into_output()
+            .unwrap();
+        let db = NutritionDb::from_toml(DB).unwrap();
+        let report = recipe.nutrition(&db, &converter);
+        assert_eq!(
+            report.unresolved[0].reason,
+            UnresolvedReason::UnknownUnit { unit: "g".into() }
+        );
+    }
+}
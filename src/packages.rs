@@ -0,0 +1,809 @@
+//! Round shopping quantities up to the packages sold in shops
+//!
+//! A [`PackageDb`] lists the package sizes an ingredient, or every ingredient
+//! of an [aisle](crate::aisle) category, is sold in, with optional prices.
+//! With it, [`IngredientList::packages`] and
+//! [`CategorizedIngredientList::packages`] compute how many packages of each
+//! size to buy and what is left over, reporting the ingredients that could
+//! not be resolved.
+//!
+//! ## TOML format
+//!
+//! Every entry has either a `name` (with optional `synonyms`) or a
+//! `category`. Ingredient entries are used before category entries. Sizes
+//! are quantities like in a recipe, `value%unit` or just a number.
+//!
+//! ```toml
+//! [[package]]
+//! name = "flour"
+//! synonyms = ["all-purpose flour"]
+//! sizes = [{ size = "1%kg", price = 1.2 }, { size = "500%g", price = 0.8 }]
+//!
+//! [[package]]
+//! name = "eggs"
+//! sizes = [{ size = "6" }, { size = "12" }]
+//!
+//! [[package]]
+//! category = "dairy"
+//! sizes = [{ size = "1%l" }]
+//! ```
+//!
+//! This module is only available with the `packages` [feature](crate::_features).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::aisle::AisleConf;
+use crate::ingredient_list::{CategorizedIngredientList, IngredientList};
+use crate::{Converter, GroupedQuantity, Quantity, Value};
+
+/// A size an ingredient is sold in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageSize {
+    /// Quantity in the package, like `1%kg` or `12`
+    pub size: String,
+    /// Price of the package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+}
+
+impl PackageSize {
+    /// Value and unit of the size, `None` if it is not a positive number
+    pub fn quantity(&self) -> Option<(f64, Option<&str>)> {
+        let (value, unit) = match self.size.split_once('%') {
+            Some((_, unit)) if unit.trim().is_empty() => return None,
+            Some((value, unit)) => (value, Some(unit.trim())),
+            None => (self.size.as_str(), None),
+        };
+        let value = value.trim().parse::<f64>().ok()?;
+        (value.is_finite() && value > 0.0).then_some((value, unit))
+    }
+}
+
+/// An entry in a [`PackageDb`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageEntry {
+    /// Name of the ingredient
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Other names the ingredient can have
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+    /// Aisle category, for entries of all the ingredients in it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Sizes the ingredient is sold in
+    pub sizes: Vec<PackageSize>,
+}
+
+/// Package database
+///
+/// Lookups are case insensitive by name, synonym or category.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PackageDbEntries")]
+pub struct PackageDb {
+    #[serde(rename = "package")]
+    entries: Vec<PackageEntry>,
+    #[serde(skip)]
+    names: HashMap<String, usize>,
+    #[serde(skip)]
+    categories: HashMap<String, usize>,
+}
+
+/// Serialized form of [`PackageDb`], the index is built when converting
+#[derive(Deserialize)]
+struct PackageDbEntries {
+    package: Vec<PackageEntry>,
+}
+
+impl TryFrom<PackageDbEntries> for PackageDb {
+    type Error = PackageDbError;
+
+    fn try_from(value: PackageDbEntries) -> Result<Self, Self::Error> {
+        Self::new(value.package)
+    }
+}
+
+impl PackageDb {
+    /// Creates a database from a list of entries
+    ///
+    /// Fails if an entry has no valid sizes, has both or none of a name and
+    /// a category, or if a name, synonym or category is repeated.
+    pub fn new(entries: Vec<PackageEntry>) -> Result<Self, PackageDbError> {
+        let mut db = Self {
+            entries,
+            names: HashMap::new(),
+            categories: HashMap::new(),
+        };
+        db.rebuild_index()?;
+        Ok(db)
+    }
+
+    /// Parses a database in TOML format
+    pub fn from_toml(input: &str) -> Result<Self, PackageDbError> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct File {
+            #[serde(default)]
+            package: Vec<PackageEntry>,
+        }
+        let file: File = toml::from_str(input).map_err(|e| PackageDbError::Toml {
+            message: e.message().to_string(),
+        })?;
+        Self::new(file.package)
+    }
+
+    fn rebuild_index(&mut self) -> Result<(), PackageDbError> {
+        self.names.clear();
+        self.categories.clear();
+        for (i, entry) in self.entries.iter().enumerate() {
+            let (index, keys) = match (&entry.name, &entry.category) {
+                (Some(name), None) => (
+                    &mut self.names,
+                    std::iter::once(name).chain(&entry.synonyms).collect(),
+                ),
+                (None, Some(category)) if entry.synonyms.is_empty() => {
+                    (&mut self.categories, vec![category])
+                }
+                _ => return Err(PackageDbError::InvalidEntry { index: i }),
+            };
+            if entry.sizes.is_empty() {
+                return Err(PackageDbError::InvalidEntry { index: i });
+            }
+            if let Some(size) = entry.sizes.iter().find(|s| s.quantity().is_none()) {
+                return Err(PackageDbError::InvalidSize {
+                    size: size.size.clone(),
+                });
+            }
+            for key in keys {
+                if index.insert(key.trim().to_lowercase(), i).is_some() {
+                    return Err(PackageDbError::DuplicateName { name: key.clone() });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// All the entries
+    pub fn entries(&self) -> &[PackageEntry] {
+        &self.entries
+    }
+
+    /// Finds the entry of an ingredient by name or synonym, or else by its
+    /// category
+    pub fn find(&self, name: &str, category: Option<&str>) -> Option<&PackageEntry> {
+        self.names
+            .get(&name.trim().to_lowercase())
+            .or_else(|| self.categories.get(&category?.trim().to_lowercase()))
+            .map(|&i| &self.entries[i])
+    }
+}
+
+/// Error loading a [`PackageDb`]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PackageDbError {
+    #[error("Error parsing package database: {message}")]
+    Toml { message: String },
+    #[error("Package entry {index} needs sizes and either a name or a category")]
+    InvalidEntry { index: usize },
+    #[error("Invalid package size: '{size}'")]
+    InvalidSize { size: String },
+    #[error("Duplicate name in package database: '{name}'")]
+    DuplicateName { name: String },
+}
+
+/// How to choose the packages of an ingredient
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PackageStrategy {
+    /// The fewest packages
+    #[default]
+    Fewest,
+    /// The lowest price
+    ///
+    /// Sizes without a price are not used, unless no size has a price, then
+    /// it is the same as [`PackageStrategy::Fewest`].
+    Cheapest,
+}
+
+/// Result of [`IngredientList::packages`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PackageReport {
+    /// Packages of every resolved ingredient
+    pub ingredients: Vec<PackagedIngredient>,
+    /// Ingredients (or some of their quantities) that could not be resolved
+    ///
+    /// They are not included in the packages.
+    pub unresolved: Vec<UnresolvedIngredient>,
+}
+
+impl PackageReport {
+    /// Checks if every ingredient was resolved
+    pub fn is_complete(&self) -> bool {
+        self.unresolved.is_empty()
+    }
+
+    /// Total price, if every package has a price
+    pub fn price(&self) -> Option<f64> {
+        self.ingredients.iter().map(|i| i.price).sum()
+    }
+}
+
+/// Packages to buy of an ingredient in a [`PackageReport`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackagedIngredient {
+    /// Name of the ingredient in the list
+    pub name: String,
+    /// Category of the ingredient, if known
+    pub category: Option<String>,
+    /// Required quantity, in the unit of the ingredient
+    pub required: Quantity,
+    /// Packages to buy, largest first
+    pub packages: Vec<PackageCount>,
+    /// Quantity in all the packages, in the unit of [`Self::required`]
+    pub total: Quantity,
+    /// What is left after using [`Self::required`]
+    pub leftover: Quantity,
+    /// Price of the packages, if all of them have a price
+    pub price: Option<f64>,
+}
+
+impl std::fmt::Display for PackagedIngredient {
+    /// Like `flour: 2 × 1 kg, 1 × 500 g (300 g left over)`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:", self.name)?;
+        for (i, p) in self.packages.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            let size = p.size.size.replace('%', " ");
+            write!(f, "{sep}{} × {size}", p.count)?;
+        }
+        if matches!(self.leftover.value(), Value::Number(n) if n.value() > 0.0) {
+            write!(f, " ({} left over)", self.leftover)?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of packages of a size
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageCount {
+    pub size: PackageSize,
+    pub count: u32,
+}
+
+/// An ingredient that could not be resolved in a [`PackageReport`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnresolvedIngredient {
+    /// Name of the ingredient in the list
+    pub name: String,
+    /// Why it could not be resolved
+    pub reason: UnresolvedReason,
+}
+
+/// Why an ingredient could not be resolved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum UnresolvedReason {
+    /// The ingredient is not in the database
+    NotInDatabase,
+    /// The ingredient has no quantity
+    NoQuantity,
+    /// The quantity is text, like `a pinch`
+    TextValue { value: String },
+    /// The quantity can't be converted to the unit of the ingredient or of
+    /// the packages
+    IncompatibleUnit { quantity: Quantity },
+}
+
+impl std::fmt::Display for UnresolvedReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnresolvedReason::NotInDatabase => write!(f, "not in the package database"),
+            UnresolvedReason::NoQuantity => write!(f, "no quantity"),
+            UnresolvedReason::TextValue { value } => write!(f, "text value: '{value}'"),
+            UnresolvedReason::IncompatibleUnit { quantity } => {
+                write!(f, "'{quantity}' can't be converted to the package sizes")
+            }
+        }
+    }
+}
+
+impl IngredientList {
+    /// Computes the packages to buy for every ingredient
+    ///
+    /// All the quantities of an ingredient are added in the unit of the
+    /// first one the package sizes can be converted to, using the
+    /// [ingredient conversions](Converter::ingredient_conversions) of the
+    /// converter if needed. Ranges use the end. The package sizes are
+    /// converted to the same unit and the combination with the fewest
+    /// packages or the lowest price that covers the quantity is chosen.
+    ///
+    /// If an `aisle` is given, it is used to find the category of the
+    /// ingredients for the category entries of the database.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, ingredient_list::IngredientList};
+    /// # use cooklang::packages::{PackageDb, PackageStrategy};
+    /// let db = PackageDb::from_toml(r#"
+    /// [[package]]
+    /// name = "flour"
+    /// sizes = [{ size = "1%kg" }, { size = "500%g" }]
+    ///
+    /// [[package]]
+    /// name = "eggs"
+    /// sizes = [{ size = "6" }, { size = "12" }]
+    /// "#).unwrap();
+    /// let parser = CooklangParser::default();
+    /// let recipe = parser
+    ///     .parse("@flour{800%g} @eggs{14} @salt{}")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let list = IngredientList::from_recipe(&recipe, parser.converter(), false);
+    /// let report = list.packages(&db, None, PackageStrategy::Fewest, parser.converter());
+    /// assert_eq!(
+    ///     report.ingredients[0].to_string(),
+    ///     "flour: 1 × 1 kg (200 g left over)"
+    /// );
+    /// assert_eq!(report.ingredients[1].to_string(), "eggs: 1 × 12, 1 × 6 (4 left over)");
+    /// assert_eq!(report.unresolved[0].name, "salt");
+    /// ```
+    pub fn packages(
+        &self,
+        db: &PackageDb,
+        aisle: Option<&AisleConf>,
+        strategy: PackageStrategy,
+        converter: &Converter,
+    ) -> PackageReport {
+        let info = aisle.map(|a| a.ingredients_info()).unwrap_or_default();
+        let mut report = PackageReport::default();
+        for (name, quantity) in self.iter() {
+            let category = info.get(&name.to_lowercase()).map(|i| i.category);
+            package_ingredient(
+                &mut report,
+                name,
+                quantity,
+                category,
+                db,
+                strategy,
+                converter,
+            );
+        }
+        report
+    }
+}
+
+impl CategorizedIngredientList {
+    /// Computes the packages to buy for every ingredient
+    ///
+    /// Like [`IngredientList::packages`], using the categories of the list.
+    pub fn packages(
+        &self,
+        db: &PackageDb,
+        strategy: PackageStrategy,
+        converter: &Converter,
+    ) -> PackageReport {
+        let mut report = PackageReport::default();
+        let lists = self
+            .categories
+            .iter()
+            .map(|(category, list)| (Some(category.as_str()), list))
+            .chain(std::iter::once((None, &self.other)));
+        for (category, list) in lists {
+            for (name, quantity) in list.iter() {
+                package_ingredient(
+                    &mut report,
+                    name,
+                    quantity,
+                    category,
+                    db,
+                    strategy,
+                    converter,
+                );
+            }
+        }
+        report
+    }
+}
+
+fn package_ingredient(
+    report: &mut PackageReport,
+    name: &str,
+    quantity: &GroupedQuantity,
+    category: Option<&str>,
+    db: &PackageDb,
+    strategy: PackageStrategy,
+    converter: &Converter,
+) {
+    let mut unresolved = |reason| {
+        report.unresolved.push(UnresolvedIngredient {
+            name: name.to_string(),
+            reason,
+        })
+    };
+
+    let Some(entry) = db.find(name, category) else {
+        unresolved(UnresolvedReason::NotInDatabase);
+        return;
+    };
+    if quantity.is_empty() {
+        unresolved(UnresolvedReason::NoQuantity);
+        return;
+    }
+
+    let mut values = Vec::new();
+    for q in quantity.iter() {
+        match q.value() {
+            Value::Number(n) => values.push((n.value(), q)),
+            Value::Range { end, .. } => values.push((end.value(), q)),
+            Value::Text(t) => unresolved(UnresolvedReason::TextValue { value: t.clone() }),
+        }
+    }
+    let priced =
+        strategy == PackageStrategy::Cheapest && entry.sizes.iter().any(|s| s.price.is_some());
+    let sizes_in = |unit: Option<&str>| -> Vec<(&PackageSize, f64)> {
+        entry
+            .sizes
+            .iter()
+            .filter(|s| !priced || s.price.is_some())
+            .filter_map(|s| {
+                let (value, size_unit) = s.quantity()?;
+                Some((s, convert(name, value, size_unit, unit, converter)?))
+            })
+            .filter(|(_, amount)| *amount > 0.0)
+            .collect()
+    };
+
+    // work in the unit of the first quantity the packages can be converted to
+    let Some((unit, mut sizes)) = values
+        .iter()
+        .map(|(_, q)| (q.unit(), sizes_in(q.unit())))
+        .find(|(_, sizes)| !sizes.is_empty())
+    else {
+        for (_, q) in values {
+            unresolved(UnresolvedReason::IncompatibleUnit {
+                quantity: q.clone(),
+            });
+        }
+        return;
+    };
+    let mut required = 0.0;
+    for (value, q) in values {
+        match convert(name, value, q.unit(), unit, converter) {
+            Some(v) => required += v,
+            None => unresolved(UnresolvedReason::IncompatibleUnit {
+                quantity: q.clone(),
+            }),
+        }
+    }
+    // try the best sizes first, so good solutions prune the rest sooner
+    match strategy {
+        PackageStrategy::Fewest => sizes.sort_by(|a, b| b.1.total_cmp(&a.1)),
+        PackageStrategy::Cheapest => sizes.sort_by(|a, b| {
+            let unit_price =
+                |(s, amount): &(&PackageSize, f64)| s.price.unwrap_or_default() / amount;
+            unit_price(a)
+                .total_cmp(&unit_price(b))
+                .then(b.1.total_cmp(&a.1))
+        }),
+    }
+
+    let mut best = None;
+    let mut counts = vec![0; sizes.len()];
+    choose_packages(
+        &sizes,
+        0,
+        required,
+        [0.0; 3],
+        strategy,
+        &mut counts,
+        &mut best,
+    );
+    let Some((_, counts)) = best else {
+        return;
+    };
+    let mut chosen = sizes
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, c)| *c > 0)
+        .collect::<Vec<_>>();
+    chosen.sort_by(|a, b| b.0 .1.total_cmp(&a.0 .1));
+
+    let total: f64 = chosen.iter().map(|((_, a), c)| a * *c as f64).sum();
+    let price = chosen
+        .iter()
+        .map(|((s, _), c)| s.price.map(|p| p * *c as f64))
+        .sum();
+    report.ingredients.push(PackagedIngredient {
+        name: name.to_string(),
+        category: category.map(String::from),
+        required: Quantity::new(required.into(), unit.map(String::from)),
+        packages: chosen
+            .iter()
+            .map(|((s, _), count)| PackageCount {
+                size: (*s).clone(),
+                count: *count,
+            })
+            .collect(),
+        total: Quantity::new(total.into(), unit.map(String::from)),
+        leftover: Quantity::new((total - required).max(0.0).into(), unit.map(String::from)),
+        price,
+    });
+}
+
+/// Converts a value of an ingredient to another unit
+fn convert(
+    ingredient: &str,
+    value: f64,
+    from: Option<&str>,
+    to: Option<&str>,
+    converter: &Converter,
+) -> Option<f64> {
+    if from == to {
+        return Some(value);
+    }
+    let mut q = Quantity::new(Value::from(value), from.map(String::from));
+    q.convert(to?, Some(ingredient), converter).ok()?;
+    match q.value() {
+        Value::Number(n) => Some(n.value()),
+        _ => None,
+    }
+}
+
+/// Finds the best counts for `sizes`
+///
+/// A branch and bound search: every size but the last is tried from enough
+/// to cover the remaining quantity down to 0, the last one covers the rest.
+/// `used` is the packages, total and price of the sizes before `i`, and a
+/// branch is dropped when even a lower bound of its result is not better
+/// than the best one found.
+fn choose_packages(
+    sizes: &[(&PackageSize, f64)],
+    i: usize,
+    remaining: f64,
+    used: [f64; 3],
+    strategy: PackageStrategy,
+    counts: &mut [u32],
+    best: &mut Option<([f64; 3], Vec<u32>)>,
+) {
+    let key = |[packages, total, price]: [f64; 3]| match strategy {
+        PackageStrategy::Fewest => [packages, total, price],
+        PackageStrategy::Cheapest => [price, total, packages],
+    };
+    let is_better = |key: &[f64; 3], best: &Option<([f64; 3], Vec<u32>)>| {
+        best.as_ref().is_none_or(|(best, _)| {
+            key.iter()
+                .zip(best)
+                .map(|(a, b)| a.total_cmp(b))
+                .find(|o| o.is_ne())
+                .is_some_and(|o| o.is_lt())
+        })
+    };
+    // avoid an extra package because of float errors
+    let needed = |amount: f64| (remaining / amount - 1e-9).ceil().max(0.0) as u32;
+
+    let rest = &sizes[i..];
+    let largest = rest.iter().map(|(_, a)| *a).fold(0.0, f64::max);
+    let unit_price = rest
+        .iter()
+        .map(|(s, a)| s.price.unwrap_or_default() / a)
+        .fold(f64::INFINITY, f64::min);
+    let bound = [
+        used[0] + needed(largest) as f64,
+        used[1] + remaining,
+        used[2] + remaining * unit_price,
+    ];
+    if !is_better(&key(bound), best) {
+        return;
+    }
+
+    let (size, amount) = sizes[i];
+    let with = |count: u32| {
+        let count = count as f64;
+        [
+            used[0] + count,
+            used[1] + amount * count,
+            used[2] + size.price.unwrap_or_default() * count,
+        ]
+    };
+    if i + 1 == sizes.len() {
+        counts[i] = needed(amount);
+        let key = key(with(counts[i]));
+        if is_better(&key, best) {
+            *best = Some((key, counts.to_vec()));
+        }
+        return;
+    }
+    for count in (0..=needed(amount)).rev() {
+        counts[i] = count;
+        let left = (remaining - amount * count as f64).max(0.0);
+        choose_packages(sizes, i + 1, left, with(count), strategy, counts, best);
+    }
+    counts[i] = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CooklangParser, Extensions};
+
+    const DB: &str = r#"
+[[package]]
+name = "flour"
+synonyms = ["Plain Flour"]
+sizes = [{ size = "1%kg", price = 2.0 }, { size = "500%g", price = 0.8 }]
+
+[[package]]
+name = "eggs"
+sizes = [{ size = "6", price = 1.5 }, { size = "12", price = 2.5 }]
+
+[[package]]
+category = "dairy"
+sizes = [{ size = "1%l" }]
+"#;
+
+    fn list(input: &str) -> IngredientList {
+        let converter = Converter::bundled();
+        let recipe = CooklangParser::new(Extensions::all(), converter.clone())
+            .parse(input)
+            .into_output()
+            .unwrap();
+        IngredientList::from_recipe(&recipe, &converter, false)
+    }
+
+    fn packages(input: &str, strategy: PackageStrategy) -> PackageReport {
+        let db = PackageDb::from_toml(DB).unwrap();
+        list(input).packages(&db, None, strategy, &Converter::bundled())
+    }
+
+    #[test]
+    fn database_errors() {
+        assert!(matches!(
+            PackageDb::from_toml("[[package]]\nname = 1"),
+            Err(PackageDbError::Toml { .. })
+        ));
+        let err = PackageDb::from_toml(
+            "[[package]]\nname = 'a'\ncategory = 'b'\nsizes = [{ size = '1' }]",
+        );
+        assert_eq!(err, Err(PackageDbError::InvalidEntry { index: 0 }));
+        let err = PackageDb::from_toml("[[package]]\nname = 'a'\nsizes = []");
+        assert_eq!(err, Err(PackageDbError::InvalidEntry { index: 0 }));
+        let err = PackageDb::from_toml("[[package]]\nname = 'a'\nsizes = [{ size = '0%kg' }]");
+        assert_eq!(
+            err,
+            Err(PackageDbError::InvalidSize {
+                size: "0%kg".into()
+            })
+        );
+        let err = PackageDb::from_toml(
+            "[[package]]\nname = 'a'\nsizes = [{ size = '1' }]\n\
+             [[package]]\nname = 'b'\nsynonyms = ['A']\nsizes = [{ size = '1' }]",
+        );
+        assert_eq!(err, Err(PackageDbError::DuplicateName { name: "A".into() }));
+    }
+
+    #[test]
+    fn fewest_and_cheapest() {
+        let report = packages("@plain flour{800%g} @eggs{14}", PackageStrategy::Fewest);
+        assert!(report.is_complete());
+        assert_eq!(
+            report.ingredients[0].to_string(),
+            "plain flour: 1 × 1 kg (200 g left over)"
+        );
+        assert_eq!(
+            report.ingredients[1].to_string(),
+            "eggs: 1 × 12, 1 × 6 (4 left over)"
+        );
+        assert_eq!(report.price(), Some(6.0));
+
+        let report = packages("@plain flour{800%g} @eggs{14}", PackageStrategy::Cheapest);
+        assert_eq!(
+            report.ingredients[0].to_string(),
+            "plain flour: 2 × 500 g (200 g left over)"
+        );
+        assert_eq!(report.ingredients[0].total.to_string(), "1000 g");
+        assert_eq!(
+            report.ingredients[1].to_string(),
+            "eggs: 1 × 12, 1 × 6 (4 left over)"
+        );
+        assert_eq!(report.price(), Some(5.6));
+
+        // exact amounts have no leftovers
+        let report = packages("@eggs{12}", PackageStrategy::Fewest);
+        assert_eq!(report.ingredients[0].to_string(), "eggs: 1 × 12");
+    }
+
+    #[test]
+    fn units_and_unresolved() {
+        let report = packages(
+            "@flour{200%g} @&flour{1%cup} @eggs{a few} @salt{1%tsp} @water",
+            PackageStrategy::Fewest,
+        );
+        assert_eq!(report.ingredients.len(), 1);
+        assert_eq!(report.ingredients[0].required.to_string(), "200 g");
+        let reasons: Vec<_> = report
+            .unresolved
+            .iter()
+            .map(|u| format!("{}: {}", u.name, u.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                "flour: '1 c' can't be converted to the package sizes",
+                "eggs: text value: 'a few'",
+                "salt: not in the package database",
+                "water: not in the package database",
+            ]
+        );
+    }
+
+    #[test]
+    fn categories() {
+        let converter = Converter::bundled();
+        let db = PackageDb::from_toml(DB).unwrap();
+        let aisle = crate::aisle::parse("[dairy]\nmilk\ncream\n").unwrap();
+        let list = list("@milk{1500%ml} @cream{200%g} @butter{}");
+
+        let report = list.packages(&db, Some(&aisle), PackageStrategy::Fewest, &converter);
+        assert_eq!(
+            report.ingredients[0].to_string(),
+            "milk: 2 × 1 l (0.5 l left over)"
+        );
+        assert_eq!(report.ingredients[0].category.as_deref(), Some("dairy"));
+        assert!(matches!(
+            report.unresolved[0].reason,
+            UnresolvedReason::IncompatibleUnit { .. }
+        ));
+        assert_eq!(report.unresolved[1].reason, UnresolvedReason::NotInDatabase);
+
+        let categorized =
+            list.categorize(&aisle)
+                .packages(&db, PackageStrategy::Fewest, &converter);
+        assert_eq!(categorized.ingredients, report.ingredients);
+        assert_eq!(categorized.unresolved, report.unresolved);
+    }
+
+    #[test]
+    fn large_amounts() {
+        let db = PackageDb::from_toml(
+            r#"
+[[package]]
+name = "flour"
+sizes = [
+    { size = "1%kg", price = 2.0 },
+    { size = "500%g", price = 0.99 },
+    { size = "250%g", price = 0.6 },
+    { size = "100%g", price = 0.25 },
+]
+"#,
+        )
+        .unwrap();
+        let converter = Converter::bundled();
+        let list = list("@flour{80.35%kg}");
+
+        let report = list.packages(&db, None, PackageStrategy::Fewest, &converter);
+        assert_eq!(
+            report.ingredients[0].to_string(),
+            "flour: 80 × 1 kg, 1 × 500 g (0.15 kg left over)"
+        );
+        let report = list.packages(&db, None, PackageStrategy::Cheapest, &converter);
+        assert_eq!(
+            report.ingredients[0].to_string(),
+            "flour: 160 × 500 g, 1 × 250 g, 1 × 100 g"
+        );
+    }
+
+    #[test]
+    fn serde_database() {
+        let db = PackageDb::from_toml(DB).unwrap();
+        let json = serde_json::to_string(&db).unwrap();
+        let de: PackageDb = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, db);
+        assert_eq!(de.find("plain flour", None).unwrap().sizes.len(), 2);
+        assert!(de.find("milk", Some("Dairy")).is_some());
+
+        let json = r#"{"package": [{"name": "a", "sizes": []}]}"#;
+        assert!(serde_json::from_str::<PackageDb>(json).is_err());
+    }
+}
@@ -1,15 +1,15 @@
 //! Quantity model
 
-use std::{collections::HashMap, fmt::Display, sync::Arc};
+use std::{cmp::Ordering, collections::HashMap, fmt::Display, sync::Arc};
 
-use enum_map::EnumMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[cfg(feature = "ts")]
 use tsify::Tsify;
 
-use crate::convert::{ConvertError, Converter, PhysicalQuantity, Unit};
+use crate::convert::{ConvertError, Converter, PhysicalQuantity, QuantityMap, Unit};
+use crate::rational;
 
 /// A quantity used in components
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -19,6 +19,9 @@ pub struct Quantity {
     pub(crate) value: Value,
     pub(crate) unit: Option<String>,
     pub(crate) scalable: bool,
+    /// Value before [humanizing](Quantity::humanize), if it was rounded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) exact: Option<Box<Value>>,
 }
 
 impl PartialEq for Quantity {
@@ -47,6 +50,17 @@ impl Value {
     pub fn is_text(&self) -> bool {
         matches!(self, Value::Text(_))
     }
+
+    /// Returns `true` if any number in the value is an approximation
+    ///
+    /// See [`Number::is_approx`].
+    pub fn is_approx(&self) -> bool {
+        match self {
+            Value::Number(n) => n.is_approx(),
+            Value::Range { start, end } => start.is_approx() || end.is_approx(),
+            Value::Text(_) => false,
+        }
+    }
 }
 
 /// Wrapper for different kinds of numbers
@@ -69,6 +83,39 @@ impl Value {
 /// assert_eq!(num.to_string(), "2 1/2");
 /// assert_eq!(num.value(), 2.501);
 /// ```
+///
+/// # Exact arithmetic
+///
+/// Adding, subtracting, multiplying and dividing [values](Value), so also
+/// scaling recipes and adding quantities, is exact for rationals. Only
+/// [`Number::Fraction`]s without error store the rational, for other
+/// numbers it's guessed from the `f64`, with some limits:
+///
+/// - Only denominators up to 1 000 000 and values below 2<sup>53</sup> are
+///   guessed. Other numbers use `f64` arithmetic.
+/// - A float a few ULPs away from a rational with a small denominator is
+///   taken as that rational, so `0.333333333333333` is `1/3`.
+/// - The result is an `f64` again unless both numbers are fractions.
+///
+/// ```
+/// # use cooklang::quantity::{Number, TryAdd, Value};
+/// let third = Value::from(1.0 / 3.0);
+/// let one = third.try_add(&third).unwrap().try_add(&third).unwrap();
+/// assert_eq!(one, Value::from(1.0));
+///
+/// // 1/3 is guessed, not what was written
+/// let almost = Value::from(0.333_333_333_333_333);
+/// let sum = almost.try_add(&almost).unwrap().try_add(&almost).unwrap();
+/// assert_eq!(sum, Value::from(1.0));
+///
+/// // too big denominator, float error
+/// let small = Value::from(1.0 / 1_000_003.0);
+/// let mut sum = Value::from(0.0);
+/// for _ in 0..1_000_003 {
+///     sum = sum.try_add(&small).unwrap();
+/// }
+/// assert_ne!(sum, Value::from(1.0));
+/// ```
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(tag = "type", content = "value", rename_all = "camelCase")]
 pub enum Number {
@@ -117,6 +164,23 @@ impl Number {
             } => whole as f64 + err + num as f64 / den as f64,
         }
     }
+
+    /// Difference between the true value and the displayed one
+    ///
+    /// It's always `0` for [`Number::Regular`].
+    pub fn err(self) -> f64 {
+        match self {
+            Number::Regular(_) => 0.0,
+            Number::Fraction { err, .. } => err,
+        }
+    }
+
+    /// Returns `true` if the displayed number is not the true value
+    ///
+    /// Useful to show something like "about 1/2 cup".
+    pub fn is_approx(self) -> bool {
+        self.err().abs() > 1e-10
+    }
 }
 
 impl PartialEq for Number {
@@ -132,6 +196,7 @@ impl Quantity {
             value,
             unit,
             scalable: false,
+            exact: None,
         }
     }
 
@@ -155,6 +220,58 @@ impl Quantity {
         &mut self.value
     }
 
+    /// Value before [humanizing](Quantity::humanize)
+    ///
+    /// It's the same as [`Quantity::value`] if the quantity was not rounded.
+    pub fn exact_value(&self) -> &Value {
+        self.exact.as_deref().unwrap_or(&self.value)
+    }
+
+    /// Returns `true` if the value is an approximation
+    ///
+    /// That is, if it was rounded when [humanizing](Quantity::humanize) or
+    /// if it's an approximated [fraction](Number::is_approx).
+    pub fn is_approx(&self) -> bool {
+        self.exact.is_some() || self.value.is_approx()
+    }
+
+    /// Difference between the displayed value and the true value
+    ///
+    /// It's in the unit of the quantity, and the largest of both ends for
+    /// ranges. Positive when the displayed value is bigger. It's `0` if the
+    /// quantity is not an [approximation](Quantity::is_approx).
+    pub fn approx_error(&self) -> f64 {
+        fn error(shown: Number, exact: Number) -> f64 {
+            shown.value() - shown.err() - exact.value()
+        }
+        match (&self.value, self.exact_value()) {
+            (Value::Number(shown), Value::Number(exact)) => error(*shown, *exact),
+            (
+                Value::Range { start, end },
+                Value::Range {
+                    start: exact_start,
+                    end: exact_end,
+                },
+            ) => {
+                let start = error(*start, *exact_start);
+                let end = error(*end, *exact_end);
+                if start.abs() > end.abs() {
+                    start
+                } else {
+                    end
+                }
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Undo the rounding of [humanizing](Quantity::humanize)
+    pub(crate) fn restore_exact(&mut self) {
+        if let Some(exact) = self.exact.take() {
+            self.value = *exact;
+        }
+    }
+
     /// Get the corresponding [`Unit`]
     ///
     /// This can return `None` if there is no unit or if it's not in the
@@ -329,18 +446,75 @@ impl Quantity {
 
         // 2. Convert rhs to the unit of the first one if needed
         let mut rhs = rhs.clone();
+        rhs.restore_exact();
         if let Some(to) = convert_to {
-            rhs.convert(&to, converter)?;
+            rhs.convert(&to, None, converter)?;
         };
 
         // 3. Sum values
-        let value = self.value.try_add(&rhs.value)?;
+        let value = self.exact_value().try_add(&rhs.value)?;
 
         // 4. New quantity
         let qty = Quantity::new(value, self.unit.clone());
 
         Ok(qty)
     }
+
+    /// Try subtracting two quantities
+    ///
+    /// The result is in the unit of `self` and can be negative.
+    pub fn try_sub(&self, rhs: &Self, converter: &Converter) -> Result<Self, QuantityAddError> {
+        let rhs = self.to_common_unit(rhs, converter)?;
+        let value = self.exact_value().try_sub(&rhs.value)?;
+        Ok(Quantity::new(value, self.unit.clone()))
+    }
+
+    /// Multiply the quantity by a factor
+    ///
+    /// Unlike [scaling](crate::Recipe::scale), this does not care about
+    /// [`Self::scalable`] and keeps the unit.
+    pub fn mul(&self, factor: f64) -> Result<Self, TextValueError> {
+        Ok(Self {
+            value: self.exact_value().mul(factor)?,
+            exact: None,
+            ..self.clone()
+        })
+    }
+
+    /// Divide the quantity by a divisor
+    ///
+    /// See [`Self::mul`].
+    pub fn div(&self, divisor: f64) -> Result<Self, TextValueError> {
+        Ok(Self {
+            value: self.exact_value().div(divisor)?,
+            exact: None,
+            ..self.clone()
+        })
+    }
+
+    /// Try comparing two quantities
+    ///
+    /// `rhs` is converted to the unit of `self` first. Like
+    /// [`PartialOrd`], this returns `None` when there is no order, which is
+    /// the case of overlapping ranges. See [`Value::try_cmp`].
+    pub fn try_cmp(
+        &self,
+        rhs: &Self,
+        converter: &Converter,
+    ) -> Result<Option<Ordering>, QuantityAddError> {
+        let rhs = self.to_common_unit(rhs, converter)?;
+        Ok(self.exact_value().try_cmp(&rhs.value)?)
+    }
+
+    fn to_common_unit(&self, rhs: &Self, converter: &Converter) -> Result<Self, QuantityAddError> {
+        let convert_to = self.compatible_unit(rhs, converter)?;
+        let mut rhs = rhs.clone();
+        rhs.restore_exact();
+        if let Some(to) = convert_to {
+            rhs.convert(&to, None, converter)?;
+        }
+        Ok(rhs)
+    }
 }
 
 pub trait TryAdd: Sized {
@@ -359,16 +533,16 @@ impl TryAdd for Value {
 
     fn try_add(&self, rhs: &Self) -> Result<Value, TextValueError> {
         let val = match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => Value::Number((a.value() + b.value()).into()),
+            (Value::Number(a), Value::Number(b)) => Value::Number(rational::add(*a, *b)),
             (Value::Number(n), Value::Range { start, end })
             | (Value::Range { start, end }, Value::Number(n)) => Value::Range {
-                start: (start.value() + n.value()).into(),
-                end: (end.value() + n.value()).into(),
+                start: rational::add(*start, *n),
+                end: rational::add(*end, *n),
             },
             (Value::Range { start: s1, end: e1 }, Value::Range { start: s2, end: e2 }) => {
                 Value::Range {
-                    start: (s1.value() + s2.value()).into(),
-                    end: (e1.value() + e2.value()).into(),
+                    start: rational::add(*s1, *s2),
+                    end: rational::add(*e1, *e2),
                 }
             }
             (t @ Value::Text(_), _) | (_, t @ Value::Text(_)) => {
@@ -380,6 +554,109 @@ impl TryAdd for Value {
     }
 }
 
+impl Value {
+    /// Try subtracting two values
+    ///
+    /// Ranges are subtracted as intervals, so the result contains all the
+    /// possible differences: `3-4 - 1-2` is `1-3`.
+    ///
+    /// See [exact arithmetic](Number#exact-arithmetic).
+    pub fn try_sub(&self, rhs: &Self) -> Result<Value, TextValueError> {
+        let val = match (self, rhs) {
+            (Value::Number(a), Value::Number(b)) => Value::Number(rational::sub(*a, *b)),
+            (Value::Number(n), Value::Range { start, end }) => Value::Range {
+                start: rational::sub(*n, *end),
+                end: rational::sub(*n, *start),
+            },
+            (Value::Range { start, end }, Value::Number(n)) => Value::Range {
+                start: rational::sub(*start, *n),
+                end: rational::sub(*end, *n),
+            },
+            (Value::Range { start: s1, end: e1 }, Value::Range { start: s2, end: e2 }) => {
+                Value::Range {
+                    start: rational::sub(*s1, *e2),
+                    end: rational::sub(*e1, *s2),
+                }
+            }
+            (t @ Value::Text(_), _) | (_, t @ Value::Text(_)) => {
+                return Err(TextValueError(t.to_owned()));
+            }
+        };
+
+        Ok(val)
+    }
+
+    /// Multiply the value by a factor
+    ///
+    /// A negative factor swaps the start and end of ranges.
+    ///
+    /// See [exact arithmetic](Number#exact-arithmetic).
+    pub fn mul(&self, factor: f64) -> Result<Value, TextValueError> {
+        self.map_numbers(factor < 0.0, |n| rational::mul(n, factor))
+    }
+
+    /// Divide the value by a divisor
+    ///
+    /// Like with `f64`, dividing by `0` results in infinite values.
+    ///
+    /// See [exact arithmetic](Number#exact-arithmetic).
+    pub fn div(&self, divisor: f64) -> Result<Value, TextValueError> {
+        self.map_numbers(divisor < 0.0, |n| rational::div(n, divisor))
+    }
+
+    fn map_numbers(
+        &self,
+        swap: bool,
+        f: impl Fn(Number) -> Number,
+    ) -> Result<Value, TextValueError> {
+        let val = match self {
+            Value::Number(n) => Value::Number(f(*n)),
+            Value::Range { start, end } => {
+                let (start, end) = (f(*start), f(*end));
+                if swap {
+                    Value::Range {
+                        start: end,
+                        end: start,
+                    }
+                } else {
+                    Value::Range { start, end }
+                }
+            }
+            Value::Text(_) => return Err(TextValueError(self.clone())),
+        };
+        Ok(val)
+    }
+
+    /// Try comparing two values
+    ///
+    /// A number is like a range where the start and the end are the same. A
+    /// range is less than another if it ends before the other starts and
+    /// equal if both start and end at the same numbers. Otherwise, they
+    /// overlap and there is no order, so this returns `None`.
+    pub fn try_cmp(&self, rhs: &Self) -> Result<Option<Ordering>, TextValueError> {
+        let bounds = |v: &Value| match v {
+            Value::Number(n) => Ok((*n, *n)),
+            Value::Range { start, end } => Ok((*start, *end)),
+            Value::Text(_) => Err(TextValueError(v.clone())),
+        };
+        let (s1, e1) = bounds(self)?;
+        let (s2, e2) = bounds(rhs)?;
+
+        let ord = if rational::cmp(e1, s2) == Some(Ordering::Less) {
+            Some(Ordering::Less)
+        } else if rational::cmp(s1, e2) == Some(Ordering::Greater) {
+            Some(Ordering::Greater)
+        } else if rational::cmp(s1, s2) == Some(Ordering::Equal)
+            && rational::cmp(e1, e2) == Some(Ordering::Equal)
+        {
+            Some(Ordering::Equal)
+        } else {
+            None
+        };
+        Ok(ord)
+    }
+}
+
 /// Group of quantities
 ///
 /// This support efficient adding of new quantities, merging other groups..
@@ -397,7 +674,7 @@ impl TryAdd for Value {
 #[cfg_attr(feature = "ts", tsify(into_wasm_abi, from_wasm_abi))]
 pub struct GroupedQuantity {
     /// known units
-    known: EnumMap<PhysicalQuantity, Option<Quantity>>,
+    known: QuantityMap<Option<Quantity>>,
     /// unknown units
     unknown: HashMap<String, Quantity>,
     /// no units
@@ -413,7 +690,22 @@ impl GroupedQuantity {
     }
 
     /// Add a new quantity to the group
-    pub fn add(&mut self, q: &Quantity, converter: &Converter) {
+    ///
+    /// With the name of the `ingredient`, when the converter has
+    /// [conversions](Converter::ingredient_conversions) for it, quantities
+    /// that can't be added otherwise are converted to mass and added
+    /// together. For example, `1 cup` and `100 g` of flour.
+    ///
+    /// A single quantity is never converted, only when it can be added to
+    /// others.
+    pub fn add(&mut self, q: &Quantity, ingredient: Option<&str>, converter: &Converter) {
+        self.add_quantity(q, converter);
+        if let Some(ingredient) = ingredient {
+            self.unify_mass(ingredient, converter);
+        }
+    }
+
+    fn add_quantity(&mut self, q: &Quantity, converter: &Converter) {
         macro_rules! add {
             ($stored:expr, $quantity:ident, $converter:expr, $other:expr) => {
                 match $stored.try_add($quantity, $converter) {
@@ -431,6 +723,15 @@ impl GroupedQuantity {
             return;
         }
         if q.unit.is_none() {
+            // with a unitless unit configured, add it to the known ones if
+            // possible
+            if let Some(unit) = converter.unitless_unit() {
+                if let Some(stored) = self.known.entry(unit.physical_quantity) {
+                    let q = &Quantity::new(q.value.clone(), Some(unit.symbol().to_string()));
+                    add!(stored, q, converter, self.other);
+                    return;
+                }
+            }
             if let Some(stored) = &mut self.no_unit {
                 add!(stored, q, converter, self.other);
             } else {
@@ -443,10 +744,20 @@ impl GroupedQuantity {
         let info = q.unit_info(converter);
         match info {
             Some(unit) => {
-                if let Some(stored) = &mut self.known[unit.physical_quantity] {
+                let stored = self.known.entry(unit.physical_quantity);
+                if let Some(stored) = stored {
                     add!(stored, q, converter, self.other);
                 } else {
-                    self.known[unit.physical_quantity] = Some(q.clone());
+                    *stored = Some(q.clone());
+                    // now the quantities without unit may be added
+                    if converter
+                        .unitless_unit()
+                        .is_some_and(|u| u.physical_quantity == unit.physical_quantity)
+                    {
+                        if let Some(no_unit) = self.no_unit.take() {
+                            self.add_quantity(&no_unit, converter);
+                        }
+                    }
                 }
             }
             None => {
@@ -460,9 +771,153 @@ impl GroupedQuantity {
     }
 
     /// Merge the group with another one
-    pub fn merge(&mut self, other: &Self, converter: &Converter) {
+    ///
+    /// See [`Self::add`] for the `ingredient`.
+    pub fn merge(&mut self, other: &Self, ingredient: Option<&str>, converter: &Converter) {
+        for q in other.iter() {
+            self.add_quantity(q, converter)
+        }
+        if let Some(ingredient) = ingredient {
+            self.unify_mass(ingredient, converter);
+        }
+    }
+
+    /// Subtract a quantity from the group
+    ///
+    /// This is the same as [adding](Self::add) the negated quantity, so if
+    /// there is nothing to subtract it from, the group ends up with a
+    /// negative quantity.
+    pub fn try_sub(&mut self, q: &Quantity, converter: &Converter) -> Result<(), TextValueError> {
+        self.add_quantity(&q.mul(-1.0)?, converter);
+        Ok(())
+    }
+
+    /// Subtract all the quantities of another group
+    ///
+    /// See [`Self::try_sub`].
+    pub fn try_sub_group(
+        &mut self,
+        other: &Self,
+        converter: &Converter,
+    ) -> Result<(), TextValueError> {
         for q in other.iter() {
-            self.add(q, converter)
+            self.try_sub(q, converter)?;
+        }
+        Ok(())
+    }
+
+    /// Multiply all the quantities by a factor
+    ///
+    /// Text values can't be multiplied and are left as they are.
+    pub fn mul(&mut self, factor: f64) {
+        self.map_values(|v| v.mul(factor));
+    }
+
+    /// Divide all the quantities by a divisor
+    ///
+    /// Text values can't be divided and are left as they are.
+    pub fn div(&mut self, divisor: f64) {
+        self.map_values(|v| v.div(divisor));
+    }
+
+    fn map_values(&mut self, f: impl Fn(&Value) -> Result<Value, TextValueError>) {
+        for q in self
+            .known
+            .values_mut()
+            .filter_map(|q| q.as_mut())
+            .chain(self.unknown.values_mut())
+            .chain(self.other.iter_mut())
+            .chain(self.no_unit.iter_mut())
+        {
+            if let Ok(value) = f(q.exact_value()) {
+                q.value = value;
+                q.exact = None;
+            }
+        }
+    }
+
+    /// Try comparing two groups
+    ///
+    /// Each quantity is compared with the one it could be added to in the
+    /// other group, or with zero if there is none. The groups are ordered
+    /// only if all of these comparisons agree, for example, `1 kg, 2 cans`
+    /// is greater than `500 g`. Otherwise, this returns `None`.
+    pub fn try_cmp(
+        &self,
+        rhs: &Self,
+        converter: &Converter,
+    ) -> Result<Option<Ordering>, QuantityAddError> {
+        let zero = Value::from(0.0);
+        let mut used = vec![false; rhs.len()];
+        let mut ords = Vec::with_capacity(self.len() + rhs.len());
+
+        for a in self.iter() {
+            let pair = rhs
+                .iter()
+                .enumerate()
+                .find(|(i, b)| !used[*i] && a.compatible_unit(b, converter).is_ok());
+            match pair {
+                Some((i, b)) => {
+                    used[i] = true;
+                    ords.push(a.try_cmp(b, converter)?);
+                }
+                None => ords.push(a.value.try_cmp(&zero)?),
+            }
+        }
+        for (b, _) in rhs.iter().zip(&used).filter(|(_, used)| !**used) {
+            ords.push(zero.try_cmp(&b.value)?);
+        }
+
+        let mut acc = Ordering::Equal;
+        for ord in ords {
+            match (acc, ord) {
+                (_, None) => return Ok(None),
+                (_, Some(Ordering::Equal)) => {}
+                (Ordering::Equal, Some(ord)) => acc = ord,
+                (acc, Some(ord)) if acc == ord => {}
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(acc))
+    }
+
+    fn unify_mass(&mut self, ingredient: &str, converter: &Converter) {
+        if self.len() < 2 || converter.ingredient_conversions(ingredient).is_none() {
+            return;
+        }
+
+        let mut candidates = Vec::new();
+        if let Some(q) = self.known.entry(PhysicalQuantity::Volume).take() {
+            candidates.push(q);
+        }
+        candidates.extend(self.unknown.drain().map(|(_, q)| q));
+        candidates.extend(self.no_unit.take());
+
+        // converted quantities next to the original ones
+        let mut masses = Vec::new();
+        let mut rest = Vec::new();
+        for q in candidates {
+            let mut mass = q.clone();
+            match mass.convert("g", Some(ingredient), converter) {
+                Ok(()) => masses.push((q, mass)),
+                Err(_) => rest.push(q),
+            }
+        }
+
+        // only worth it if at least 2 quantities end up together
+        let has_mass = self.known.entry(PhysicalQuantity::Mass).is_some();
+        if masses.len() + (has_mass as usize) < 2 {
+            rest.extend(masses.drain(..).map(|(original, _)| original));
+        }
+        let converted = !masses.is_empty();
+        let masses = masses.into_iter().map(|(_, mass)| mass).collect::<Vec<_>>();
+        for q in masses.iter().chain(&rest) {
+            self.add_quantity(q, converter);
+        }
+        if converted {
+            if let Some(mass) = self.known.entry(PhysicalQuantity::Mass) {
+                let _ = mass.fit(converter);
+            }
         }
     }
 
@@ -481,6 +936,23 @@ impl GroupedQuantity {
         Ok(())
     }
 
+    /// Calls [`Quantity::humanize`] on all the quantities
+    ///
+    /// The true values are kept, so the group can still be added to others
+    /// without accumulating rounding errors.
+    pub fn humanize(&mut self, converter: &Converter) {
+        for q in self
+            .known
+            .values_mut()
+            .filter_map(|q| q.as_mut())
+            .chain(self.unknown.values_mut())
+            .chain(self.other.iter_mut())
+            .chain(self.no_unit.iter_mut())
+        {
+            q.humanize(converter);
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.iter().next().is_none()
     }
@@ -510,8 +982,8 @@ impl GroupedQuantity {
             .into_values()
             .flatten()
             .chain(self.unknown.into_values())
-            .chain(self.other.into_iter())
-            .chain(self.no_unit.into_iter())
+            .chain(self.other)
+            .chain(self.no_unit)
         {
             v.push(q)
         }
@@ -543,6 +1015,48 @@ where
     Ok(())
 }
 
+/// A quantity split in many units, like `1 h 30 min` or `1 lb 4 oz`
+///
+/// Created with [`Quantity::to_compound`]. The parts are ordered from the
+/// biggest to the smallest unit.
+///
+/// The display impl separates the parts with spaces.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(Tsify))]
+#[cfg_attr(feature = "ts", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct CompoundQuantity(Vec<Quantity>);
+
+impl CompoundQuantity {
+    pub(crate) fn new(parts: Vec<Quantity>) -> Self {
+        debug_assert!(!parts.is_empty(), "empty compound quantity");
+        Self(parts)
+    }
+
+    /// Get the parts of the compound
+    pub fn parts(&self) -> &[Quantity] {
+        &self.0
+    }
+
+    /// Turn the compound into its parts
+    pub fn into_parts(self) -> Vec<Quantity> {
+        self.0
+    }
+}
+
+impl Display for CompoundQuantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = self.0.iter();
+        if let Some(first) = parts.next() {
+            first.fmt(f)?;
+        }
+        for q in parts {
+            f.write_str(" ")?;
+            q.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
 // All the fractions stuff
 
 static TABLE: std::sync::LazyLock<FractionLookupTable> =
@@ -0,0 +1,349 @@
+//! Exact arithmetic for numbers that are integers or fractions
+//!
+//! Quantities store `f64`, but most values in a recipe are integers, simple
+//! fractions or short decimals. Operating with them as rationals avoids
+//! accumulating floating point errors, like `2.0000001 eggs` after adding
+//! thirds many times. The rational is guessed from the `f64` when needed, see
+//! [exact arithmetic](crate::quantity::Number#exact-arithmetic) for the limits.
+
+use std::cmp::Ordering;
+
+use crate::quantity::Number;
+
+/// Max denominator when recovering a rational from a float
+const MAX_DEN: i64 = 1_000_000;
+
+/// A reduced fraction, `den` is always positive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Rational {
+    num: i64,
+    den: i64,
+}
+
+impl Rational {
+    pub fn new(num: i64, den: i64) -> Option<Self> {
+        if den == 0 {
+            return None;
+        }
+        let g = gcd(num.unsigned_abs(), den.unsigned_abs()).max(1) as i64;
+        let sign = den.signum();
+        Some(Self {
+            num: num / g * sign,
+            den: den / g * sign,
+        })
+    }
+
+    fn from_i128(num: i128, den: i128) -> Option<Self> {
+        let g = gcd_u128(num.unsigned_abs(), den.unsigned_abs()).max(1) as i128;
+        Self::new((num / g).try_into().ok()?, (den / g).try_into().ok()?)
+    }
+
+    /// Recovers the rational a float most likely represents
+    ///
+    /// Only rationals with a small denominator that are equal to the float
+    /// up to rounding errors are recovered.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        const MAX_EXACT: f64 = (1u64 << 53) as f64;
+        if !value.is_finite() || value.abs() >= MAX_EXACT {
+            return None;
+        }
+        if value.fract() == 0.0 {
+            return Self::new(value as i64, 1);
+        }
+
+        // continued fraction expansion until a convergent is close enough
+        let x = value.abs();
+        let tolerance = 8.0 * f64::EPSILON * x.max(1.0);
+        let (mut h0, mut h1) = (0i64, 1i64);
+        let (mut k0, mut k1) = (1i64, 0i64);
+        let mut rest = x;
+        for _ in 0..64 {
+            let a = rest.floor();
+            if a >= MAX_EXACT {
+                return None;
+            }
+            let a = a as i64;
+            let h2 = a.checked_mul(h1)?.checked_add(h0)?;
+            let k2 = a.checked_mul(k1)?.checked_add(k0)?;
+            if k2 > MAX_DEN {
+                return None;
+            }
+            if (x - h2 as f64 / k2 as f64).abs() <= tolerance {
+                return Self::new(h2 * value.signum() as i64, k2);
+            }
+            (h0, h1, k0, k1) = (h1, h2, k1, k2);
+            let frac = rest - rest.floor();
+            if frac == 0.0 {
+                return None;
+            }
+            rest = 1.0 / frac;
+        }
+        None
+    }
+
+    /// The exact value of a number, if it's a rational
+    pub fn from_number(n: Number) -> Option<Self> {
+        if let Number::Fraction {
+            whole,
+            num,
+            den,
+            err,
+        } = n
+        {
+            if err == 0.0 {
+                return Self::new(whole as i64 * den as i64 + num as i64, den as i64);
+            }
+        }
+        Self::from_f64(n.value())
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let num = self.num as i128 * rhs.den as i128 + rhs.num as i128 * self.den as i128;
+        let den = self.den as i128 * rhs.den as i128;
+        Self::from_i128(num, den)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let num = self.num as i128 * rhs.num as i128;
+        let den = self.den as i128 * rhs.den as i128;
+        Self::from_i128(num, den)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            num: self.num.checked_neg()?,
+            den: self.den,
+        })
+    }
+
+    pub fn checked_recip(self) -> Option<Self> {
+        Self::new(self.den, self.num)
+    }
+
+    /// Turns the rational into a number
+    ///
+    /// Integers are [`Number::Regular`]. With `fraction`, other positive
+    /// values are an exact [`Number::Fraction`].
+    pub fn into_number(self, fraction: bool) -> Number {
+        if self.den == 1 {
+            return Number::Regular(self.num as f64);
+        }
+        let whole = self.num / self.den;
+        if fraction && self.num > 0 {
+            if let (Ok(whole), Ok(num), Ok(den)) = (
+                u32::try_from(whole),
+                u32::try_from(self.num % self.den),
+                u32::try_from(self.den),
+            ) {
+                return Number::Fraction {
+                    whole,
+                    num,
+                    den,
+                    err: 0.0,
+                };
+            }
+        }
+        Number::Regular(self.to_f64())
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Returns `true` if the number was written as a fraction
+fn is_exact_fraction(n: Number) -> bool {
+    matches!(n, Number::Fraction { den, err, .. } if err == 0.0 && den > 1)
+}
+
+/// Adds 2 numbers, exactly if both are rationals
+///
+/// The result is only a fraction if both numbers are fractions.
+pub(crate) fn add(a: Number, b: Number) -> Number {
+    let fraction = is_exact_fraction(a) && is_exact_fraction(b);
+    Rational::from_number(a)
+        .zip(Rational::from_number(b))
+        .and_then(|(a, b)| a.checked_add(b))
+        .map(|r| r.into_number(fraction))
+        .unwrap_or_else(|| Number::Regular(a.value() + b.value()))
+}
+
+/// Multiplies a number by a factor, exactly if both are rationals
+pub(crate) fn mul(n: Number, factor: f64) -> Number {
+    Rational::from_number(n)
+        .zip(Rational::from_f64(factor))
+        .and_then(|(a, b)| a.checked_mul(b))
+        .map(|r| r.into_number(is_exact_fraction(n)))
+        .unwrap_or_else(|| Number::Regular(n.value() * factor))
+}
+
+/// Subtracts 2 numbers, exactly if both are rationals
+///
+/// Like [`add`], the result is only a fraction if both numbers are fractions.
+pub(crate) fn sub(a: Number, b: Number) -> Number {
+    let fraction = is_exact_fraction(a) && is_exact_fraction(b);
+    Rational::from_number(a)
+        .zip(Rational::from_number(b).and_then(Rational::checked_neg))
+        .and_then(|(a, b)| a.checked_add(b))
+        .map(|r| r.into_number(fraction))
+        .unwrap_or_else(|| Number::Regular(a.value() - b.value()))
+}
+
+/// Divides a number by a divisor, exactly if both are rationals
+pub(crate) fn div(n: Number, divisor: f64) -> Number {
+    Rational::from_number(n)
+        .zip(Rational::from_f64(divisor).and_then(Rational::checked_recip))
+        .and_then(|(a, b)| a.checked_mul(b))
+        .map(|r| r.into_number(is_exact_fraction(n)))
+        .unwrap_or_else(|| Number::Regular(n.value() / divisor))
+}
+
+/// Compares 2 numbers, exactly if both are rationals
+///
+/// Rounding errors from unit conversions are ignored this way, so `1000 g`
+/// converted to `lb` and back is still equal to `1 kg`.
+pub(crate) fn cmp(a: Number, b: Number) -> Option<Ordering> {
+    match Rational::from_number(a).zip(Rational::from_number(b)) {
+        Some((a, b)) => Some((a.num as i128 * b.den as i128).cmp(&(b.num as i128 * a.den as i128))),
+        None => a.value().partial_cmp(&b.value()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn r(num: i64, den: i64) -> Option<Rational> {
+        Rational::new(num, den)
+    }
+
+    #[test_case(2.0 => r(2, 1))]
+    #[test_case(-3.0 => r(-3, 1); "negative")]
+    #[test_case(0.5 => r(1, 2))]
+    #[test_case(0.1 => r(1, 10))]
+    #[test_case(1.0 / 3.0 => r(1, 3); "third")]
+    #[test_case(-8.0 / 3.0 => r(-8, 3); "negative thirds")]
+    #[test_case(1.25 => r(5, 4); "decimal")]
+    #[test_case(0.236_588_236 => None; "long decimal")]
+    #[test_case(0.333_333_333_333_333 => r(1, 3); "close to a third")]
+    #[test_case(1.0 / 1_000_003.0 => None; "big denominator")]
+    #[test_case(1.0 / 999_983.0 => r(1, 999_983); "max denominator")]
+    #[test_case(std::f64::consts::PI => None; "irrational")]
+    #[test_case(f64::NAN => None; "nan")]
+    #[test_case(1e20 => None; "too big")]
+    fn from_f64(value: f64) -> Option<Rational> {
+        Rational::from_f64(value)
+    }
+
+    #[test]
+    fn normalized() {
+        assert_eq!(r(2, 4), r(1, 2));
+        assert_eq!(r(1, -2), r(-1, 2));
+        assert_eq!(r(1, 0), None);
+    }
+
+    #[test]
+    fn add_thirds() {
+        let third = Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 3,
+            err: 0.0,
+        };
+        let two_thirds = add(third, third);
+        assert!(matches!(
+            two_thirds,
+            Number::Fraction {
+                whole: 0,
+                num: 2,
+                den: 3,
+                ..
+            }
+        ));
+        assert!(matches!(add(two_thirds, third), Number::Regular(v) if v == 1.0));
+    }
+
+    #[test]
+    fn add_decimals() {
+        assert!(matches!(add(0.1.into(), 0.2.into()), Number::Regular(v) if v == 0.3));
+    }
+
+    #[test]
+    fn mul_inexact() {
+        let n = mul(std::f64::consts::PI.into(), 2.0);
+        assert_eq!(n.value(), std::f64::consts::PI * 2.0);
+    }
+
+    #[test]
+    fn sub_fractions() {
+        let half = Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 2,
+            err: 0.0,
+        };
+        let quarter = Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 4,
+            err: 0.0,
+        };
+        assert!(matches!(
+            sub(half, quarter),
+            Number::Fraction {
+                whole: 0,
+                num: 1,
+                den: 4,
+                ..
+            }
+        ));
+        assert!(matches!(sub(quarter, half), Number::Regular(v) if v == -0.25));
+        assert!(matches!(sub(0.3.into(), 0.1.into()), Number::Regular(v) if v == 0.2));
+    }
+
+    #[test]
+    fn div_exact() {
+        let n = div(1.0.into(), 3.0);
+        assert!(matches!(mul(n, 3.0), Number::Regular(v) if v == 1.0));
+        assert!(div(1.0.into(), 0.0).value().is_infinite());
+    }
+
+    #[test_case(0.1 + 0.2, 0.3 => Some(Ordering::Equal); "rounding error")]
+    #[test_case(1.0 / 3.0, 0.333 => Some(Ordering::Greater); "third")]
+    #[test_case(std::f64::consts::PI, 3.0 => Some(Ordering::Greater); "irrational")]
+    #[test_case(f64::NAN, 3.0 => None; "nan")]
+    fn compare(a: f64, b: f64) -> Option<Ordering> {
+        cmp(a.into(), b.into())
+    }
+
+    #[test]
+    fn big_denominator_is_float() {
+        let n = 1.0 / 1_000_003.0;
+        assert!(matches!(add(n.into(), n.into()), Number::Regular(v) if v == n + n));
+        assert!(matches!(mul(n.into(), 3.0), Number::Regular(v) if v == n * 3.0));
+    }
+
+    #[test]
+    fn many_thirds() {
+        let mut n = Number::Regular(0.0);
+        for _ in 0..30 {
+            n = add(n, (2.0 / 3.0).into());
+        }
+        assert!(matches!(n, Number::Regular(v) if v == 20.0));
+    }
+}
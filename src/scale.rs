@@ -1,6 +1,6 @@
 //! Support for recipe scaling
 
-use crate::{convert::Converter, quantity::Value, Quantity, Recipe};
+use crate::{convert::Converter, quantity::Value, rational, Quantity, Recipe};
 use thiserror::Error;
 
 /// Error type for scaling operations
@@ -26,6 +26,7 @@ impl Recipe {
     pub fn scale(&mut self, factor: f64, converter: &Converter) {
         let scale_quantity = |q: &mut Quantity| {
             if q.scalable {
+                q.restore_exact();
                 q.value.scale(factor);
                 let _ = q.fit(converter);
             }
@@ -76,16 +77,7 @@ impl Recipe {
         target: u32,
         converter: &Converter,
     ) -> Result<(), ScaleError> {
-        let current_servings = self
-            .metadata
-            .servings()
-            .ok_or(ScaleError::InvalidServings)?;
-
-        let base = current_servings
-            .as_number()
-            .ok_or(ScaleError::InvalidServings)?;
-
-        let factor = target as f64 / base as f64;
+        let factor = self.servings_factor(target)?;
         self.scale(factor, converter);
 
         // Update servings metadata to the target value
@@ -157,6 +149,47 @@ impl Recipe {
         target_unit: &str,
         converter: &Converter,
     ) -> Result<(), ScaleError> {
+        let factor = self.yield_factor(target_value, target_unit)?;
+        self.scale(factor, converter);
+
+        // Update yield metadata to the target value (always use % format)
+        // TODO: use std keys
+        if let Some(yield_meta) = self.metadata.get_mut("yield") {
+            *yield_meta = serde_yaml::Value::String(format!("{target_value}%{target_unit}"));
+        }
+
+        Ok(())
+    }
+
+    /// Factor [`Self::scale_to_target`] would scale the recipe by
+    ///
+    /// Fails in the same cases.
+    pub fn target_factor(
+        &self,
+        target_value: f64,
+        target_unit: Option<&str>,
+    ) -> Result<f64, ScaleError> {
+        match target_unit {
+            Some("servings") | Some("serving") => self.servings_factor(target_value.round() as u32),
+            Some(unit) => self.yield_factor(target_value, unit),
+            None => Ok(target_value),
+        }
+    }
+
+    fn servings_factor(&self, target: u32) -> Result<f64, ScaleError> {
+        let current_servings = self
+            .metadata
+            .servings()
+            .ok_or(ScaleError::InvalidServings)?;
+
+        let base = current_servings
+            .as_number()
+            .ok_or(ScaleError::InvalidServings)?;
+
+        Ok(target as f64 / base as f64)
+    }
+
+    fn yield_factor(&self, target_value: f64, target_unit: &str) -> Result<f64, ScaleError> {
         // Get current yield from metadata
         // TODO: use std keys
         let yield_value = self.metadata.get("yield").ok_or(ScaleError::InvalidYield)?;
@@ -184,16 +217,7 @@ impl Recipe {
             });
         }
 
-        let factor = target_value / current_value;
-        self.scale(factor, converter);
-
-        // Update yield metadata to the target value (always use % format)
-        // TODO: use std keys
-        if let Some(yield_meta) = self.metadata.get_mut("yield") {
-            *yield_meta = serde_yaml::Value::String(format!("{target_value}%{target_unit}"));
-        }
-
-        Ok(())
+        Ok(target_value / current_value)
     }
 }
 
@@ -201,11 +225,11 @@ impl Value {
     fn scale(&mut self, factor: f64) {
         match self {
             Value::Number(n) => {
-                *n = (n.value() * factor).into();
+                *n = rational::mul(*n, factor);
             }
             Value::Range { start, end } => {
-                *start = (start.value() * factor).into();
-                *end = (end.value() * factor).into();
+                *start = rational::mul(*start, factor);
+                *end = rational::mul(*end, factor);
             }
             Value::Text(_) => {}
         }
@@ -4,11 +4,16 @@
 //! in a tree structure. Recipe references start with `./` and can have nested
 //! children via indentation.
 
+use std::collections::{HashMap, HashSet};
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::error::{CowStr, Label, RichError};
+use crate::error::{CowStr, Label, RichError, SourceReport};
+use crate::ingredient_list::{Contribution, IngredientList};
+use crate::quantity::{GroupedQuantity, Quantity, Value};
 use crate::span::Span;
+use crate::{Converter, CooklangParser, Modifiers, Recipe};
 
 /// A shopping list containing recipe references and free-hand ingredients
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -33,10 +38,52 @@ pub struct RecipeItem {
     pub path: String,
     /// Optional multiplier/scale factor
     pub multiplier: Option<f64>,
+    /// Optional target servings or yield (e.g. `{6%servings}` or `{2%loaves}`)
+    ///
+    /// [`parse`] sets only one of `multiplier` and `target`.
+    #[serde(default)]
+    pub target: Option<ScaleTarget>,
     /// Nested items (sub-recipes and ingredients of this recipe)
     pub children: Vec<ShoppingListItem>,
 }
 
+/// Target servings or yield of a [`RecipeItem`]
+///
+/// The recipe is scaled like [`Recipe::scale_to_target`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScaleTarget {
+    /// Number of servings or yield amount
+    pub value: f64,
+    /// `servings` or the unit of the recipe yield
+    pub unit: String,
+    /// Location of the target in the shopping list, if it was parsed
+    #[serde(skip)]
+    pub span: Option<Span>,
+}
+
+impl ScaleTarget {
+    /// Target number of servings
+    pub fn servings(servings: u32) -> Self {
+        Self {
+            value: servings as f64,
+            unit: "servings".to_string(),
+            span: None,
+        }
+    }
+
+    /// Checks if the target is a number of servings instead of a yield
+    pub fn is_servings(&self) -> bool {
+        matches!(self.unit.as_str(), "servings" | "serving")
+    }
+}
+
+impl PartialEq for ScaleTarget {
+    /// The span is ignored
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.unit == other.unit
+    }
+}
+
 /// A free-hand ingredient in the shopping list
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IngredientItem {
@@ -46,8 +93,8 @@ pub struct IngredientItem {
     pub quantity: Option<String>,
 }
 
-/// Error generated by [`parse`]
-#[derive(Debug, Error, PartialEq, Eq)]
+/// Error generated by [`parse`] and [`ShoppingList::resolve`]
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
 pub enum ShoppingListError {
     #[error("Error parsing shopping list: {message}")]
     Parse { span: Span, message: String },
@@ -55,6 +102,12 @@ pub enum ShoppingListError {
     InvalidMultiplier { span: Span, message: String },
     #[error("Invalid indentation at line")]
     InvalidIndentation { span: Span },
+    #[error("Cannot scale '{path}' to the target: {message}")]
+    InvalidTarget {
+        span: Option<Span>,
+        path: String,
+        message: String,
+    },
 }
 
 impl RichError for ShoppingListError {
@@ -68,6 +121,10 @@ impl RichError for ShoppingListError {
             ShoppingListError::InvalidIndentation { span } => {
                 vec![label!(span, "unexpected indentation")]
             }
+            ShoppingListError::InvalidTarget { span, .. } => span
+                .iter()
+                .map(|span| label!(span, "cannot scale to this"))
+                .collect(),
         }
         .into()
     }
@@ -78,7 +135,10 @@ impl RichError for ShoppingListError {
                 vec!["Use 2 spaces per indentation level".into()]
             }
             ShoppingListError::InvalidMultiplier { .. } => {
-                vec!["Multiplier must be a number, e.g. {2} or {0.5}".into()]
+                vec!["Use a number, e.g. {2} or {0.5}, or a target, e.g. {6%servings}".into()]
+            }
+            ShoppingListError::InvalidTarget { .. } => {
+                vec!["The recipe needs numeric `servings` or a `yield` with the same unit".into()]
             }
             _ => vec![],
         }
@@ -99,13 +159,13 @@ pub fn parse(input: &str) -> Result<ShoppingList, ShoppingListError> {
     Ok(ShoppingList { items })
 }
 
-struct ParsedLine<'a> {
-    content: &'a str,
-    indent: usize,
-    offset: usize,
+pub(crate) struct ParsedLine<'a> {
+    pub(crate) content: &'a str,
+    pub(crate) indent: usize,
+    pub(crate) offset: usize,
 }
 
-fn collect_lines(input: &str) -> Vec<ParsedLine<'_>> {
+pub(crate) fn collect_lines(input: &str) -> Vec<ParsedLine<'_>> {
     let mut lines = Vec::new();
     let mut offset = 0;
 
@@ -141,7 +201,7 @@ fn collect_lines(input: &str) -> Vec<ParsedLine<'_>> {
 /// space so adjacent tokens don't get glued together.
 ///
 /// Returns `ShoppingListError::Parse` if a `[-` is opened but never closed.
-fn strip_block_comments(input: &str) -> Result<String, ShoppingListError> {
+pub(crate) fn strip_block_comments(input: &str) -> Result<String, ShoppingListError> {
     let mut result = String::with_capacity(input.len());
     let mut chars = input.char_indices().peekable();
 
@@ -204,7 +264,7 @@ fn parse_items(
         }
 
         if line.content.starts_with("./") {
-            let (path, multiplier) = parse_recipe_line(line)?;
+            let (path, multiplier, target) = parse_recipe_line(line)?;
 
             let child_indent = base_indent + 2;
             let child_start = i + 1;
@@ -222,6 +282,7 @@ fn parse_items(
             items.push(ShoppingListItem::Recipe(RecipeItem {
                 path,
                 multiplier,
+                target,
                 children,
             }));
 
@@ -241,37 +302,61 @@ fn parse_items(
 
 /// Collapse runs of whitespace (left over from block comment stripping) into
 /// single spaces, and trim ends.
-fn normalize_whitespace(s: &str) -> String {
+pub(crate) fn normalize_whitespace(s: &str) -> String {
     s.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-fn parse_recipe_line(line: &ParsedLine<'_>) -> Result<(String, Option<f64>), ShoppingListError> {
+type RecipeLine = (String, Option<f64>, Option<ScaleTarget>);
+
+fn parse_recipe_line(line: &ParsedLine<'_>) -> Result<RecipeLine, ShoppingListError> {
     let content = &line.content[2..];
 
-    if let Some(brace_start) = content.rfind('{') {
-        if content.ends_with('}') {
-            let path = normalize_whitespace(&content[..brace_start]);
-            let multiplier_str = &content[brace_start + 1..content.len() - 1];
-            let multiplier: f64 =
-                multiplier_str
+    let Some(brace_start) = content.rfind('{').filter(|_| content.ends_with('}')) else {
+        return Ok((normalize_whitespace(content), None, None));
+    };
+    let path = normalize_whitespace(&content[..brace_start]);
+    let scale_str = &content[brace_start + 1..content.len() - 1];
+    let span = Span::new(
+        line.offset + 2 + brace_start + 1,
+        line.offset + 2 + content.len() - 1,
+    );
+    let invalid = |message| ShoppingListError::InvalidMultiplier { span, message };
+
+    match scale_str.split_once('%') {
+        Some((value, unit)) => {
+            let (value, unit) = (value.trim(), unit.trim());
+            let mut target = ScaleTarget {
+                value: value
                     .parse()
-                    .map_err(|_| ShoppingListError::InvalidMultiplier {
-                        span: Span::new(
-                            line.offset + 2 + brace_start + 1,
-                            line.offset + 2 + content.len() - 1,
-                        ),
-                        message: format!("'{multiplier_str}' is not a valid number"),
-                    })?;
-            Ok((path, Some(multiplier)))
-        } else {
-            Ok((normalize_whitespace(content), None))
+                    .ok()
+                    .filter(|v: &f64| v.is_finite() && *v > 0.0)
+                    .ok_or_else(|| invalid(format!("'{value}' is not a valid number")))?,
+                unit: unit.to_string(),
+                span: Some(span),
+            };
+            if unit.is_empty() {
+                return Err(invalid("missing unit after '%'".to_string()));
+            }
+            if matches!(unit.to_lowercase().as_str(), "servings" | "serving") {
+                if target.value.fract() != 0.0 {
+                    return Err(invalid(format!(
+                        "'{value}' is not a valid number of servings"
+                    )));
+                }
+                target.unit = "servings".to_string();
+            }
+            Ok((path, None, Some(target)))
+        }
+        None => {
+            let multiplier: f64 = scale_str
+                .parse()
+                .map_err(|_| invalid(format!("'{scale_str}' is not a valid number")))?;
+            Ok((path, Some(multiplier), None))
         }
-    } else {
-        Ok((normalize_whitespace(content), None))
     }
 }
 
-fn parse_ingredient_line(
+pub(crate) fn parse_ingredient_line(
     line: &ParsedLine<'_>,
 ) -> Result<(String, Option<String>), ShoppingListError> {
     let content = line.content;
@@ -316,6 +401,13 @@ fn write_items(
                     } else {
                         write!(w, "{{{m}}}")?;
                     }
+                } else if let Some(target) = &recipe.target {
+                    let unit = &target.unit;
+                    if target.value.fract() == 0.0 {
+                        write!(w, "{{{}%{unit}}}", target.value as i64)?;
+                    } else {
+                        write!(w, "{{{}%{unit}}}", target.value)?;
+                    }
                 }
                 writeln!(w)?;
                 write_items(&recipe.children, depth + 1, w)?;
@@ -332,6 +424,218 @@ fn write_items(
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Resolving a shopping list into ingredients
+// ---------------------------------------------------------------------------
+
+/// Ingredients of a [`ShoppingList`], obtained from [`ShoppingList::resolve`]
+#[derive(Debug, Default)]
+pub struct ResolvedShoppingList {
+    /// Ingredients to buy
+    pub ingredients: IngredientList,
+    /// Ingredients already checked, see [`ResolvedShoppingList::apply_checked`]
+    pub checked: IngredientList,
+    /// Items that could not be resolved
+    ///
+    /// Recipes that could not be loaded are not included in the ingredients.
+    pub diagnostics: Vec<ResolveDiagnostic>,
+}
+
+/// Problem found while resolving a [`ShoppingList`]
+#[derive(Debug, Clone, Error)]
+pub enum ResolveDiagnostic {
+    /// The loader did not find the recipe
+    #[error("Recipe not found: {path}")]
+    NotFound { path: String },
+    /// The recipe could not be parsed
+    #[error("Recipe '{path}' has errors")]
+    InvalidRecipe { path: String, report: SourceReport },
+    /// A free-hand quantity could not be parsed, the ingredient is listed
+    /// with the quantity as text
+    #[error("Invalid quantity '{quantity}' for '{name}'")]
+    InvalidQuantity { name: String, quantity: String },
+    /// The recipe could not be scaled to the target of the item, it is a
+    /// [`ShoppingListError::InvalidTarget`]
+    #[error(transparent)]
+    InvalidTarget(ShoppingListError),
+}
+
+impl ShoppingList {
+    /// Resolve the list into the ingredients of all its items
+    ///
+    /// `load` returns the source of a recipe from its path, or `None` if it
+    /// does not exist. Every referenced recipe is parsed with `parser` and
+    /// scaled by its multiplier or to its target. The multiplier of a nested
+    /// item is relative to its parent, a target is not. Free-hand ingredients
+    /// inside a recipe are scaled like the recipe.
+    ///
+    /// When a recipe can't be scaled to its target, it is listed with the
+    /// scale of its parent and a [`ResolveDiagnostic::InvalidTarget`] is
+    /// reported.
+    ///
+    /// Ingredients that reference other recipes are not listed, nested
+    /// recipes must be children of the item in the list.
+    pub fn resolve(
+        &self,
+        parser: &CooklangParser,
+        mut load: impl FnMut(&str) -> Option<String>,
+    ) -> ResolvedShoppingList {
+        let mut resolver = Resolver {
+            parser,
+            load: &mut load,
+            recipes: HashMap::new(),
+            resolved: ResolvedShoppingList::default(),
+        };
+        resolver.items(&self.items, 1.0, None);
+        resolver.resolved
+    }
+}
+
+impl ResolvedShoppingList {
+    /// Move the checked ingredients to [`Self::checked`]
+    ///
+    /// `checked` is the set from [`checked_set`], with lowercased names.
+    pub fn apply_checked(&mut self, checked: &HashSet<String>) {
+        let done = self
+            .ingredients
+            .extract_if(|name, _| checked.contains(&name.to_lowercase()));
+        // names are never in both lists, so nothing is converted
+        self.checked.merge(done, &Converter::empty());
+    }
+}
+
+struct Resolver<'a, 'c> {
+    parser: &'c CooklangParser,
+    load: &'a mut dyn FnMut(&str) -> Option<String>,
+    /// Parsed recipes by path, `None` if they could not be loaded
+    recipes: HashMap<String, Option<Recipe>>,
+    resolved: ResolvedShoppingList,
+}
+
+impl Resolver<'_, '_> {
+    /// Adds `items`, scaled by `factor`, nested in the recipe at `parent`
+    fn items(&mut self, items: &[ShoppingListItem], factor: f64, parent: Option<&str>) {
+        for item in items {
+            match item {
+                ShoppingListItem::Recipe(item) => {
+                    let factor = self.recipe(item, factor);
+                    self.items(&item.children, factor, Some(&item.path));
+                }
+                ShoppingListItem::Ingredient(item) => self.ingredient(item, factor, parent),
+            }
+        }
+    }
+
+    /// Adds the recipe of an item and returns the factor it was scaled by
+    fn recipe(&mut self, item: &RecipeItem, parent_factor: f64) -> f64 {
+        let path = item.path.as_str();
+        if !self.recipes.contains_key(path) {
+            let recipe = self.load(path);
+            self.recipes.insert(path.to_string(), recipe);
+        }
+        let mut factor = parent_factor * item.multiplier.unwrap_or(1.0);
+        let Some(recipe) = &self.recipes[path] else {
+            return factor;
+        };
+        if let Some(target) = &item.target {
+            match recipe.target_factor(target.value, Some(&target.unit)) {
+                Ok(f) => factor = f,
+                Err(err) => self
+                    .resolved
+                    .diagnostics
+                    .push(ResolveDiagnostic::InvalidTarget(
+                        ShoppingListError::InvalidTarget {
+                            span: target.span,
+                            path: path.to_string(),
+                            message: err.to_string(),
+                        },
+                    )),
+            }
+        }
+        let converter = self.parser.converter();
+        if factor == 1.0 {
+            self.resolved
+                .ingredients
+                .add_recipe_from(path, factor, recipe, converter, false);
+        } else {
+            let mut scaled = recipe.clone();
+            scaled.scale(factor, converter);
+            self.resolved
+                .ingredients
+                .add_recipe_from(path, factor, &scaled, converter, false);
+        }
+        factor
+    }
+
+    fn load(&mut self, path: &str) -> Option<Recipe> {
+        let Some(input) = (self.load)(path) else {
+            self.resolved.diagnostics.push(ResolveDiagnostic::NotFound {
+                path: path.to_string(),
+            });
+            return None;
+        };
+        match self.parser.parse(&input).into_result() {
+            Ok((recipe, _)) => Some(recipe),
+            Err(report) => {
+                self.resolved
+                    .diagnostics
+                    .push(ResolveDiagnostic::InvalidRecipe {
+                        path: path.to_string(),
+                        report,
+                    });
+                None
+            }
+        }
+    }
+
+    fn ingredient(&mut self, item: &IngredientItem, factor: f64, parent: Option<&str>) {
+        let converter = self.parser.converter();
+        let mut quantity = GroupedQuantity::empty();
+        if let Some(q) = &item.quantity {
+            match parse_free_hand_quantity(self.parser, q, factor) {
+                Some(q) => quantity.add(&q, Some(&item.name), converter),
+                None => {
+                    self.resolved
+                        .diagnostics
+                        .push(ResolveDiagnostic::InvalidQuantity {
+                            name: item.name.clone(),
+                            quantity: q.clone(),
+                        });
+                    quantity.add(
+                        &Quantity::new(Value::Text(q.trim().to_string()), None),
+                        None,
+                        converter,
+                    );
+                }
+            }
+        }
+        // nested items belong to the recipe, so removing it removes them too
+        let contribution = Contribution {
+            recipe: parent.map(String::from),
+            multiplier: factor,
+            index: None,
+            name: item.name.clone(),
+            quantity: quantity.clone(),
+            modifiers: Modifiers::empty(),
+        };
+        self.resolved
+            .ingredients
+            .add(item.name.clone(), &quantity, vec![contribution], converter);
+    }
+}
+
+/// Parses a quantity like the ones of a recipe ingredient, e.g. `500%g`
+fn parse_free_hand_quantity(parser: &CooklangParser, q: &str, factor: f64) -> Option<Quantity> {
+    if q.contains(['{', '}', '\n']) {
+        return None;
+    }
+    let mut recipe = parser.parse(&format!("@item{{{q}}}")).into_output()?;
+    if factor != 1.0 {
+        recipe.scale(factor, parser.converter());
+    }
+    recipe.ingredients.pop()?.quantity
+}
+
 // ---------------------------------------------------------------------------
 // Checked file (.shopping-checked) — append-only log of checked/unchecked items
 // ---------------------------------------------------------------------------
@@ -379,8 +683,7 @@ pub fn parse_checked(input: &str) -> Vec<CheckEntry> {
 /// Matching is case-insensitive, so the returned set contains **lowercased
 /// names only** — compare against your shopping list with
 /// `name.to_lowercase()`.
-pub fn checked_set(entries: &[CheckEntry]) -> std::collections::HashSet<String> {
-    use std::collections::HashMap;
+pub fn checked_set(entries: &[CheckEntry]) -> HashSet<String> {
     let mut state: HashMap<String, bool> = HashMap::new();
     for entry in entries {
         match entry {
@@ -424,7 +727,7 @@ where
     I: IntoIterator<Item = &'a str>,
 {
     let current = checked_set(entries);
-    let list_ingredients: std::collections::HashSet<String> = current_ingredients
+    let list_ingredients: HashSet<String> = current_ingredients
         .into_iter()
         .map(|n| n.to_lowercase())
         .collect();
@@ -468,6 +771,350 @@ fn collect_ingredient_names_from_items(items: &[ShoppingListItem], names: &mut V
     }
 }
 
+// ---------------------------------------------------------------------------
+// Three-way merge of shopping lists and checked logs
+// ---------------------------------------------------------------------------
+
+/// Result of [`merge`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MergedShoppingList {
+    /// The merged list, conflicts are already solved in it
+    pub list: ShoppingList,
+    /// Items changed in both sides in different ways
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// An item changed in both sides of a [`merge`]
+///
+/// A `None` version means the item does not exist in that version.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergeConflict {
+    /// Paths of the recipes the item is nested in, outermost first
+    pub parents: Vec<String>,
+    pub base: Option<ShoppingListItem>,
+    pub ours: Option<ShoppingListItem>,
+    pub theirs: Option<ShoppingListItem>,
+}
+
+impl MergeConflict {
+    fn new(
+        parents: &[String],
+        base: Option<&ShoppingListItem>,
+        ours: Option<&ShoppingListItem>,
+        theirs: Option<&ShoppingListItem>,
+    ) -> Self {
+        Self {
+            parents: parents.to_vec(),
+            base: base.cloned(),
+            ours: ours.cloned(),
+            theirs: theirs.cloned(),
+        }
+    }
+}
+
+/// Three-way merge of shopping lists
+///
+/// `ours` and `theirs` are two versions of the list edited from `base`.
+/// Items are matched by recipe path or by ingredient name (case-insensitive)
+/// and the changes of both sides to them are combined: added and removed
+/// items, multipliers, targets, quantities and the children of recipes.
+///
+/// When both sides change the same thing differently the version of `ours`
+/// is kept, except if one side removed an item the other changed, then the
+/// changed item is kept. Every conflict is reported.
+///
+/// The merged list keeps the order of `ours` with the items added by
+/// `theirs` after the item they follow in `theirs`. Merging the same lists
+/// always gives the same result.
+pub fn merge(
+    base: &ShoppingList,
+    ours: &ShoppingList,
+    theirs: &ShoppingList,
+) -> MergedShoppingList {
+    let mut conflicts = Vec::new();
+    let items = merge_items(
+        &mut Vec::new(),
+        &base.items,
+        &ours.items,
+        &theirs.items,
+        &mut conflicts,
+    );
+    MergedShoppingList {
+        list: ShoppingList { items },
+        conflicts,
+    }
+}
+
+/// Identity of an item in a list, the last field numbers repeated items
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ItemKey {
+    Recipe(String, usize),
+    Ingredient(String, usize),
+}
+
+fn keyed_items(items: &[ShoppingListItem]) -> Vec<(ItemKey, &ShoppingListItem)> {
+    let mut seen = HashMap::new();
+    items
+        .iter()
+        .map(|item| {
+            let (is_recipe, name) = match item {
+                ShoppingListItem::Recipe(r) => (true, r.path.clone()),
+                ShoppingListItem::Ingredient(i) => (false, i.name.to_lowercase()),
+            };
+            let n = seen.entry((is_recipe, name.clone())).or_insert(0);
+            let key = if is_recipe {
+                ItemKey::Recipe(name, *n)
+            } else {
+                ItemKey::Ingredient(name, *n)
+            };
+            *n += 1;
+            (key, item)
+        })
+        .collect()
+}
+
+fn merge_items(
+    parents: &mut Vec<String>,
+    base: &[ShoppingListItem],
+    ours: &[ShoppingListItem],
+    theirs: &[ShoppingListItem],
+    conflicts: &mut Vec<MergeConflict>,
+) -> Vec<ShoppingListItem> {
+    let base: HashMap<_, _> = keyed_items(base).into_iter().collect();
+    let ours = keyed_items(ours);
+    let theirs = keyed_items(theirs);
+    let in_ours: HashSet<_> = ours.iter().map(|(key, _)| key).collect();
+    let theirs_map: HashMap<_, _> = theirs.iter().map(|(k, i)| (k, *i)).collect();
+
+    let mut merged = Vec::new();
+    for (key, o) in &ours {
+        let item = match (base.get(key).copied(), theirs_map.get(key)) {
+            (b, Some(t)) => merge_item(parents, b, o, t, conflicts),
+            // removed by theirs
+            (Some(b), None) if b == *o => continue,
+            (Some(b), None) => {
+                conflicts.push(MergeConflict::new(parents, Some(b), Some(o), None));
+                (*o).clone()
+            }
+            (None, None) => (*o).clone(),
+        };
+        merged.push((key.clone(), item));
+    }
+
+    let mut anchor: Option<&ItemKey> = None;
+    for (key, t) in &theirs {
+        if !in_ours.contains(key) {
+            match base.get(key).copied() {
+                Some(b) if b == *t => {}
+                b => {
+                    // removed by ours but changed by theirs
+                    if b.is_some() {
+                        conflicts.push(MergeConflict::new(parents, b, None, Some(t)));
+                    }
+                    let pos = match anchor {
+                        Some(anchor) => merged
+                            .iter()
+                            .position(|(k, _)| k == anchor)
+                            .map_or(merged.len(), |p| p + 1),
+                        None => 0,
+                    };
+                    merged.insert(pos, (key.clone(), (*t).clone()));
+                }
+            }
+        }
+        if merged.iter().any(|(k, _)| k == key) {
+            anchor = Some(key);
+        }
+    }
+
+    merged.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Merge an item present in both sides
+fn merge_item(
+    parents: &mut Vec<String>,
+    base: Option<&ShoppingListItem>,
+    ours: &ShoppingListItem,
+    theirs: &ShoppingListItem,
+    conflicts: &mut Vec<MergeConflict>,
+) -> ShoppingListItem {
+    match (ours, theirs) {
+        (ShoppingListItem::Recipe(o), ShoppingListItem::Recipe(t)) => {
+            let b = match base {
+                Some(ShoppingListItem::Recipe(b)) => Some(b),
+                _ => None,
+            };
+            let scale = |r: &RecipeItem| (r.multiplier, r.target.clone());
+            let (multiplier, target) = merge_value(b.map(scale), scale(o), scale(t))
+                .unwrap_or_else(|| {
+                    conflicts.push(MergeConflict::new(parents, base, Some(ours), Some(theirs)));
+                    scale(o)
+                });
+            parents.push(o.path.clone());
+            let children = merge_items(
+                parents,
+                b.map(|b| b.children.as_slice()).unwrap_or_default(),
+                &o.children,
+                &t.children,
+                conflicts,
+            );
+            parents.pop();
+            ShoppingListItem::Recipe(RecipeItem {
+                path: o.path.clone(),
+                multiplier,
+                target,
+                children,
+            })
+        }
+        (ShoppingListItem::Ingredient(o), ShoppingListItem::Ingredient(t)) => {
+            let b = match base {
+                Some(ShoppingListItem::Ingredient(b)) => Some(b),
+                _ => None,
+            };
+            // the name can only change in case
+            let name = merge_value(b.map(|b| &b.name), &o.name, &t.name);
+            let quantity = merge_value(b.map(|b| &b.quantity), &o.quantity, &t.quantity);
+            if name.is_none() || quantity.is_none() {
+                conflicts.push(MergeConflict::new(parents, base, Some(ours), Some(theirs)));
+            }
+            ShoppingListItem::Ingredient(IngredientItem {
+                name: name.unwrap_or(&o.name).clone(),
+                quantity: quantity.unwrap_or(&o.quantity).clone(),
+            })
+        }
+        // keys are different for recipes and ingredients
+        _ => unreachable!("merging a recipe with an ingredient"),
+    }
+}
+
+/// Three-way merge of a value, `None` if both sides changed it differently
+fn merge_value<T: PartialEq>(base: Option<T>, ours: T, theirs: T) -> Option<T> {
+    if ours == theirs || base.as_ref() == Some(&theirs) {
+        Some(ours)
+    } else if base.as_ref() == Some(&ours) {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+/// Result of [`merge_checked`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MergedChecked {
+    /// Compacted merged log, with a `+ name` entry per checked ingredient
+    /// sorted by name
+    pub entries: Vec<CheckEntry>,
+    /// Ingredients checked in one side and unchecked in the other
+    pub conflicts: Vec<CheckConflict>,
+}
+
+/// An ingredient changed in both sides of a [`merge_checked`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckConflict {
+    /// Name of the ingredient, as written in `ours`
+    pub name: String,
+    /// If it is checked in `ours`, this is the state that is kept
+    pub ours: bool,
+    /// If it is checked in `theirs`
+    pub theirs: bool,
+}
+
+/// Three-way merge of checked logs
+///
+/// `ours` and `theirs` are two versions of the log since `base`. If a log
+/// was appended to, the entries after `base` are the changes of that side.
+/// Otherwise, like after [`compact_checked`], the changes are the
+/// ingredients with a different state than in `base`.
+///
+/// Ingredients changed only in one side take the state of that side. If
+/// both sides end with different states, the state of `ours` is kept and
+/// the conflict is reported.
+///
+/// Names are matched case-insensitive like in [`checked_set`], keeping the
+/// last spelling of the side the state comes from. The merged log is
+/// compacted like [`compact_checked`], so it can be written with
+/// [`write_checked`] replacing the previous log.
+pub fn merge_checked(
+    base: &[CheckEntry],
+    ours: &[CheckEntry],
+    theirs: &[CheckEntry],
+) -> MergedChecked {
+    let base_set = checked_set(base);
+    let changed = |log: &[CheckEntry], set: &HashSet<String>| -> HashSet<String> {
+        let common = log.iter().zip(base).take_while(|(a, b)| a == b).count();
+        if common < base.len() {
+            // not appended to base, like after compacting, so only the
+            // state can be compared
+            return set.symmetric_difference(&base_set).cloned().collect();
+        }
+        log[common..]
+            .iter()
+            .map(|e| entry_name(e).to_lowercase())
+            .collect()
+    };
+    let (ours_set, theirs_set) = (checked_set(ours), checked_set(theirs));
+    let ours_changed = changed(ours, &ours_set);
+    let theirs_changed = changed(theirs, &theirs_set);
+
+    let mut names: Vec<&String> = base_set
+        .iter()
+        .chain(&ours_changed)
+        .chain(&theirs_changed)
+        .collect();
+    names.sort();
+    names.dedup();
+
+    // the last spelling of every name in each log
+    let spellings = |log: &[CheckEntry]| -> HashMap<String, String> {
+        log.iter()
+            .map(|e| (entry_name(e).to_lowercase(), entry_name(e).to_string()))
+            .collect()
+    };
+    let (base_names, ours_names, theirs_names) =
+        (spellings(base), spellings(ours), spellings(theirs));
+
+    let mut merged = MergedChecked::default();
+    for key in names {
+        let o = ours_set.contains(key);
+        let t = theirs_set.contains(key);
+        let changes = (ours_changed.contains(key), theirs_changed.contains(key));
+        let (checked, spellings) = match changes {
+            (false, false) => (base_set.contains(key), &base_names),
+            (true, false) => (o, &ours_names),
+            (false, true) => (t, &theirs_names),
+            (true, true) => {
+                if o != t {
+                    merged.conflicts.push(CheckConflict {
+                        name: ours_names.get(key).unwrap_or(key).clone(),
+                        ours: o,
+                        theirs: t,
+                    });
+                }
+                (o, &ours_names)
+            }
+        };
+        if checked {
+            let name = spellings.get(key).unwrap_or(key).clone();
+            merged.entries.push(CheckEntry::Checked(name));
+        }
+    }
+    merged
+}
+
+fn entry_name(entry: &CheckEntry) -> &str {
+    match entry {
+        CheckEntry::Checked(n) | CheckEntry::Unchecked(n) => n,
+    }
+}
+
+/// Write a checked log, one entry per line.
+pub fn write_checked(entries: &[CheckEntry], mut w: impl std::io::Write) -> std::io::Result<()> {
+    for entry in entries {
+        write_check_entry(entry, &mut w)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -763,6 +1410,50 @@ salt
         assert!(matches!(err, ShoppingListError::InvalidMultiplier { .. }));
     }
 
+    #[test]
+    fn target_servings_and_yield() {
+        let list = parse("./Pancakes{6 % Servings}\n./Bread{1.5%loaves}").unwrap();
+        let targets: Vec<_> = list
+            .items
+            .iter()
+            .map(|item| match item {
+                ShoppingListItem::Recipe(r) => {
+                    assert_eq!(r.multiplier, None);
+                    r.target.clone().unwrap()
+                }
+                _ => panic!("expected recipe"),
+            })
+            .collect();
+        assert_eq!(targets[0], ScaleTarget::servings(6));
+        assert!(targets[0].is_servings());
+        assert_eq!(targets[0].span, Some(Span::new(11, 23)));
+        assert_eq!(targets[1].value, 1.5);
+        assert_eq!(targets[1].unit, "loaves");
+        assert!(!targets[1].is_servings());
+
+        let mut buf = Vec::new();
+        write(&list, &mut buf).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert_eq!(output, "./Pancakes{6%servings}\n./Bread{1.5%loaves}\n");
+        assert_eq!(parse(&output).unwrap(), list);
+    }
+
+    #[test]
+    fn error_invalid_target() {
+        for input in [
+            "./Recipe{%servings}",
+            "./Recipe{2.5%servings}",
+            "./Recipe{0%servings}",
+            "./Recipe{2%}",
+        ] {
+            let err = parse(input).unwrap_err();
+            assert!(
+                matches!(err, ShoppingListError::InvalidMultiplier { .. }),
+                "{input}"
+            );
+        }
+    }
+
     #[test]
     fn error_empty_ingredient_name() {
         let err = parse("{4%l}").unwrap_err();
@@ -872,4 +1563,297 @@ salt
         let parsed = parse_checked(&output);
         assert_eq!(parsed, entries);
     }
+
+    // -- Resolver tests --
+
+    fn resolve(input: &str) -> ResolvedShoppingList {
+        let parser = CooklangParser::new(crate::Extensions::all(), crate::Converter::bundled());
+        let recipes: HashMap<&str, &str> = [
+            (
+                "Pancakes",
+                "---\nservings: 2\n---\n@flour{200%g} and @milk{300%ml}, @salt",
+            ),
+            ("Syrup", "@sugar{100%g} and @water{50%ml}"),
+            ("Broken", "@flour{200%g}\n>> [mode]: unknown"),
+            ("Bread", "---\nyield: 2%loaves\n---\n@flour{500%g}"),
+        ]
+        .into();
+        parse(input)
+            .unwrap()
+            .resolve(&parser, |path| recipes.get(path).map(|s| s.to_string()))
+    }
+
+    fn quantities(list: &IngredientList) -> Vec<(String, String)> {
+        list.iter()
+            .map(|(name, q)| (name.clone(), q.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn resolve_recipes_and_ingredients() {
+        let resolved = resolve(
+            "\
+./Pancakes{2}
+  ./Syrup{0.5}
+  milk{1/2%l}
+flour{1%kg}
+bread
+",
+        );
+        assert!(resolved.diagnostics.is_empty());
+        assert_eq!(
+            quantities(&resolved.ingredients),
+            [
+                ("flour".into(), "1400 g".into()),
+                ("milk".into(), "1600 ml".into()),
+                ("salt".into(), "".into()),
+                ("sugar".into(), "100 g".into()),
+                ("water".into(), "50 ml".into()),
+                ("bread".into(), "".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_diagnostics() {
+        let resolved = resolve("./Missing\n./Broken{2}\n./Missing\nherbs{a bunch}\neggs{2}}");
+        let diagnostics: Vec<String> = resolved.diagnostics.iter().map(|d| d.to_string()).collect();
+        // a missing recipe is only reported once
+        assert_eq!(
+            diagnostics,
+            [
+                "Recipe not found: Missing",
+                "Recipe 'Broken' has errors",
+                "Invalid quantity '2}' for 'eggs'"
+            ]
+        );
+        assert_eq!(
+            quantities(&resolved.ingredients),
+            [
+                ("herbs".into(), "a bunch".into()),
+                ("eggs".into(), "2}".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_apply_checked() {
+        let mut resolved = resolve("./Pancakes\nSalt");
+        let entries = parse_checked("+ salt\n+ flour\n- flour\n+ milk");
+        resolved.apply_checked(&checked_set(&entries));
+        assert_eq!(
+            quantities(&resolved.ingredients),
+            [("flour".into(), "200 g".into())]
+        );
+        assert_eq!(
+            quantities(&resolved.checked),
+            [
+                ("milk".into(), "300 ml".into()),
+                ("salt".into(), "".into()),
+                // checking is case insensitive
+                ("Salt".into(), "".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_targets() {
+        let resolved = resolve(
+            "\
+./Pancakes{6%servings}
+  ./Syrup{2}
+  butter{10%g}
+./Bread{3%loaves}
+",
+        );
+        assert!(resolved.diagnostics.is_empty());
+        assert_eq!(
+            quantities(&resolved.ingredients),
+            [
+                ("flour".into(), "1350 g".into()),
+                ("milk".into(), "900 ml".into()),
+                ("salt".into(), "".into()),
+                ("sugar".into(), "600 g".into()),
+                ("water".into(), "300 ml".into()),
+                ("butter".into(), "30 g".into()),
+            ]
+        );
+
+        // the target is not relative to the parent
+        let resolved = resolve("./Syrup{2}\n  ./Pancakes{4%servings}");
+        assert_eq!(
+            quantities(&resolved.ingredients)[2],
+            ("flour".into(), "400 g".into())
+        );
+    }
+
+    #[test]
+    fn resolve_breakdown() {
+        let mut resolved = resolve("./Pancakes{6%servings}\n  flour{100%g}\n./Bread\nflour{1%kg}");
+        let flour = resolved.ingredients.breakdown().next().unwrap();
+        assert_eq!(
+            flour.to_string(),
+            "flour: 600 g (Pancakes ×3), 300 g (Pancakes ×3), 500 g (Bread), 1 kg"
+        );
+
+        resolved
+            .ingredients
+            .remove_recipe("Pancakes", &Converter::bundled());
+        assert_eq!(
+            quantities(&resolved.ingredients),
+            [("flour".into(), "1500 g".into())]
+        );
+
+        // checking keeps the contributions
+        resolved.apply_checked(&HashSet::from(["flour".to_string()]));
+        assert_eq!(resolved.checked.contributions("flour").len(), 2);
+    }
+
+    #[test]
+    fn resolve_invalid_target() {
+        let input = "./Syrup{4%servings}\n./Bread{1%kg}";
+        let resolved = resolve(input);
+        let spans: Vec<_> = resolved
+            .diagnostics
+            .iter()
+            .map(|d| match d {
+                ResolveDiagnostic::InvalidTarget(ShoppingListError::InvalidTarget {
+                    span: Some(span),
+                    ..
+                }) => &input[span.range()],
+                _ => panic!("expected invalid target"),
+            })
+            .collect();
+        assert_eq!(spans, ["4%servings", "1%kg"]);
+        // listed without scaling
+        assert_eq!(
+            quantities(&resolved.ingredients),
+            [
+                ("sugar".into(), "100 g".into()),
+                ("water".into(), "50 ml".into()),
+                ("flour".into(), "500 g".into()),
+            ]
+        );
+    }
+
+    fn merge_str(base: &str, ours: &str, theirs: &str) -> (String, Vec<MergeConflict>) {
+        let merged = merge(
+            &parse(base).unwrap(),
+            &parse(ours).unwrap(),
+            &parse(theirs).unwrap(),
+        );
+        let mut out = Vec::new();
+        write(&merged.list, &mut out).unwrap();
+        (String::from_utf8(out).unwrap(), merged.conflicts)
+    }
+
+    #[test]
+    fn merge_combines_changes() {
+        let base = "./A{2}\n  ./B\nmilk{1%l}\n";
+        let ours = "./A{3}\n  ./B\nmilk{1%l}\neggs{12}\n";
+        let theirs = "./A{2}\n  ./B\n  butter\nMilk{2%l}\n";
+        let (merged, conflicts) = merge_str(base, ours, theirs);
+        assert_eq!(merged, "./A{3}\n  ./B\n  butter\nMilk{2%l}\neggs{12}\n");
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn merge_removed_and_added_items() {
+        let base = "./A\n./B\nsalt\n";
+        let ours = "./B\nsalt\n";
+        let theirs = "pepper\n./A\n./B\n";
+        let (merged, conflicts) = merge_str(base, ours, theirs);
+        assert_eq!(merged, "pepper\n./B\n");
+        assert!(conflicts.is_empty());
+
+        // added in both sides
+        let (merged, conflicts) = merge_str("", "./A{6%servings}\n", "./A{6%servings}\n");
+        assert_eq!(merged, "./A{6%servings}\n");
+        assert!(conflicts.is_empty());
+
+        // repeated items are matched in order
+        let (merged, _) = merge_str("./A\n./A\n", "./A{2}\n./A\n", "./A\n./A{3}\n");
+        assert_eq!(merged, "./A{2}\n./A{3}\n");
+    }
+
+    #[test]
+    fn merge_conflicts() {
+        let base = "./A{2}\nmilk{1%l}\nsalt\n";
+        let ours = "./A{3}\nmilk{2%l}\n";
+        let theirs = "./A{4}\nmilk{1%l}\nsalt{1%tsp}\n";
+        let (merged, conflicts) = merge_str(base, ours, theirs);
+        // ours wins, but changed items are not removed
+        assert_eq!(merged, "./A{3}\nmilk{2%l}\nsalt{1%tsp}\n");
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts[0].parents.is_empty());
+        assert!(matches!(
+            &conflicts[0].theirs,
+            Some(ShoppingListItem::Recipe(r)) if r.multiplier == Some(4.0)
+        ));
+        assert!(conflicts[1].ours.is_none());
+        assert!(conflicts[1].base.is_some());
+
+        let (merged, conflicts) = merge_str(
+            "./A\n  ./B\n    eggs{2}\n",
+            "./A\n  ./B\n    eggs{3}\n",
+            "./A\n  ./B\n    eggs{4}\n",
+        );
+        assert_eq!(merged, "./A\n  ./B\n    eggs{3}\n");
+        assert_eq!(conflicts[0].parents, ["A", "B"]);
+    }
+
+    #[test]
+    fn merge_checked_logs() {
+        let base = parse_checked("+ salt\n+ milk\n");
+        let ours = parse_checked("+ salt\n+ milk\n- milk\n+ eggs\n");
+        let theirs = parse_checked("+ salt\n+ milk\n+ Butter\n- salt\n");
+        let merged = merge_checked(&base, &ours, &theirs);
+        assert_eq!(
+            merged.entries,
+            [
+                CheckEntry::Checked("Butter".into()),
+                CheckEntry::Checked("eggs".into())
+            ]
+        );
+        assert!(merged.conflicts.is_empty());
+
+        // a compacted log only changes what has a different state
+        let ours = parse_checked("+ milk\n+ salt\n");
+        let merged = merge_checked(&base, &ours, &theirs);
+        assert_eq!(
+            merged.entries,
+            [
+                CheckEntry::Checked("Butter".into()),
+                CheckEntry::Checked("milk".into())
+            ]
+        );
+        assert!(merged.conflicts.is_empty());
+        let ours = parse_checked("+ milk\n+ Eggs\n");
+        let merged = merge_checked(&base, &ours, &theirs);
+        assert_eq!(
+            merged.entries,
+            [
+                CheckEntry::Checked("Butter".into()),
+                CheckEntry::Checked("Eggs".into()),
+                CheckEntry::Checked("milk".into())
+            ]
+        );
+        // both unchecked salt
+        assert!(merged.conflicts.is_empty());
+
+        let ours = parse_checked("+ salt\n+ milk\n- milk\n");
+        let theirs = parse_checked("+ salt\n+ milk\n- milk\n+ milk\n");
+        let merged = merge_checked(&base, &ours, &theirs);
+        assert_eq!(
+            merged.conflicts,
+            [CheckConflict {
+                name: "milk".into(),
+                ours: false,
+                theirs: true
+            }]
+        );
+        let mut out = Vec::new();
+        write_checked(&merged.entries, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "+ salt\n");
+    }
 }
@@ -0,0 +1,392 @@
+//! Dependency graph between the steps of a recipe
+//!
+//! Steps depend on other steps when they use an ingredient that was first
+//! introduced in them or, with the
+//! [`INTERMEDIATE_PREPARATIONS`](crate::Extensions::INTERMEDIATE_PREPARATIONS)
+//! extension, when they use their output like in `@&(~1)dough{}`.
+//!
+//! The graph can be exported to [DOT](StepGraph::to_dot) and
+//! [Mermaid](StepGraph::to_mermaid) to visualize it.
+
+use std::fmt::Write;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ts")]
+use tsify::Tsify;
+
+use crate::{
+    model::{Content, IngredientReferenceTarget, Item},
+    Recipe,
+};
+
+/// A node of a [`StepGraph`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(Tsify))]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StepNode {
+    /// A step
+    Step {
+        /// Index in [`Recipe::sections`]
+        section: usize,
+        /// Index in [`Section::content`](crate::model::Section::content). It's
+        /// guaranteed that the content is a step.
+        content: usize,
+    },
+    /// The output of a whole section
+    Section {
+        /// Index in [`Recipe::sections`]
+        section: usize,
+    },
+}
+
+impl StepNode {
+    /// Index of the section the node is in
+    pub fn section(&self) -> usize {
+        match *self {
+            StepNode::Step { section, .. } | StepNode::Section { section } => section,
+        }
+    }
+
+    /// Id of the node in the exported graphs
+    fn id(&self) -> String {
+        match self {
+            StepNode::Step { section, content } => format!("s{section}_{content}"),
+            StepNode::Section { section } => format!("sec{section}"),
+        }
+    }
+}
+
+/// Why a node depends on another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EdgeKind {
+    /// Uses the output of the node, like `@&(~1)dough{}`
+    UsesOutput,
+    /// Uses an ingredient first introduced in the node
+    UsesIngredient,
+    /// The step is part of the section
+    ///
+    /// Only steps that no other step of the section depends on have this
+    /// edge, the rest are part of the section through them.
+    PartOf,
+}
+
+/// A dependency in a [`StepGraph`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepEdge {
+    /// Node that has to be done first
+    pub from: StepNode,
+    /// Node that depends on `from`
+    pub to: StepNode,
+    /// Kind of dependency
+    pub kind: EdgeKind,
+    /// Indices in [`Recipe::ingredients`] of the ingredients in `to` that
+    /// create the dependency
+    ///
+    /// Empty for [`EdgeKind::PartOf`].
+    pub ingredients: Vec<usize>,
+}
+
+/// Dependency graph between the steps and sections of a recipe
+///
+/// Created from [`Recipe::step_graph`]. There are no cycles, because
+/// ingredients can only reference what came before them.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepGraph<'a> {
+    #[serde(skip)]
+    recipe: &'a Recipe,
+    nodes: Vec<StepNode>,
+    edges: Vec<StepEdge>,
+}
+
+impl Recipe {
+    /// Builds the dependency graph between the steps of the recipe
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Extensions, Converter};
+    /// # use cooklang::step_graph::{StepNode, EdgeKind};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::empty());
+    /// let recipe = parser
+    ///     .parse("Mix @flour{200%g} and @water.\n\nLet the @&(~1)dough{} rest.")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let graph = recipe.step_graph();
+    ///
+    /// let edge = &graph.edges()[0];
+    /// assert_eq!(edge.from, StepNode::Step { section: 0, content: 0 });
+    /// assert_eq!(edge.to, StepNode::Step { section: 0, content: 1 });
+    /// assert_eq!(edge.kind, EdgeKind::UsesOutput);
+    /// ```
+    pub fn step_graph(&self) -> StepGraph<'_> {
+        StepGraph::new(self)
+    }
+}
+
+impl<'a> StepGraph<'a> {
+    fn new(recipe: &'a Recipe) -> Self {
+        // where each ingredient is
+        let mut located_in = vec![None; recipe.ingredients.len()];
+        let mut nodes = Vec::new();
+        for (section_index, section) in recipe.sections.iter().enumerate() {
+            for (content_index, content) in section.content.iter().enumerate() {
+                let Content::Step(step) = content else {
+                    continue;
+                };
+                let node = StepNode::Step {
+                    section: section_index,
+                    content: content_index,
+                };
+                nodes.push(node);
+                for item in &step.items {
+                    if let Item::Ingredient { index } = item {
+                        located_in[*index] = Some(node);
+                    }
+                }
+            }
+            nodes.push(StepNode::Section {
+                section: section_index,
+            });
+        }
+
+        let mut edges: IndexMap<(StepNode, StepNode, EdgeKind), Vec<usize>> = IndexMap::new();
+        for (index, ingredient) in recipe.ingredients.iter().enumerate() {
+            let Some(to) = located_in[index] else {
+                continue;
+            };
+            let Some((target, target_kind)) = ingredient.relation.references_to() else {
+                continue;
+            };
+            let (from, kind) = match target_kind {
+                IngredientReferenceTarget::Ingredient => {
+                    let Some(from) = located_in[target] else {
+                        continue;
+                    };
+                    (from, EdgeKind::UsesIngredient)
+                }
+                IngredientReferenceTarget::Step => (
+                    StepNode::Step {
+                        section: to.section(),
+                        content: target,
+                    },
+                    EdgeKind::UsesOutput,
+                ),
+                IngredientReferenceTarget::Section => {
+                    (StepNode::Section { section: target }, EdgeKind::UsesOutput)
+                }
+            };
+            if from == to {
+                continue;
+            }
+            edges.entry((from, to, kind)).or_default().push(index);
+        }
+
+        let mut edges = edges
+            .into_iter()
+            .map(|((from, to, kind), ingredients)| StepEdge {
+                from,
+                to,
+                kind,
+                ingredients,
+            })
+            .collect::<Vec<_>>();
+
+        // only the last steps of a chain are linked to the section
+        let part_of = nodes
+            .iter()
+            .filter(|node| matches!(node, StepNode::Step { .. }))
+            .filter(|node| {
+                !edges.iter().any(|e| {
+                    e.from == **node
+                        && matches!(e.to, StepNode::Step { section, .. } if section == node.section())
+                })
+            })
+            .map(|node| StepEdge {
+                from: *node,
+                to: StepNode::Section {
+                    section: node.section(),
+                },
+                kind: EdgeKind::PartOf,
+                ingredients: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        edges.extend(part_of);
+
+        Self {
+            recipe,
+            nodes,
+            edges,
+        }
+    }
+
+    /// All the nodes
+    ///
+    /// They are in recipe order, which is also a topological order: every
+    /// node comes after all of its dependencies. Each section node comes
+    /// after its steps.
+    pub fn nodes(&self) -> &[StepNode] {
+        &self.nodes
+    }
+
+    /// All the edges
+    pub fn edges(&self) -> &[StepEdge] {
+        &self.edges
+    }
+
+    /// Edges to the nodes `node` depends on
+    pub fn dependencies(&self, node: StepNode) -> impl Iterator<Item = &StepEdge> {
+        self.edges.iter().filter(move |e| e.to == node)
+    }
+
+    /// Edges to the nodes that depend on `node`
+    pub fn dependents(&self, node: StepNode) -> impl Iterator<Item = &StepEdge> {
+        self.edges.iter().filter(move |e| e.from == node)
+    }
+
+    /// Human readable label of a node
+    ///
+    /// Steps are `Step <number>` and sections their name or
+    /// `Section <number>` if they don't have one.
+    pub fn label(&self, node: StepNode) -> String {
+        match node {
+            StepNode::Step { section, content } => {
+                let number = self.recipe.sections[section].content[content]
+                    .unwrap_step()
+                    .number;
+                format!("Step {number}")
+            }
+            StepNode::Section { section } => self.section_label(section),
+        }
+    }
+
+    fn section_label(&self, section: usize) -> String {
+        self.recipe.sections[section]
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Section {}", section + 1))
+    }
+
+    fn edge_label(&self, edge: &StepEdge) -> String {
+        let mut names: Vec<String> = Vec::new();
+        for &i in &edge.ingredients {
+            let name = self.recipe.ingredients[i].display_name().into_owned();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names.join(", ")
+    }
+
+    /// Groups the nodes by section, in order
+    fn sections(&self) -> impl Iterator<Item = (usize, &[StepNode])> {
+        self.nodes
+            .chunk_by(|a, b| a.section() == b.section())
+            .map(|nodes| (nodes[0].section(), nodes))
+    }
+
+    /// Exports the graph in the [DOT](https://graphviz.org/doc/info/lang.html)
+    /// language
+    ///
+    /// Named sections are drawn as clusters and section nodes as boxes.
+    /// Edges are labelled with the ingredients that create them and
+    /// [`EdgeKind::PartOf`] edges are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph recipe {\n");
+        for (section, nodes) in self.sections() {
+            let named = self.recipe.sections[section].name.is_some();
+            let indent = if named { "    " } else { "  " };
+            if named {
+                writeln!(out, "  subgraph cluster_{section} {{").unwrap();
+                writeln!(
+                    out,
+                    "    label=\"{}\";",
+                    dot_escape(&self.section_label(section))
+                )
+                .unwrap();
+            }
+            for node in nodes {
+                let shape = match node {
+                    StepNode::Step { .. } => "",
+                    StepNode::Section { .. } => ", shape=box",
+                };
+                writeln!(
+                    out,
+                    "{indent}{} [label=\"{}\"{shape}];",
+                    node.id(),
+                    dot_escape(&self.label(*node))
+                )
+                .unwrap();
+            }
+            if named {
+                out.push_str("  }\n");
+            }
+        }
+        for edge in &self.edges {
+            write!(out, "  {} -> {}", edge.from.id(), edge.to.id()).unwrap();
+            match edge.kind {
+                EdgeKind::PartOf => out.push_str(" [style=dashed]"),
+                _ => write!(out, " [label=\"{}\"]", dot_escape(&self.edge_label(edge))).unwrap(),
+            }
+            out.push_str(";\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Exports the graph as a [Mermaid](https://mermaid.js.org/syntax/flowchart.html)
+    /// flowchart
+    ///
+    /// Named sections are drawn as subgraphs. Edges are labelled with the
+    /// ingredients that create them and [`EdgeKind::PartOf`] edges are
+    /// dotted.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for (section, nodes) in self.sections() {
+            let named = self.recipe.sections[section].name.is_some();
+            let indent = if named { "    " } else { "  " };
+            if named {
+                writeln!(
+                    out,
+                    "  subgraph cluster_{section} [\"{}\"]",
+                    mermaid_escape(&self.section_label(section))
+                )
+                .unwrap();
+            }
+            for node in nodes {
+                let label = mermaid_escape(&self.label(*node));
+                match node {
+                    StepNode::Step { .. } => writeln!(out, "{indent}{}[\"{label}\"]", node.id()),
+                    StepNode::Section { .. } => {
+                        writeln!(out, "{indent}{}[[\"{label}\"]]", node.id())
+                    }
+                }
+                .unwrap();
+            }
+            if named {
+                out.push_str("  end\n");
+            }
+        }
+        for edge in &self.edges {
+            let (from, to) = (edge.from.id(), edge.to.id());
+            match edge.kind {
+                EdgeKind::PartOf => writeln!(out, "  {from} -.-> {to}"),
+                _ => writeln!(
+                    out,
+                    "  {from} -->|\"{}\"| {to}",
+                    mermaid_escape(&self.edge_label(edge))
+                ),
+            }
+            .unwrap();
+        }
+        out
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
@@ -0,0 +1,118 @@
+//! "Did you mean" suggestions for misspelled names
+
+/// Finds the candidate closest to `word`, if it's close enough to be a typo
+///
+/// If `word` is one of the candidates there is no suggestion. The
+/// comparison is case insensitive and a swap of 2 adjacent chars counts
+/// as a single edit. Short words (2 chars or less) are never suggested nor
+/// get suggestions and the first char has to match, otherwise common words
+/// like `pinch` or `mug` would be taken for typos of `inch` or `mg`. Only
+/// long words (8 chars or more) can be 2 edits away, so real words like
+/// `grains` are not taken for `grams`. Ties are resolved with the first
+/// candidate in lexicographic order, so the result does not depend on the
+/// iteration order of `candidates`.
+pub(crate) fn closest<'a>(
+    word: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let word_chars = word.to_lowercase().chars().collect::<Vec<_>>();
+    let max = match word_chars.len() {
+        0..=2 => return None,
+        3..=7 => 1,
+        _ => 2,
+    };
+
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        if candidate == word {
+            // not a typo
+            return None;
+        }
+        let candidate_chars = candidate.to_lowercase().chars().collect::<Vec<_>>();
+        if candidate_chars.len() <= 2
+            || candidate_chars.len().abs_diff(word_chars.len()) > max
+            || candidate_chars.first() != word_chars.first()
+        {
+            continue;
+        }
+        let d = distance(&word_chars, &candidate_chars);
+        if d > max {
+            continue;
+        }
+        if best.is_none_or(|(best_d, best_c)| (d, candidate) < (best_d, best_c)) {
+            best = Some((d, candidate));
+        }
+    }
+    best.map(|(_, c)| c)
+}
+
+/// Hint text for a suggestion
+pub(crate) fn did_you_mean(suggestion: &str) -> String {
+    format!("Did you mean '{suggestion}'?")
+}
+
+/// Optimal string alignment distance
+///
+/// Like the Levenshtein distance but transpositions of adjacent chars are a
+/// single edit.
+fn distance(a: &[char], b: &[char]) -> usize {
+    // 3 rows are enough: current, previous and the one before for
+    // transpositions
+    let mut prev2 = vec![0; b.len() + 1];
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut cur = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        cur[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                cur[j] = cur[j].min(prev2[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn d(a: &str, b: &str) -> usize {
+        let a = a.chars().collect::<Vec<_>>();
+        let b = b.chars().collect::<Vec<_>>();
+        distance(&a, &b)
+    }
+
+    #[test_case("", "" => 0)]
+    #[test_case("abc", "" => 3)]
+    #[test_case("kitten", "sitting" => 3)]
+    #[test_case("tsp", "tps" => 1; "transposition")]
+    #[test_case("servigs", "servings" => 1)]
+    #[test_case("ºC", "°C" => 1; "unicode")]
+    fn osa_distance(a: &str, b: &str) -> usize {
+        d(a, b)
+    }
+
+    #[test_case("servigs" => Some("servings"))]
+    #[test_case("Tbsp" => Some("tbsp"); "case")]
+    #[test_case("tps" => Some("tsp"))]
+    #[test_case("gr" => None; "too short")]
+    #[test_case("handful" => None)]
+    #[test_case("serbigns" => Some("servings"); "long word")]
+    #[test_case("serbics" => None; "too far")]
+    #[test_case("tsp" => None; "exact")]
+    fn closest_candidate(word: &str) -> Option<&'static str> {
+        closest(word, ["tsp", "tbsp", "servings", "serves", "cup"])
+    }
+
+    #[test]
+    fn deterministic_ties() {
+        assert_eq!(closest("cap", ["cup", "cat"]), Some("cat"));
+        assert_eq!(closest("cap", ["cat", "cup"]), Some("cat"));
+    }
+}
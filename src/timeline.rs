@@ -0,0 +1,891 @@
+//! Plan when to do each step of a recipe
+//!
+//! Steps are scheduled following the [`StepGraph`]. Each step has some
+//! active time, where a cook has to be working on it, followed by the
+//! passive time of its timers, like letting the dough rise or baking. While a
+//! step is passive, the cooks can work on other independent steps. The
+//! cookware of a step is in use during all the step, so steps sharing an oven
+//! can't overlap unless it has room for more, see
+//! [`TimelineOptions::cookware_capacity`].
+//!
+//! Several recipes cooked together are planned with [`MealTimeline`].
+//!
+//! ```
+//! # use cooklang::{CooklangParser, Extensions, Converter};
+//! # use cooklang::timeline::{TimelineOptions, ClockTime};
+//! let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+//! let recipe = parser
+//!     .parse(indoc::indoc! {"
+//!         Mix @flour{500%g} and @water{300%ml}.
+//!
+//!         Let the @&(~1)dough{} rise for ~{1%hour}.
+//!
+//!         Chop @onion{2}.
+//!
+//!         Bake the @&(2)dough{} with the @&onion for ~{30%min}.
+//!     "})
+//!     .into_output()
+//!     .unwrap();
+//! let options = TimelineOptions {
+//!     sequential: false,
+//!     ..Default::default()
+//! };
+//! let timeline = recipe.timeline(parser.converter(), &options);
+//!
+//! // the onion is chopped while the dough rises
+//! assert_eq!(timeline.total, 5.0 + 5.0 + 60.0 + 5.0 + 30.0);
+//!
+//! let dinner = ClockTime::new(20, 0).unwrap();
+//! assert_eq!(timeline.start_at(dinner).to_string(), "18:15");
+//! ```
+
+use std::{cmp::Ordering, collections::HashMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    convert::{Converter, PhysicalQuantity},
+    metadata::{time_to_minutes, RecipeTime},
+    model::{Cookware, Item},
+    quantity::{Quantity, Value},
+    step_graph::{StepGraph, StepNode},
+    Metadata, Recipe,
+};
+
+/// Options for [`Recipe::timeline`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimelineOptions {
+    /// Minutes of hands-on work of each step, apart from its timers
+    ///
+    /// Recipes do not say how long it takes to chop an onion, so every step
+    /// gets the same estimate. By default 5.
+    pub active_minutes: f64,
+    /// Number of people cooking
+    ///
+    /// At most this number of steps are active at the same time. By default 1.
+    pub cooks: usize,
+    /// Make steps without dependencies wait for the previous step in their
+    /// section
+    ///
+    /// Most recipes do not use references for every step, and a step like
+    /// `Put it in the oven for ~{30%min}` still goes after the previous one.
+    /// Disable it to do the steps without dependencies at any moment, for
+    /// recipes where references mark everything a step depends on. By
+    /// default `true`.
+    pub sequential: bool,
+    /// How many steps can use a cookware item at the same time
+    ///
+    /// The keys are cookware names, case insensitive. Cookware that is not
+    /// here can only be used by one step at a time. A step that needs more
+    /// than one, like `#burner{2}`, uses more of the capacity.
+    ///
+    /// Steps sharing a cookware item at the same time must be at the same
+    /// temperature, so a single oven can bake two trays at 180 °C but not one
+    /// at 180 °C and another at 220 °C.
+    pub cookware_capacity: HashMap<String, usize>,
+}
+
+impl Default for TimelineOptions {
+    fn default() -> Self {
+        Self {
+            active_minutes: 5.0,
+            cooks: 1,
+            sequential: true,
+            cookware_capacity: HashMap::new(),
+        }
+    }
+}
+
+/// A step placed in a [`Timeline`]
+///
+/// All times are in minutes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineStep {
+    /// The step. It's always a [`StepNode::Step`].
+    pub node: StepNode,
+    /// Minutes from the start of the recipe
+    pub start: f64,
+    /// Hands-on time, it goes first
+    pub active: f64,
+    /// Time of the timers of the step, after the active time
+    pub passive: f64,
+    /// Index of the cook doing the step, starting at 0
+    pub cook: usize,
+    /// If the step is in the critical path
+    pub critical: bool,
+    /// Indices in [`Recipe::cookware`] of the cookware the step uses during
+    /// all its duration
+    pub cookware: Vec<usize>,
+    /// First temperature inline quantity of the step
+    ///
+    /// Only with the
+    /// [`INLINE_QUANTITIES`](crate::Extensions::INLINE_QUANTITIES) extension.
+    pub temperature: Option<Quantity>,
+}
+
+impl TimelineStep {
+    /// Minutes from the start of the recipe until the step is finished
+    pub fn end(&self) -> f64 {
+        self.start + self.active + self.passive
+    }
+
+    /// Total duration of the step
+    pub fn duration(&self) -> f64 {
+        self.active + self.passive
+    }
+}
+
+/// Schedule of the steps of a recipe
+///
+/// Created from [`Recipe::timeline`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Timeline {
+    /// Steps ordered by start time
+    pub steps: Vec<TimelineStep>,
+    /// Minutes from the start until everything is done
+    pub total: f64,
+    /// Longest chain of dependent steps
+    ///
+    /// No schedule can be shorter than the sum of the durations of these
+    /// steps, but it can be longer if the cooks are busy.
+    pub critical_path: Vec<StepNode>,
+    /// Indices in [`Recipe::timers`] of the timers without a known duration
+    ///
+    /// They are not taken into account.
+    pub unknown_timers: Vec<usize>,
+}
+
+impl Recipe {
+    /// Plans when to do each step
+    ///
+    /// Timers durations are converted to minutes with the converter. When a
+    /// timer is a range, the longest time is used.
+    pub fn timeline(&self, converter: &Converter, options: &TimelineOptions) -> Timeline {
+        Timeline::new(self, &self.step_graph(), converter, options)
+    }
+}
+
+impl Timeline {
+    fn new(
+        recipe: &Recipe,
+        graph: &StepGraph,
+        converter: &Converter,
+        options: &TimelineOptions,
+    ) -> Self {
+        let mut resources = Resources::new(options);
+        let Tasks {
+            tasks,
+            critical_path,
+            unknown_timers,
+        } = Tasks::new(0, recipe, graph, converter, options, &mut resources);
+        let starts = schedule(&tasks, &resources, converter, options, false);
+        let mut steps = tasks
+            .into_iter()
+            .zip(starts)
+            .filter_map(|(task, start)| task.into_step(start))
+            .collect::<Vec<_>>();
+        steps.sort_by(|a, b| a.start.total_cmp(&b.start));
+        Self {
+            total: steps.iter().map(TimelineStep::end).fold(0.0, f64::max),
+            steps,
+            critical_path,
+            unknown_timers,
+        }
+    }
+
+    /// Sum of the active time of all steps
+    pub fn active_time(&self) -> f64 {
+        self.steps.iter().map(|s| s.active).sum()
+    }
+
+    /// Sum of the passive time of all steps
+    pub fn passive_time(&self) -> f64 {
+        self.steps.iter().map(|s| s.passive).sum()
+    }
+
+    /// Finds a step in the timeline
+    pub fn step(&self, node: StepNode) -> Option<&TimelineStep> {
+        self.steps.iter().find(|s| s.node == node)
+    }
+
+    /// When to start cooking to have everything ready at `ready_at`
+    pub fn start_at(&self, ready_at: ClockTime) -> ClockTime {
+        clock_time(self.total, 0.0, ready_at)
+    }
+
+    /// When to start each step to have everything ready at `ready_at`
+    ///
+    /// In the same order as [`Timeline::steps`].
+    pub fn clock_times(&self, ready_at: ClockTime) -> Vec<(&TimelineStep, ClockTime)> {
+        self.steps
+            .iter()
+            .map(|s| (s, clock_time(self.total, s.start, ready_at)))
+            .collect()
+    }
+
+    /// Compares the timeline with the time in the metadata
+    ///
+    /// Returns [`None`] if the recipe has no time in the metadata.
+    pub fn compare_time(
+        &self,
+        metadata: &Metadata,
+        converter: &Converter,
+    ) -> Option<TimeComparison> {
+        metadata.time(converter).map(|expected| TimeComparison {
+            expected,
+            total: self.total,
+            active: self.active_time(),
+        })
+    }
+}
+
+/// A step of a recipe in a [`MealTimeline`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MealStep {
+    /// Index of the recipe in the list given to [`MealTimeline::new`]
+    pub recipe: usize,
+    /// The step
+    #[serde(flatten)]
+    pub step: TimelineStep,
+}
+
+/// Schedule of several recipes cooked together
+///
+/// The cooks and the cookware are shared between all the recipes, and the
+/// steps are placed as late as possible so all the recipes are ready at the
+/// same time.
+///
+/// ```
+/// # use cooklang::{CooklangParser, Extensions, Converter};
+/// # use cooklang::timeline::{MealTimeline, TimelineOptions};
+/// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+/// let roast = parser
+///     .parse("Roast the @turkey in the #oven at 180 °C for ~{3%hours}.")
+///     .into_output()
+///     .unwrap();
+/// let pie = parser
+///     .parse("Bake the @pie in the #oven at 200 °C for ~{45%min}.")
+///     .into_output()
+///     .unwrap();
+///
+/// let meal = MealTimeline::new(&[&roast, &pie], parser.converter(), &TimelineOptions::default());
+///
+/// // there is only one oven, so the pie is baked before the turkey
+/// assert_eq!(meal.total, 5.0 + 45.0 + 5.0 + 180.0);
+/// assert_eq!(meal.ready, [meal.total, 50.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MealTimeline {
+    /// Steps ordered by start time
+    pub steps: Vec<MealStep>,
+    /// Minutes from the start until everything is done
+    pub total: f64,
+    /// Minutes from the start until each recipe is done
+    ///
+    /// Usually all are [`MealTimeline::total`], but some may be ready
+    /// earlier if the cooks or the cookware are busy at the end.
+    pub ready: Vec<f64>,
+    /// Indices of the recipe and the timer in [`Recipe::timers`] of the
+    /// timers without a known duration
+    ///
+    /// They are not taken into account.
+    pub unknown_timers: Vec<(usize, usize)>,
+}
+
+impl MealTimeline {
+    /// Plans when to do each step of several recipes
+    ///
+    /// See [`Recipe::timeline`].
+    pub fn new(recipes: &[&Recipe], converter: &Converter, options: &TimelineOptions) -> Self {
+        let mut resources = Resources::new(options);
+        let mut all_tasks = Vec::new();
+        let mut unknown_timers = Vec::new();
+        let mut per_recipe = Vec::new();
+        for (index, recipe) in recipes.iter().enumerate() {
+            let graph = recipe.step_graph();
+            let mut tasks = Tasks::new(index, recipe, &graph, converter, options, &mut resources);
+            let offset = all_tasks.len();
+            for task in &mut tasks.tasks {
+                task.deps.iter_mut().for_each(|d| *d += offset);
+            }
+            unknown_timers.extend(tasks.unknown_timers.iter().map(|&t| (index, t)));
+            per_recipe.push(offset..offset + tasks.tasks.len());
+            all_tasks.append(&mut tasks.tasks);
+        }
+
+        let starts = schedule(&all_tasks, &resources, converter, options, true);
+        let ends = |range: std::ops::Range<usize>| {
+            range
+                .filter(|&i| matches!(all_tasks[i].node, StepNode::Step { .. }))
+                .map(|i| starts[i].0 + all_tasks[i].duration())
+                .fold(0.0, f64::max)
+        };
+        let ready = per_recipe.into_iter().map(ends).collect::<Vec<_>>();
+        let total = ready.iter().copied().fold(0.0, f64::max);
+
+        let mut steps = all_tasks
+            .into_iter()
+            .zip(starts)
+            .filter_map(|(task, start)| {
+                Some(MealStep {
+                    recipe: task.recipe,
+                    step: task.into_step(start)?,
+                })
+            })
+            .collect::<Vec<_>>();
+        steps.sort_by(|a, b| a.step.start.total_cmp(&b.step.start));
+
+        Self {
+            steps,
+            total,
+            ready,
+            unknown_timers,
+        }
+    }
+
+    /// When to start cooking to have everything ready at `ready_at`
+    pub fn start_at(&self, ready_at: ClockTime) -> ClockTime {
+        clock_time(self.total, 0.0, ready_at)
+    }
+
+    /// When to start each step to have everything ready at `ready_at`
+    ///
+    /// In the same order as [`MealTimeline::steps`].
+    pub fn clock_times(&self, ready_at: ClockTime) -> Vec<(&MealStep, ClockTime)> {
+        self.steps
+            .iter()
+            .map(|s| (s, clock_time(self.total, s.step.start, ready_at)))
+            .collect()
+    }
+}
+
+/// Clock time of a moment in a timeline, rounded down to the minute
+fn clock_time(total: f64, minutes: f64, ready_at: ClockTime) -> ClockTime {
+    ClockTime(ready_at.0 + (minutes - total).floor() as i64)
+}
+
+/// A step or section to schedule
+struct Task {
+    recipe: usize,
+    node: StepNode,
+    active: f64,
+    passive: f64,
+    /// Indices of the tasks it depends on
+    deps: Vec<usize>,
+    critical: bool,
+    cookware: Vec<usize>,
+    /// Resource index and amount
+    resources: Vec<(usize, usize)>,
+    temperature: Option<Quantity>,
+}
+
+impl Task {
+    fn duration(&self) -> f64 {
+        self.active + self.passive
+    }
+
+    /// The step, if the task is a step
+    fn into_step(self, (start, cook): (f64, usize)) -> Option<TimelineStep> {
+        matches!(self.node, StepNode::Step { .. }).then_some(TimelineStep {
+            node: self.node,
+            start,
+            active: self.active,
+            passive: self.passive,
+            cook,
+            critical: self.critical,
+            cookware: self.cookware,
+            temperature: self.temperature,
+        })
+    }
+}
+
+/// Tasks of a recipe
+struct Tasks {
+    tasks: Vec<Task>,
+    critical_path: Vec<StepNode>,
+    unknown_timers: Vec<usize>,
+}
+
+impl Tasks {
+    fn new(
+        recipe_index: usize,
+        recipe: &Recipe,
+        graph: &StepGraph,
+        converter: &Converter,
+        options: &TimelineOptions,
+        resources: &mut Resources,
+    ) -> Self {
+        let nodes = graph.nodes();
+        let position = |node: StepNode| nodes.iter().position(|n| *n == node).unwrap();
+
+        let mut unknown_timers = Vec::new();
+        let mut tasks = nodes
+            .iter()
+            .map(|&node| {
+                let mut task = Task {
+                    recipe: recipe_index,
+                    node,
+                    active: 0.0,
+                    passive: 0.0,
+                    deps: graph.dependencies(node).map(|e| position(e.from)).collect(),
+                    critical: false,
+                    cookware: Vec::new(),
+                    resources: Vec::new(),
+                    temperature: None,
+                };
+                let StepNode::Step { section, content } = node else {
+                    return task;
+                };
+                task.active = options.active_minutes;
+                let step = recipe.sections[section].content[content].unwrap_step();
+                for item in &step.items {
+                    match *item {
+                        Item::Timer { index } => match timer_minutes(recipe, index, converter) {
+                            Some(minutes) => task.passive += minutes,
+                            None => unknown_timers.push(index),
+                        },
+                        Item::Cookware { index } => {
+                            let cookware = &recipe.cookware[index];
+                            task.cookware.push(index);
+                            task.resources
+                                .push(resources.get(&cookware.name, cookware_amount(cookware)));
+                        }
+                        Item::InlineQuantity { index } if task.temperature.is_none() => {
+                            let q = &recipe.inline_quantities[index];
+                            if is_temperature(q, converter) {
+                                task.temperature = Some(q.clone());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                task
+            })
+            .collect::<Vec<_>>();
+
+        if options.sequential {
+            for i in 1..nodes.len() {
+                let (prev, node) = (nodes[i - 1], nodes[i]);
+                if tasks[i].deps.is_empty()
+                    && matches!(node, StepNode::Step { .. })
+                    && matches!(prev, StepNode::Step { .. })
+                    && prev.section() == node.section()
+                {
+                    tasks[i].deps.push(i - 1);
+                }
+            }
+        }
+
+        // critical path, nodes are in topological order
+        let mut earliest_end = vec![0.0f64; tasks.len()];
+        for i in 0..tasks.len() {
+            let ready = tasks[i]
+                .deps
+                .iter()
+                .map(|&d| earliest_end[d])
+                .fold(0.0, f64::max);
+            earliest_end[i] = ready + tasks[i].duration();
+        }
+        let mut critical_path = Vec::new();
+        let mut current = (0..tasks.len())
+            .rev()
+            .max_by(|&a, &b| earliest_end[a].total_cmp(&earliest_end[b]));
+        while let Some(i) = current {
+            tasks[i].critical = true;
+            if matches!(nodes[i], StepNode::Step { .. }) {
+                critical_path.push(nodes[i]);
+            }
+            let ready = earliest_end[i] - tasks[i].duration();
+            current = tasks[i]
+                .deps
+                .iter()
+                .copied()
+                .filter(|&d| earliest_end[d] == ready)
+                .max_by_key(|&d| d);
+        }
+        critical_path.reverse();
+
+        Self {
+            tasks,
+            critical_path,
+            unknown_timers,
+        }
+    }
+}
+
+/// Duration of a timer in minutes
+fn timer_minutes(recipe: &Recipe, index: usize, converter: &Converter) -> Option<f64> {
+    let quantity = recipe.timers[index].quantity.as_ref()?;
+    let value = match quantity.value() {
+        Value::Number(n) => n.value(),
+        Value::Range { end, .. } => end.value(),
+        Value::Text(_) => return None,
+    };
+    time_to_minutes(value, quantity.unit()?, converter).ok()
+}
+
+/// How many units of a cookware item a step needs
+fn cookware_amount(cookware: &Cookware) -> usize {
+    match cookware.quantity.as_ref().map(|q| q.value()) {
+        Some(Value::Number(n)) if n.value() >= 1.0 => n.value().ceil() as usize,
+        _ => 1,
+    }
+}
+
+fn is_temperature(q: &Quantity, converter: &Converter) -> bool {
+    q.unit()
+        .and_then(|u| converter.find_unit(u))
+        .is_some_and(|u| u.physical_quantity == PhysicalQuantity::Temperature)
+}
+
+/// Checks if 2 temperatures are less than a degree apart
+///
+/// Conversions between temperature units are not exact, 356 °F is not
+/// exactly 180 °C after converting it.
+fn same_temperature(a: &Quantity, b: &Quantity, converter: &Converter) -> bool {
+    let mut b = b.clone();
+    if let Some(unit) = a.unit() {
+        if b.convert(unit, None, converter).is_err() {
+            return false;
+        }
+    }
+    match (a.value(), b.value()) {
+        (Value::Number(x), Value::Number(y)) => (x.value() - y.value()).abs() < 1.0,
+        (x, y) => matches!(x.try_cmp(y), Ok(Some(Ordering::Equal))),
+    }
+}
+
+/// Shared cookware
+struct Resources {
+    /// Lowercase name and capacity
+    list: Vec<(String, usize)>,
+    capacities: HashMap<String, usize>,
+}
+
+impl Resources {
+    fn new(options: &TimelineOptions) -> Self {
+        Self {
+            list: Vec::new(),
+            capacities: options
+                .cookware_capacity
+                .iter()
+                .map(|(name, &capacity)| (name.to_lowercase(), capacity.max(1)))
+                .collect(),
+        }
+    }
+
+    /// Index of the resource and the amount needed, never more than the
+    /// capacity
+    fn get(&mut self, name: &str, amount: usize) -> (usize, usize) {
+        let name = name.to_lowercase();
+        let index = match self.list.iter().position(|(n, _)| *n == name) {
+            Some(index) => index,
+            None => {
+                let capacity = self.capacities.get(&name).copied().unwrap_or(1);
+                self.list.push((name, capacity));
+                self.list.len() - 1
+            }
+        };
+        (index, amount.min(self.list[index].1))
+    }
+}
+
+/// A task using a resource or a cook
+struct Busy {
+    start: f64,
+    end: f64,
+    task: usize,
+    amount: usize,
+}
+
+impl Busy {
+    fn overlaps(&self, start: f64, end: f64) -> bool {
+        self.start < end && start < self.end
+    }
+}
+
+/// List scheduling of the tasks
+///
+/// Each task is placed at the earliest moment possible, where its
+/// dependencies are done, a cook is free for the active time and there is
+/// enough of its cookware for the whole duration. When many tasks are ready,
+/// the one with the longest chain of tasks after it goes first.
+///
+/// With `backward`, the time goes backwards from the end, so the tasks are
+/// placed as late as possible and the tasks without dependents all end at
+/// the same time if they can.
+///
+/// Returns the start and cook of each task.
+fn schedule(
+    tasks: &[Task],
+    resources: &Resources,
+    converter: &Converter,
+    options: &TimelineOptions,
+    backward: bool,
+) -> Vec<(f64, usize)> {
+    let n = tasks.len();
+
+    // dependencies and topological order in the direction of the time
+    let (deps, topo): (Vec<Vec<usize>>, Vec<usize>) = if backward {
+        let mut dependents = vec![Vec::new(); n];
+        for (i, task) in tasks.iter().enumerate() {
+            for &d in &task.deps {
+                dependents[d].push(i);
+            }
+        }
+        (dependents, (0..n).rev().collect())
+    } else {
+        (
+            tasks.iter().map(|t| t.deps.clone()).collect(),
+            (0..n).collect(),
+        )
+    };
+    // ties go in recipe order, in the direction of the time
+    let recipe_order = |i: usize| if backward { n - 1 - i } else { i };
+    // the active time is always at the start of the task
+    let cook_offset = |i: usize| if backward { tasks[i].passive } else { 0.0 };
+
+    // longest path from each task to the end
+    let mut tail = vec![0.0f64; n];
+    for &i in topo.iter().rev() {
+        tail[i] += tasks[i].duration();
+        for &d in &deps[i] {
+            tail[d] = tail[d].max(tail[i]);
+        }
+    }
+
+    let compatible = |a: &Option<Quantity>, b: &Option<Quantity>| match (a, b) {
+        (Some(a), Some(b)) => same_temperature(a, b, converter),
+        _ => true,
+    };
+
+    let mut cooks: Vec<Vec<Busy>> = (0..options.cooks.max(1)).map(|_| Vec::new()).collect();
+    let mut used: Vec<Vec<Busy>> = resources.list.iter().map(|_| Vec::new()).collect();
+    let mut start = vec![None::<(f64, usize)>; n];
+
+    // earliest start of a task from `ready` and the cook doing it
+    let fit = |i: usize, ready: f64, cooks: &[Vec<Busy>], used: &[Vec<Busy>]| {
+        let task = &tasks[i];
+        let offset = cook_offset(i);
+        let mut candidates = vec![ready];
+        if task.active > 0.0 {
+            candidates.extend(cooks.iter().flatten().map(|b| b.end - offset));
+        }
+        for &(r, _) in &task.resources {
+            candidates.extend(used[r].iter().map(|b| b.end));
+        }
+        candidates.retain(|&t| t >= ready);
+        candidates.sort_by(f64::total_cmp);
+
+        for t in candidates {
+            let cook = if task.active > 0.0 {
+                let (s, e) = (t + offset, t + offset + task.active);
+                match cooks
+                    .iter()
+                    .position(|c| !c.iter().any(|b| b.overlaps(s, e)))
+                {
+                    Some(cook) => cook,
+                    None => continue,
+                }
+            } else {
+                0
+            };
+            let end = t + task.duration();
+            let fits = end <= t
+                || task.resources.iter().all(|&(r, amount)| {
+                    let overlapping = used[r]
+                        .iter()
+                        .filter(|b| b.overlaps(t, end))
+                        .collect::<Vec<_>>();
+                    let temperatures_ok = overlapping
+                        .iter()
+                        .all(|b| compatible(&task.temperature, &tasks[b.task].temperature));
+                    let capacity = resources.list[r].1;
+                    let capacity_ok =
+                        overlapping
+                            .iter()
+                            .map(|b| b.start.max(t))
+                            .chain([t])
+                            .all(|p| {
+                                let in_use: usize = overlapping
+                                    .iter()
+                                    .filter(|b| b.start <= p && p < b.end)
+                                    .map(|b| b.amount)
+                                    .sum();
+                                in_use + amount <= capacity
+                            });
+                    temperatures_ok && capacity_ok
+                });
+            if fits {
+                return (t, cook);
+            }
+        }
+        unreachable!("the end of every busy interval is a candidate")
+    };
+
+    loop {
+        let next = (0..n)
+            .filter(|&i| start[i].is_none())
+            .filter_map(|i| {
+                let ready = deps[i]
+                    .iter()
+                    .map(|&d| start[d].map(|(s, _)| s + tasks[d].duration()))
+                    .try_fold(0.0f64, |acc, e| Some(acc.max(e?)))?;
+                let (at, cook) = fit(i, ready, &cooks, &used);
+                Some((i, at, cook))
+            })
+            .min_by(|a, b| {
+                a.1.total_cmp(&b.1)
+                    .then(tail[b.0].total_cmp(&tail[a.0]))
+                    .then(recipe_order(a.0).cmp(&recipe_order(b.0)))
+            });
+        let Some((i, at, cook)) = next else {
+            break;
+        };
+        let task = &tasks[i];
+        start[i] = Some((at, cook));
+        if task.active > 0.0 {
+            let s = at + cook_offset(i);
+            cooks[cook].push(Busy {
+                start: s,
+                end: s + task.active,
+                task: i,
+                amount: 1,
+            });
+        }
+        if task.duration() > 0.0 {
+            for &(r, amount) in &task.resources {
+                used[r].push(Busy {
+                    start: at,
+                    end: at + task.duration(),
+                    task: i,
+                    amount,
+                });
+            }
+        }
+    }
+
+    let start = start.into_iter().map(Option::unwrap).collect::<Vec<_>>();
+    if !backward {
+        return start;
+    }
+    let total = start
+        .iter()
+        .zip(tasks)
+        .map(|((s, _), t)| s + t.duration())
+        .fold(0.0, f64::max);
+    start
+        .into_iter()
+        .zip(tasks)
+        .map(|((s, cook), t)| (total - s - t.duration(), cook))
+        .collect()
+}
+
+/// Result of [`Timeline::compare_time`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeComparison {
+    /// Time in the metadata
+    pub expected: RecipeTime,
+    /// Total minutes of the timeline
+    pub total: f64,
+    /// Active minutes of the timeline
+    pub active: f64,
+}
+
+impl TimeComparison {
+    /// Minutes the timeline takes more than the metadata says
+    ///
+    /// Negative if the timeline is shorter.
+    pub fn difference(&self) -> f64 {
+        self.total - self.expected.total() as f64
+    }
+
+    /// Minutes the active time takes more than the preparation time in the
+    /// metadata
+    ///
+    /// Only if the metadata has a preparation time.
+    pub fn prep_difference(&self) -> Option<f64> {
+        match self.expected {
+            RecipeTime::Composed {
+                prep_time: Some(prep),
+                ..
+            } => Some(self.active - prep as f64),
+            _ => None,
+        }
+    }
+
+    /// Checks if the total time of the timeline and the metadata are within
+    /// `tolerance`, a fraction of the metadata time
+    ///
+    /// For example, with a tolerance of `0.25`, a timeline of 70 minutes is
+    /// consistent with a total time of 1 hour, but one of 80 minutes is not.
+    pub fn is_consistent(&self, tolerance: f64) -> bool {
+        self.difference().abs() <= self.expected.total() as f64 * tolerance
+    }
+}
+
+/// Time of the day with an offset in days
+///
+/// Used to tell when to start cooking. Times before the day the dish is
+/// ready have a negative [`day`](ClockTime::day).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ClockTime(i64);
+
+const MINUTES_IN_DAY: i64 = 24 * 60;
+
+impl ClockTime {
+    /// Creates a new time of the day
+    ///
+    /// Returns [`None`] if the hour or minute are out of range.
+    pub fn new(hour: u32, minute: u32) -> Option<Self> {
+        (hour < 24 && minute < 60).then_some(Self(hour as i64 * 60 + minute as i64))
+    }
+
+    /// Hour of the day
+    pub fn hour(&self) -> u32 {
+        (self.0.rem_euclid(MINUTES_IN_DAY) / 60) as u32
+    }
+
+    /// Minute of the hour
+    pub fn minute(&self) -> u32 {
+        (self.0.rem_euclid(60)) as u32
+    }
+
+    /// Days from the day the dish is ready, 0 is the same day
+    pub fn day(&self) -> i64 {
+        self.0.div_euclid(MINUTES_IN_DAY)
+    }
+}
+
+impl fmt::Display for ClockTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())?;
+        match self.day() {
+            0 => Ok(()),
+            1 | -1 => write!(f, " ({:+} day)", self.day()),
+            day => write!(f, " ({day:+} days)"),
+        }
+    }
+}
+
+/// Error parsing a [`ClockTime`]
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Invalid time '{0}', expected 'HH:MM'")]
+pub struct ParseClockTimeError(String);
+
+impl FromStr for ClockTime {
+    type Err = ParseClockTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseClockTimeError(s.to_string());
+        let (hour, minute) = s.trim().split_once(':').ok_or_else(err)?;
+        if minute.len() != 2 {
+            return Err(err());
+        }
+        let hour = hour.parse().map_err(|_| err())?;
+        let minute = minute.parse().map_err(|_| err())?;
+        Self::new(hour, minute).ok_or_else(err)
+    }
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    convert::ConvertError, Converter, CooklangParser, Extensions, Quantity, Recipe, Value,
+};
+use test_case::test_case;
+
+fn q(value: f64, unit: &str) -> Quantity {
+    Quantity::new(Value::from(value), Some(unit.to_string()))
+}
+
+fn parse(input: &str) -> (Recipe, usize) {
+    let (recipe, report) = CooklangParser::new(Extensions::all(), Converter::bundled())
+        .parse(input)
+        .into_tuple();
+    (recipe.unwrap(), report.errors().count())
+}
+
+#[test_case("@beef{1%lb 4%oz}" => q(1.25, "lb"); "lb oz")]
+#[test_case("@beef{1%kg 500%g}" => q(1.5, "kg"); "kg g")]
+#[test_case("@milk{1%l 250%ml}" => q(1.25, "l"); "l ml")]
+#[test_case("@beef{1/2%lb 4%oz}" => q(0.75, "lb"); "fraction first")]
+#[test_case("@beef{1%lb 1/2%oz}" => q(1.03125, "lb"); "fraction part")]
+#[test_case("@beef{1%lb  4 %oz}" => q(1.25, "lb"); "spaces")]
+fn parse_ingredient(input: &str) -> Quantity {
+    let (recipe, errors) = parse(input);
+    assert_eq!(errors, 0);
+    recipe.ingredients[0].quantity.clone().unwrap()
+}
+
+#[test]
+fn parse_timer() {
+    let (recipe, errors) = parse("Bake for ~{1%h 30%min}.");
+    assert_eq!(errors, 0);
+    let quantity = recipe.timers[0].quantity.as_ref().unwrap();
+    assert_eq!(quantity, &q(1.5, "h"));
+}
+
+#[test]
+fn scale_parsed() {
+    let (mut recipe, _) = parse("@beef{1%lb 4%oz}");
+    recipe.scale(2.0, &Converter::bundled());
+    let quantity = recipe.ingredients[0].quantity.as_ref().unwrap();
+    let compound = quantity.to_compound(&Converter::bundled()).unwrap();
+    assert_eq!(compound.to_string(), "2 lb 8 oz");
+}
+
+#[test_case("@beef{1%lb 4%ml}"; "mixed quantities")]
+#[test_case("@beef{1%lb 4%stuff}"; "unknown unit")]
+#[test_case("@beef{1%lb 4%oz six%g}"; "text part")]
+#[test_case("@oven{180%°C 5%°C}"; "temperature")]
+fn parse_invalid(input: &str) {
+    let (_, errors) = parse(input);
+    assert_eq!(errors, 1);
+}
+
+#[test]
+fn not_a_compound() {
+    let (recipe, errors) = parse("@tea{1%cup of 2%stuff}");
+    assert_eq!(errors, 0);
+    let q = recipe.ingredients[0].quantity.as_ref().unwrap();
+    assert_eq!(q.unit(), Some("cup of 2%stuff"));
+}
+
+#[test]
+fn disabled() {
+    let recipe = CooklangParser::new(
+        Extensions::all() ^ Extensions::ADVANCED_UNITS,
+        Converter::bundled(),
+    )
+    .parse("@beef{1%lb 4%oz}")
+    .into_output()
+    .unwrap();
+    let q = recipe.ingredients[0].quantity.as_ref().unwrap();
+    assert_eq!(q.unit(), Some("lb 4%oz"));
+}
+
+#[test_case(q(1.5, "h") => "1 h 30 min")]
+#[test_case(q(90.0, "min") => "1 h 30 min"; "from min")]
+#[test_case(q(5415.0, "s") => "1 h 30 min 15 s")]
+#[test_case(q(2.0, "h") => "2 h")]
+#[test_case(q(0.5, "h") => "30 min")]
+#[test_case(q(1.25, "lb") => "1 lb 4 oz")]
+#[test_case(q(20.0, "oz") => "1 lb 4 oz"; "from oz")]
+#[test_case(q(1.5, "kg") => "1 kg 500 g")]
+#[test_case(q(0.25, "lb") => "4 oz")]
+fn to_compound(q: Quantity) -> String {
+    q.to_compound(&Converter::bundled()).unwrap().to_string()
+}
+
+#[test]
+fn compound_parts() {
+    let c = q(1.5, "h").to_compound(&Converter::bundled()).unwrap();
+    assert_eq!(c.parts(), &[q(1.0, "h"), q(30.0, "min")]);
+}
+
+#[test]
+fn compound_round_trip() {
+    let converter = Converter::bundled();
+    let (recipe, _) = parse("~{1%h 30%min}");
+    let q = recipe.timers[0].quantity.as_ref().unwrap();
+    assert_eq!(q.to_compound(&converter).unwrap().to_string(), "1 h 30 min");
+}
+
+#[test]
+fn compound_errors() {
+    let converter = Converter::bundled();
+    let range = Quantity::new(
+        Value::Range {
+            start: 1.0.into(),
+            end: 2.0.into(),
+        },
+        Some("h".into()),
+    );
+    assert!(matches!(
+        range.to_compound(&converter),
+        Err(ConvertError::InvalidCompound(_))
+    ));
+    assert!(matches!(
+        q(180.0, "°C").to_compound(&converter),
+        Err(ConvertError::InvalidCompound(_))
+    ));
+    assert!(matches!(
+        Quantity::new(Value::from(1.0), None).to_compound(&converter),
+        Err(ConvertError::NoUnit(_))
+    ));
+    assert!(matches!(
+        q(1.0, "handful").to_compound(&converter),
+        Err(ConvertError::UnknownUnit(_))
+    ));
+}
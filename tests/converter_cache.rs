@@ -0,0 +1,156 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    convert::{ConvertTo, IngredientsFile, System, UnitsFile},
+    Converter, Quantity, Value,
+};
+
+const UNITS: &str = r#"
+custom_quantities = ["count"]
+
+[fractions.quantity]
+count = { enabled = true, max_denominator = 2 }
+
+[humanize.unit]
+pc = [{ step = 0.5 }]
+
+[[quantity]]
+quantity = "count"
+best = ["piece", "dozen"]
+[quantity.units]
+unspecified = [
+    { names = ["piece", "pieces"], symbols = ["pc"], ratio = 1 },
+    { names = ["dozen", "dozens"], symbols = ["dz"], ratio = 12 },
+]
+
+[region.uk]
+system = "imperial"
+locales = ["en-GB"]
+best = { volume = ["ml", "l"] }
+"#;
+
+const INGREDIENTS: &str = r#"
+[[ingredient]]
+names = ["flour"]
+density = 0.5
+measure = { metric = "mass" }
+"#;
+
+fn custom() -> Converter {
+    Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(toml::from_str::<UnitsFile>(UNITS).unwrap())
+        .unwrap()
+        .with_ingredients_file(toml::from_str::<IngredientsFile>(INGREDIENTS).unwrap())
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+/// Results of some conversions that cover the different parts of the
+/// converter
+fn fingerprint(converter: &Converter) -> Vec<String> {
+    let inputs = [
+        (1.5, "cup", ConvertTo::Best(System::Metric)),
+        (300.0, "ml", ConvertTo::Best(System::Imperial)),
+        (200.0, "°C", ConvertTo::Best(System::Imperial)),
+        (18.0, "pc", ConvertTo::from("dz")),
+        (1.0, "qt", ConvertTo::Region("uk")),
+    ];
+    let mut out = Vec::new();
+    for (value, unit, to) in inputs {
+        let mut q = Quantity::new(Value::from(value), Some(unit.to_string()));
+        if q.convert(to, None, converter).is_ok() {
+            q.humanize(converter);
+        }
+        out.push(q.to_string());
+    }
+    let mut flour = Quantity::new(Value::from(2.0), Some("cup".to_string()));
+    let _ = flour.convert("g", Some("flour"), converter);
+    out.push(flour.to_string());
+    out
+}
+
+#[test]
+fn bundled_is_built_once() {
+    assert_eq!(Converter::bundled(), Converter::bundled());
+}
+
+#[test]
+fn json_roundtrip() {
+    for converter in [Converter::bundled(), custom()] {
+        let json = serde_json::to_string(&converter).unwrap();
+        let loaded: Converter = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, converter);
+        assert_eq!(fingerprint(&loaded), fingerprint(&converter));
+    }
+}
+
+#[test]
+fn empty_roundtrip() {
+    let json = serde_json::to_string(&Converter::empty()).unwrap();
+    let loaded: Converter = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, Converter::empty());
+    assert_eq!(loaded.unit_count(), 0);
+}
+
+#[cfg(feature = "converter_cache")]
+mod binary {
+    use super::*;
+    use cooklang::convert::ConverterCacheError;
+
+    #[test]
+    fn roundtrip() {
+        for converter in [Converter::bundled(), custom()] {
+            let bytes = converter.to_bytes();
+            let loaded = Converter::from_bytes(&bytes).unwrap();
+            assert_eq!(loaded, converter);
+            assert_eq!(fingerprint(&loaded), fingerprint(&converter));
+        }
+    }
+
+    #[test]
+    fn smaller_than_json() {
+        let converter = Converter::bundled();
+        let json = serde_json::to_vec(&converter).unwrap();
+        assert!(converter.to_bytes().len() < json.len());
+    }
+
+    #[test]
+    fn other_version() {
+        let mut bytes = Converter::bundled().to_bytes();
+        // the version string is first, change its first char
+        assert_eq!(bytes[1], b'0');
+        bytes[1] = b'9';
+        assert!(matches!(
+            Converter::from_bytes(&bytes),
+            Err(ConverterCacheError::Version(_))
+        ));
+    }
+
+    #[test]
+    fn corrupted() {
+        let bytes = Converter::bundled().to_bytes();
+        assert!(matches!(
+            Converter::from_bytes(&bytes[..bytes.len() / 2]),
+            Err(ConverterCacheError::Decode(_))
+        ));
+        assert!(Converter::from_bytes(&[]).is_err());
+    }
+
+    #[test]
+    fn invalid_ids() {
+        let bundled = serde_json::to_value(Converter::bundled()).unwrap();
+        for id in ["/unitless", "/unit_index/g"] {
+            let mut json = bundled.clone();
+            *json.pointer_mut(id).unwrap() = 10_000.into();
+            // serde can't know the id is wrong, the cache checks it
+            let converter: Converter = serde_json::from_value(json).unwrap();
+            assert!(matches!(
+                Converter::from_bytes(&converter.to_bytes()),
+                Err(ConverterCacheError::Invalid(_))
+            ));
+        }
+    }
+}
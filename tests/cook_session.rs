@@ -0,0 +1,209 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    cook_session::{CookEvent, CookSession, CookSessionError, TimerState},
+    step_graph::StepNode,
+    Converter, CooklangParser, Extensions, Recipe,
+};
+use indoc::indoc;
+
+fn parse(input: &str) -> Recipe {
+    CooklangParser::new(Extensions::all(), Converter::bundled())
+        .parse(input)
+        .into_output()
+        .unwrap()
+}
+
+fn step(section: usize, content: usize) -> Option<StepNode> {
+    Some(StepNode::Step { section, content })
+}
+
+const RECIPE: &str = indoc! {"
+    == Dough ==
+    Mix @flour{500%g} and @water{300%ml}.
+
+    > Don't overmix it.
+
+    Let it rise for ~{1-2%hours}.
+
+    == Topping ==
+    Chop @onion{2} and @garlic{3%cloves}, fry for ~rest{30%s}.
+"};
+
+#[test]
+fn navigation() {
+    let recipe = parse(RECIPE);
+    let mut session = CookSession::new(&recipe);
+    assert_eq!(session.current(), step(0, 0));
+
+    assert!(session.next(&recipe));
+    // text is skipped
+    assert_eq!(session.current(), step(0, 2));
+    assert!(session.next(&recipe));
+    assert_eq!(session.current(), step(1, 0));
+    assert!(session.next(&recipe));
+    assert!(session.is_finished());
+    assert!(!session.next(&recipe));
+
+    assert!(session.back(&recipe));
+    assert_eq!(session.current(), step(1, 0));
+    assert!(session.back(&recipe));
+    assert!(session.back(&recipe));
+    assert_eq!(session.current(), step(0, 0));
+    assert!(!session.back(&recipe));
+    assert_eq!(session.current(), step(0, 0));
+
+    session.go_to(&recipe, 0, 2).unwrap();
+    assert_eq!(session.current(), step(0, 2));
+    assert_eq!(
+        session.go_to(&recipe, 0, 1),
+        Err(CookSessionError::UnknownStep {
+            section: 0,
+            content: 1
+        })
+    );
+}
+
+#[test]
+fn empty_recipe() {
+    let recipe = parse("");
+    let mut session = CookSession::new(&recipe);
+    assert!(session.is_finished());
+    assert!(!session.back(&recipe));
+}
+
+#[test]
+fn checked_ingredients() {
+    let recipe = parse(RECIPE);
+    let mut session = CookSession::new(&recipe);
+    session.check(&recipe, 3).unwrap();
+    assert_eq!(
+        session.step_ingredients(&recipe, step(1, 0).unwrap()),
+        vec![(2, false), (3, true)]
+    );
+    session.uncheck(3);
+    assert!(!session.is_checked(3));
+    assert_eq!(
+        session.check(&recipe, 10),
+        Err(CookSessionError::UnknownIngredient(10))
+    );
+}
+
+#[test]
+fn timers() {
+    let recipe = parse(RECIPE);
+    let converter = Converter::bundled();
+    let mut session = CookSession::new(&recipe);
+
+    // ranges use the shortest time
+    session.start_timer(&recipe, &converter, 0).unwrap();
+    session.start_timer(&recipe, &converter, 1).unwrap();
+    assert_eq!(session.timers()[0].duration, 3600.0);
+    assert_eq!(session.timers()[1].duration, 30.0);
+
+    session.pause_timer(0).unwrap();
+    assert_eq!(session.tick(20.0), Vec::<usize>::new());
+    assert_eq!(session.timers()[0].remaining, 3600.0);
+    assert_eq!(session.timers()[1].remaining, 10.0);
+
+    assert_eq!(session.tick(20.0), vec![1]);
+    assert_eq!(session.timers()[1].remaining, 0.0);
+    assert_eq!(session.timers()[1].state, TimerState::Finished);
+    // finished timers don't finish again
+    assert_eq!(session.tick(20.0), Vec::<usize>::new());
+
+    session.resume_timer(0).unwrap();
+    session.tick(600.0);
+    assert_eq!(session.timers()[0].remaining, 3000.0);
+    // starting a running timer does nothing
+    session.start_timer(&recipe, &converter, 0).unwrap();
+    assert_eq!(session.timers()[0].remaining, 3000.0);
+
+    // restart a finished timer
+    session.start_timer(&recipe, &converter, 1).unwrap();
+    assert_eq!(session.timers()[1].state, TimerState::Running);
+    assert_eq!(session.timers()[1].remaining, 30.0);
+
+    session.cancel_timer(0);
+    assert_eq!(session.timers().len(), 1);
+    assert_eq!(
+        session.pause_timer(0),
+        Err(CookSessionError::TimerNotStarted(0))
+    );
+    assert_eq!(
+        session.start_timer(&recipe, &converter, 5),
+        Err(CookSessionError::UnknownTimer(5))
+    );
+}
+
+#[test]
+fn timer_without_duration() {
+    let recipe = CooklangParser::new(Extensions::empty(), Converter::bundled())
+        .parse("Wait ~{a while}.")
+        .into_output()
+        .unwrap();
+    let mut session = CookSession::new(&recipe);
+    assert_eq!(
+        session.start_timer(&recipe, &Converter::bundled(), 0),
+        Err(CookSessionError::TimerWithoutDuration(0))
+    );
+}
+
+#[test]
+fn scale() {
+    let recipe = parse(RECIPE);
+    let converter = Converter::bundled();
+    let mut session = CookSession::new(&recipe);
+    assert_eq!(
+        session.set_scale(0.0),
+        Err(CookSessionError::InvalidScale(0.0))
+    );
+    session.set_scale(2.0).unwrap();
+    let scaled = session.scaled_recipe(&recipe, &converter);
+    assert_eq!(
+        scaled.ingredients[0].quantity.as_ref().unwrap().to_string(),
+        "1 kg"
+    );
+    // the original is untouched
+    assert_eq!(
+        recipe.ingredients[0].quantity.as_ref().unwrap().to_string(),
+        "500 g"
+    );
+}
+
+#[test]
+fn events_and_serde() {
+    let recipe = parse(RECIPE);
+    let converter = Converter::bundled();
+    let mut session = CookSession::new(&recipe);
+
+    let events: Vec<CookEvent> = serde_json::from_str(
+        r#"[
+            {"type": "check", "ingredient": 0},
+            {"type": "next"},
+            {"type": "startTimer", "timer": 0},
+            {"type": "setScale", "factor": 0.5}
+        ]"#,
+    )
+    .unwrap();
+    for event in events {
+        assert!(session
+            .apply(&recipe, &converter, event)
+            .unwrap()
+            .is_empty());
+    }
+    assert_eq!(
+        session.apply(&recipe, &converter, CookEvent::Tick { seconds: 3600.0 }),
+        Ok(vec![0])
+    );
+    assert!(session
+        .apply(&recipe, &converter, CookEvent::Check { ingredient: 99 })
+        .is_err());
+
+    let json = serde_json::to_string(&session).unwrap();
+    let restored: CookSession = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, session);
+    assert_eq!(restored.current(), step(0, 2));
+    assert!(restored.is_checked(0));
+    assert_eq!(restored.scale(), 0.5);
+}
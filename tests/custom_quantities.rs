@@ -0,0 +1,196 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    convert::{ConverterBuilderError, PhysicalQuantity, System, UnitsFile},
+    Converter, GroupedQuantity, Quantity, Value,
+};
+use test_case::test_case;
+
+const UNITS: &str = r#"
+unitless = "piece"
+custom_quantities = ["count", "energy"]
+
+[fractions.quantity]
+count = { enabled = true, max_denominator = 2 }
+
+[[quantity]]
+quantity = "count"
+best = ["piece", "dozen"]
+[quantity.units]
+unspecified = [
+    { names = ["piece", "pieces"], symbols = ["pc"], ratio = 1 },
+    { names = ["pair", "pairs"], symbols = [], ratio = 2 },
+    { names = ["dozen", "dozens"], symbols = ["dz"], ratio = 12 },
+]
+
+[[quantity]]
+quantity = "energy"
+best = { metric = ["kJ"], imperial = ["kcal"] }
+[quantity.units]
+metric = [
+    { names = ["joule", "joules"], symbols = ["J"], ratio = 1, expand_si = true },
+]
+imperial = [
+    { names = ["kilocalorie", "kilocalories"], symbols = ["kcal", "Cal"], ratio = 4184 },
+]
+"#;
+
+fn converter() -> Converter {
+    let file: UnitsFile = toml::from_str(UNITS).unwrap();
+    Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+fn q(value: f64, unit: Option<&str>) -> Quantity {
+    Quantity::new(Value::from(value), unit.map(String::from))
+}
+
+#[test]
+fn declared_quantities() {
+    let converter = converter();
+    let quantities = converter
+        .physical_quantities()
+        .map(|q| converter.quantity_name(q).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        quantities,
+        [
+            "volume",
+            "mass",
+            "length",
+            "temperature",
+            "time",
+            "heat",
+            "count",
+            "energy"
+        ]
+    );
+
+    let dozen = converter.find_unit("dozen").unwrap();
+    let count = dozen.physical_quantity;
+    assert!(count.is_custom());
+    assert_eq!(converter.find_quantity("count"), Some(count));
+    assert_eq!(converter.quantity_name(count), Some("count"));
+    assert_ne!(converter.find_quantity("energy"), Some(count));
+    assert_eq!(
+        converter.find_quantity("mass"),
+        Some(PhysicalQuantity::Mass)
+    );
+    assert_eq!(converter.find_quantity("unknown"), None);
+    assert_eq!(converter.best_units(count, None).len(), 2);
+
+    // only the converter that declared it knows it
+    let bundled = Converter::bundled();
+    assert_eq!(bundled.quantity_name(count), None);
+    assert!(bundled.best_units(count, None).is_empty());
+}
+
+#[test_case(24.0, "pc", "dozen" => "2 dz")]
+#[test_case(3.0, "pair", "pc" => "6 pc")]
+#[test_case(1.0, "kcal", "kJ" => "4.184 kJ")]
+fn convert_to_unit(value: f64, unit: &str, to: &str) -> String {
+    let mut q = q(value, Some(unit));
+    q.convert(to, None, &converter()).unwrap();
+    q.to_string()
+}
+
+#[test]
+fn convert_to_best() {
+    let converter = converter();
+    let mut energy = q(2000.0, Some("J"));
+    energy.convert(System::Imperial, None, &converter).unwrap();
+    assert_eq!(energy.to_string(), "1/2 kcal");
+
+    // count has no system, and best units are the same for all
+    let mut count = q(0.5, Some("dozen"));
+    count.convert(System::Metric, None, &converter).unwrap();
+    assert_eq!(count.to_string(), "6 pc");
+}
+
+#[test]
+fn grouped_quantity() {
+    let converter = converter();
+
+    let mut eggs = GroupedQuantity::empty();
+    eggs.add(&q(6.0, None), None, &converter);
+    eggs.add(&q(1.0, Some("dozen")), None, &converter);
+    eggs.add(&q(3.0, None), None, &converter);
+    assert_eq!(eggs.to_string(), "1.75 dozen");
+
+    let mut mixed = GroupedQuantity::empty();
+    mixed.add(&q(1.0, Some("kcal")), None, &converter);
+    mixed.add(&q(1.0, Some("pair")), None, &converter);
+    mixed.add(&q(1.0, Some("g")), None, &converter);
+    assert_eq!(mixed.len(), 3);
+
+    // without unitless config, no unit is kept apart
+    let bundled = Converter::bundled();
+    let mut eggs = GroupedQuantity::empty();
+    eggs.add(&q(6.0, None), None, &bundled);
+    eggs.add(&q(1.0, Some("dozen")), None, &bundled);
+    assert_eq!(eggs.len(), 2);
+}
+
+#[test]
+fn strict_builtin() {
+    assert_eq!("time".parse(), Ok(PhysicalQuantity::Time));
+    assert!("energy".parse::<PhysicalQuantity>().is_err());
+    assert!("volum".parse::<PhysicalQuantity>().is_err());
+    let q: PhysicalQuantity = serde_json::from_str("\"time\"").unwrap();
+    assert_eq!(q, PhysicalQuantity::Time);
+    assert!(serde_json::from_str::<PhysicalQuantity>("\"energy\"").is_err());
+    assert!(serde_json::from_str::<PhysicalQuantity>("\"\"").is_err());
+}
+
+#[test]
+fn serde_converter() {
+    let converter = converter();
+    let json = serde_json::to_string(&converter).unwrap();
+    let de: Converter = serde_json::from_str(&json).unwrap();
+    assert_eq!(de, converter);
+
+    let mut eggs = GroupedQuantity::empty();
+    eggs.add(&q(1.0, Some("dozen")), None, &converter);
+    let json = serde_json::to_string(&eggs).unwrap();
+    let de: GroupedQuantity = serde_json::from_str(&json).unwrap();
+    assert_eq!(de.to_string(), "1 dozen");
+}
+
+#[test_case(
+    "custom_quantities = [\"count\"]\n[[quantity]]\nquantity = \"count\"\nunits = [{ names = [\"piece\"], symbols = [], ratio = 1 }]"
+    => matches ConverterBuilderError::InvalidQuantity { .. } ; "no best units"
+)]
+#[test_case(
+    "custom_quantities = [\"count\"]"
+    => matches ConverterBuilderError::InvalidQuantity { .. } ; "no quantity group"
+)]
+#[test_case(
+    "[[quantity]]\nquantity = \"volum\"\nbest = [\"l\"]"
+    => matches ConverterBuilderError::UndeclaredQuantity { name } if name == "volum" ; "undeclared group"
+)]
+#[test_case(
+    "[fractions.quantity]\ncount = true"
+    => matches ConverterBuilderError::UndeclaredQuantity { .. } ; "undeclared fractions"
+)]
+#[test_case(
+    "custom_quantities = [\"mass\"]"
+    => matches ConverterBuilderError::InvalidQuantity { .. } ; "builtin declared"
+)]
+#[test_case(
+    "unitless = \"nope\""
+    => matches ConverterBuilderError::UnknownUnit(_) ; "unknown unitless"
+)]
+fn builder_errors(units: &str) -> ConverterBuilderError {
+    let file: UnitsFile = toml::from_str(units).unwrap();
+    Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .and_then(|b| b.finish())
+        .unwrap_err()
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    ingredient_list::IngredientList,
+    quantity::{Number, TryAdd},
+    Converter, CooklangParser, Extensions, GroupedQuantity, Quantity, Recipe, Value,
+};
+use test_case::test_case;
+
+fn parse(input: &str) -> Recipe {
+    CooklangParser::new(Extensions::all(), Converter::bundled())
+        .parse(input)
+        .into_output()
+        .unwrap()
+}
+
+fn first_value(recipe: &Recipe) -> Value {
+    recipe.ingredients[0]
+        .quantity
+        .as_ref()
+        .unwrap()
+        .value()
+        .clone()
+}
+
+#[test_case("@flour{1/3%cup}", 3.0 => "1 c"; "third")]
+#[test_case("@flour{1/3%cup}", 2.0 => "2/3 c"; "keeps fraction")]
+#[test_case("@eggs{3}", 1.0 / 3.0 => "1"; "inverse factor")]
+#[test_case("@butter{0.1%kg}", 3.0 => "300 g"; "decimal")]
+fn scale(input: &str, factor: f64) -> String {
+    let mut recipe = parse(input);
+    recipe.scale(factor, &Converter::bundled());
+    recipe.ingredients[0].quantity.as_ref().unwrap().to_string()
+}
+
+#[test]
+fn scale_back() {
+    let converter = Converter::bundled();
+    let mut recipe = parse("@eggs{2}");
+    recipe.scale(4.0 / 3.0, &converter);
+    recipe.scale(3.0 / 4.0, &converter);
+    assert_eq!(first_value(&recipe), Value::from(2.0));
+}
+
+#[test]
+fn scale_to_servings() {
+    let converter = Converter::bundled();
+    let mut recipe = parse("---\nservings: 3\n---\n@eggs{2}");
+    recipe.scale_to_servings(7, &converter).unwrap();
+    recipe.scale_to_servings(3, &converter).unwrap();
+    assert_eq!(first_value(&recipe), Value::from(2.0));
+}
+
+#[test]
+fn references() {
+    let recipe = parse("@eggs{2/3} then @&eggs{2/3} and @&eggs{2/3}");
+    let grouped = recipe.group_ingredients(&Converter::bundled());
+    assert_eq!(grouped[0].quantity.to_string(), "2");
+}
+
+#[test]
+fn grouped_add() {
+    let converter = Converter::bundled();
+    let mut eggs = GroupedQuantity::empty();
+    for _ in 0..30 {
+        eggs.add(
+            &Quantity::new(Value::from(2.0 / 3.0), None),
+            None,
+            &converter,
+        );
+    }
+    let total = eggs.into_vec();
+    assert_eq!(total[0].value(), &Value::from(20.0));
+}
+
+#[test]
+fn shopping_list() {
+    let converter = Converter::bundled();
+    let recipe = parse("@eggs{1/3}");
+    let mut list = IngredientList::new();
+    for _ in 0..6 {
+        list.add_recipe(&recipe, &converter, false);
+    }
+    let eggs = list.iter().next().unwrap().1;
+    assert_eq!(eggs.to_string(), "2");
+}
+
+#[test]
+fn try_add_fractions() {
+    let third = Value::Number(Number::Fraction {
+        whole: 0,
+        num: 1,
+        den: 3,
+        err: 0.0,
+    });
+    let sum = third.try_add(&third).unwrap();
+    assert_eq!(sum.to_string(), "2/3");
+    let sum = sum.try_add(&third).unwrap();
+    assert_eq!(sum, Value::from(1.0));
+}
+
+#[test]
+fn ranges() {
+    let range = Value::Range {
+        start: 0.1.into(),
+        end: 0.2.into(),
+    };
+    let sum = range.try_add(&Value::from(0.2)).unwrap();
+    assert_eq!(
+        sum,
+        Value::Range {
+            start: 0.3.into(),
+            end: 0.4.into(),
+        }
+    );
+}
+
+#[test]
+fn unit_conversion_is_float() {
+    let converter = Converter::bundled();
+    let a = Quantity::new(Value::from(1.0), Some("cup".into()));
+    let b = Quantity::new(Value::from(100.0), Some("ml".into()));
+    let sum = a.try_add(&b, &converter).unwrap();
+    let Value::Number(n) = sum.value() else {
+        panic!("not a number")
+    };
+    assert!((n.value() - 1.422_675_6).abs() < 1e-6);
+}
@@ -14,6 +14,6 @@ use test_case::test_case;
 fn imperial(value: f64, unit: &str) -> String {
     let converter = Converter::bundled();
     let mut q = Quantity::new(Value::from(value), Some(unit.to_string()));
-    let _ = q.convert(System::Imperial, &converter);
+    let _ = q.convert(System::Imperial, None, &converter);
     q.to_string()
 }
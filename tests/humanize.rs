@@ -0,0 +1,195 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    convert::{ConverterBuilderError, UnitsFile},
+    quantity::Number,
+    Converter, CooklangParser, Extensions, GroupedQuantity, Quantity, Value,
+};
+use test_case::test_case;
+
+fn q(value: f64, unit: Option<&str>) -> Quantity {
+    Quantity::new(Value::from(value), unit.map(String::from))
+}
+
+#[test_case(0.37, Some("tsp") => "3/8 tsp")]
+#[test_case(1.1, Some("tsp") => "1 1/8 tsp")]
+#[test_case(1.3, Some("tbsp") => "1 1/2 tbsp")]
+#[test_case(0.7, Some("cup") => "3/4 cup")]
+#[test_case(123.0, Some("g") => "125 g")]
+#[test_case(12.34, Some("g") => "12 g")]
+#[test_case(1.234, Some("g") => "1.2 g")]
+#[test_case(1.234, Some("kg") => "1.2 kg")]
+#[test_case(176.667, Some("°C") => "175 °C")]
+#[test_case(0.8, None => "1"; "whole egg")]
+#[test_case(0.3, None => "0.5"; "half egg")]
+#[test_case(2.6, Some("min") => "2.6 min"; "disabled")]
+#[test_case(0.01, Some("tsp") => "0.01 tsp"; "not to zero")]
+#[test_case(2.345, Some("handful") => "2.345 handful"; "unknown unit")]
+fn humanize(value: f64, unit: Option<&str>) -> String {
+    let mut q = q(value, unit);
+    q.humanize(&Converter::bundled());
+    q.to_string()
+}
+
+#[test]
+fn keeps_true_value() {
+    let converter = Converter::bundled();
+
+    let mut salt = q(0.37, Some("tsp"));
+    assert!(salt.humanize(&converter));
+    assert!(salt.is_approx());
+    assert_eq!(salt.exact_value(), &Value::from(0.37));
+    assert!((salt.approx_error() - 0.005).abs() < 1e-10);
+    let Value::Number(n) = salt.value() else {
+        panic!()
+    };
+    assert_eq!(n.value(), 0.375);
+    assert!(!n.is_approx());
+
+    let mut flour = q(123.0, Some("g"));
+    flour.humanize(&converter);
+    assert_eq!(flour.value(), &Value::Number(Number::Regular(125.0)));
+    assert_eq!(flour.exact_value(), &Value::from(123.0));
+    assert_eq!(flour.approx_error(), 2.0);
+    let json = serde_json::to_string(&flour).unwrap();
+    let de: Quantity = serde_json::from_str(&json).unwrap();
+    assert_eq!(de.exact_value(), &Value::from(123.0));
+
+    // operations start from the true value
+    let mut scaled = flour.div(10.0).unwrap();
+    assert_eq!(scaled.to_string(), "12.3 g");
+    assert!(!scaled.is_approx());
+    scaled.humanize(&converter);
+    assert_eq!(scaled.to_string(), "12 g");
+
+    // already measurable
+    let mut sugar = q(100.0, Some("g"));
+    assert!(!sugar.humanize(&converter));
+    assert!(!sugar.is_approx());
+    assert_eq!(sugar.approx_error(), 0.0);
+    assert_eq!(sugar.value(), &Value::from(100.0));
+    assert!(!serde_json::to_string(&sugar).unwrap().contains("exact"));
+}
+
+#[test]
+fn grouped_totals_are_exact() {
+    let converter = Converter::bundled();
+
+    let mut a = q(123.0, Some("g"));
+    a.humanize(&converter);
+    let mut total = GroupedQuantity::empty();
+    total.add(&a, None, &converter);
+    total.add(&a, None, &converter);
+    assert_eq!(total.to_string(), "246 g");
+
+    total.humanize(&converter);
+    assert_eq!(total.to_string(), "245 g");
+    assert!(total.iter().all(|q| q.is_approx()));
+}
+
+#[test]
+fn range() {
+    let mut q = Quantity::new(
+        Value::Range {
+            start: 0.37.into(),
+            end: 0.5.into(),
+        },
+        Some("tsp".into()),
+    );
+    assert!(q.humanize(&Converter::bundled()));
+    assert_eq!(q.to_string(), "3/8-1/2 tsp");
+    assert!((q.approx_error() - 0.005).abs() < 1e-10);
+}
+
+#[test]
+fn approx_error() {
+    let converter = Converter::bundled();
+
+    let mut down = q(12.34, Some("g"));
+    down.humanize(&converter);
+    assert!((down.approx_error() + 0.34).abs() < 1e-10);
+
+    // approximated fractions also count
+    let mut third = q(1.0 / 3.0, Some("cup"));
+    third.humanize(&converter);
+    assert!(third.is_approx());
+    let shown = match third.value() {
+        Value::Number(n) => n.value() - n.err(),
+        _ => panic!(),
+    };
+    assert!((third.approx_error() - (shown - 1.0 / 3.0)).abs() < 1e-10);
+    assert!(third.approx_error() != 0.0);
+
+    let text = Quantity::new(Value::Text("a pinch".into()), None);
+    assert_eq!(text.approx_error(), 0.0);
+}
+
+#[test]
+fn recipe() {
+    let converter = Converter::bundled();
+    let parser = CooklangParser::new(Extensions::all(), converter.clone());
+    let mut recipe = parser
+        .parse("@flour{123%g} @eggs{2.7} @salt{0.37%tsp} ~{2.6%min}")
+        .into_output()
+        .unwrap();
+    recipe.humanize(&converter);
+    let quantities = recipe
+        .ingredients
+        .iter()
+        .map(|i| i.quantity.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(quantities, ["125 g", "3", "3/8 tsp"]);
+    assert_eq!(
+        recipe.timers[0].quantity.as_ref().unwrap().to_string(),
+        "2.6 min"
+    );
+}
+
+#[test]
+fn custom_rules() {
+    let file: UnitsFile = toml::from_str(
+        r#"
+        [humanize]
+        all = true
+        unitless = false
+
+        [humanize.unit]
+        g = [{ step = 10 }, { above = 1000, step = 50 }]
+        "#,
+    )
+    .unwrap();
+    let converter = Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap();
+
+    let humanize = |value, unit| {
+        let mut q = q(value, unit);
+        q.humanize(&converter);
+        q.to_string()
+    };
+    assert_eq!(humanize(123.0, Some("g")), "120 g");
+    assert_eq!(humanize(1234.0, Some("g")), "1250 g");
+    assert_eq!(humanize(0.8, None), "0.8");
+    // the rule for the quantity is still used
+    assert_eq!(humanize(1.234, Some("kg")), "1.2 kg");
+}
+
+#[test_case("[humanize]\nall = { step = 0 }" => matches ConverterBuilderError::InvalidHumanize { .. }; "zero step")]
+#[test_case("[humanize]\nall = []" => matches ConverterBuilderError::InvalidHumanize { .. }; "empty tiers")]
+#[test_case("[humanize]\nall = [{ step = 1 }, { step = 2 }]" => matches ConverterBuilderError::InvalidHumanize { .. }; "duplicate above")]
+#[test_case("[humanize.unit]\nnope = true" => matches ConverterBuilderError::UnknownUnit(_); "unknown unit")]
+#[test_case("[humanize.quantity]\nenergy = true" => matches ConverterBuilderError::UndeclaredQuantity { .. }; "undeclared quantity")]
+fn builder_errors(units: &str) -> ConverterBuilderError {
+    let file: UnitsFile = toml::from_str(units).unwrap();
+    Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap_err()
+}
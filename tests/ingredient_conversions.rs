@@ -0,0 +1,239 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    convert::{ConvertError, ConverterBuilderError, IngredientsFile, PhysicalQuantity, System},
+    ingredient_list::IngredientList,
+    Converter, CooklangParser, Extensions, GroupedQuantity, Quantity, Value,
+};
+use test_case::test_case;
+
+const INGREDIENTS: &str = r#"
+[[ingredient]]
+names = ["flour", "Plain Flour"]
+density = 0.5
+units = { cup = 125 }
+measure = { metric = "mass" }
+
+[[ingredient]]
+names = ["butter"]
+density = 0.9
+units = { stick = 113 }
+measure = "mass"
+
+[[ingredient]]
+names = ["milk"]
+density = 1.03
+
+[[ingredient]]
+names = ["eggs", "egg"]
+piece = 50
+"#;
+
+fn converter() -> Converter {
+    let file: IngredientsFile = toml::from_str(INGREDIENTS).unwrap();
+    Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_ingredients_file(file)
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+fn q(value: f64, unit: Option<&str>) -> Quantity {
+    Quantity::new(Value::from(value), unit.map(String::from))
+}
+
+#[test_case("flour", 1.0, Some("cup"), "g" => "125 g"; "unit mass over density")]
+#[test_case("flour", 100.0, Some("ml"), "g" => "50 g"; "density")]
+#[test_case("plain flour", 100.0, Some("g"), "ml" => "189.271 ml"; "mass to volume")]
+#[test_case("flour", 250.0, Some("g"), "cup" => "2 c"; "mass to unit mass")]
+#[test_case("butter", 90.0, Some("g"), "ml" => "100 ml"; "mass to volume with density")]
+#[test_case("milk", 1.0, Some("l"), "kg" => "1.03 kg"; "volume to mass")]
+#[test_case("butter", 2.0, Some("stick"), "g" => "226 g"; "unknown unit")]
+#[test_case("butter", 1.0, Some("stick"), "ml" => "125.556 ml"; "unknown unit to volume")]
+#[test_case("eggs", 3.0, None, "g" => "150 g"; "piece")]
+#[test_case("butter", 100.0, Some("g"), "oz" => "3 1/2 oz"; "same quantity")]
+fn convert_to_unit(ingredient: &str, value: f64, unit: Option<&str>, to: &str) -> String {
+    let mut q = q(value, unit);
+    q.convert(to, Some(ingredient), &converter()).unwrap();
+    q.to_string()
+}
+
+#[test]
+fn conversion_errors() {
+    let converter = converter();
+
+    let mut sugar = q(1.0, Some("cup"));
+    let err = sugar.convert("g", Some("sugar"), &converter).unwrap_err();
+    assert!(matches!(
+        err,
+        ConvertError::MixedQuantities {
+            from: PhysicalQuantity::Volume,
+            to: PhysicalQuantity::Mass
+        }
+    ));
+
+    let mut eggs = q(1.0, Some("dozen"));
+    let err = eggs.convert("g", Some("eggs"), &converter).unwrap_err();
+    assert!(matches!(err, ConvertError::UnknownUnit(_)));
+
+    let mut butter = q(1.0, None);
+    let err = butter.convert("g", Some("butter"), &converter).unwrap_err();
+    assert!(matches!(err, ConvertError::NoUnit(_)));
+}
+
+#[test]
+fn best_unit_with_measure() {
+    let converter = converter();
+
+    // flour prefers mass only in metric
+    let mut flour = q(2.0, Some("cup"));
+    flour
+        .convert(System::Metric, Some("flour"), &converter)
+        .unwrap();
+    assert_eq!(flour.to_string(), "250 g");
+    let mut flour = q(500.0, Some("ml"));
+    flour
+        .convert(System::Imperial, Some("flour"), &converter)
+        .unwrap();
+    assert_eq!(flour.to_string(), "2.113 c");
+
+    // butter always by mass
+    let mut butter = q(1.0, Some("stick"));
+    butter
+        .convert(System::Imperial, Some("butter"), &converter)
+        .unwrap();
+    assert_eq!(butter.to_string(), "4 oz");
+
+    // milk has no preference
+    let mut milk = q(1.0, Some("cup"));
+    milk.convert(System::Metric, Some("milk"), &converter)
+        .unwrap();
+    assert_eq!(milk.to_string(), "236.588 ml");
+}
+
+#[test]
+fn recipe_convert() {
+    let converter = converter();
+    let parser = CooklangParser::new(Extensions::all(), converter.clone());
+    let mut recipe = parser
+        .parse("@flour{2%cups} @butter{1%stick} @milk{1%cup} @eggs{2} @salt{1%pinch}")
+        .into_output()
+        .unwrap();
+    let errors = recipe.convert(System::Metric, &converter);
+    let quantities = recipe
+        .ingredients
+        .iter()
+        .map(|i| i.quantity.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(quantities, ["250 g", "113 g", "236.588 ml", "2", "1 pinch"]);
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn grouped_quantity_crosses_volume_and_mass() {
+    let converter = converter();
+
+    let mut flour = GroupedQuantity::empty();
+    flour.add(&q(1.0, Some("cup")), Some("flour"), &converter);
+    assert_eq!(flour.to_string(), "1 cup");
+    flour.add(&q(100.0, Some("g")), Some("flour"), &converter);
+    assert_eq!(flour.to_string(), "225 g");
+
+    let mut sugar = GroupedQuantity::empty();
+    sugar.add(&q(1.0, Some("cup")), Some("sugar"), &converter);
+    sugar.add(&q(100.0, Some("g")), Some("sugar"), &converter);
+    assert_eq!(sugar.len(), 2);
+
+    let mut eggs = GroupedQuantity::empty();
+    eggs.add(&q(2.0, None), Some("eggs"), &converter);
+    eggs.add(&q(1.0, Some("kg")), Some("eggs"), &converter);
+    assert_eq!(eggs.to_string(), "1.1 kg");
+}
+
+#[test]
+fn grouped_quantity_keeps_single_conversion() {
+    let converter = converter();
+
+    let mut flour = GroupedQuantity::empty();
+    flour.add(&q(1.0, Some("cup")), Some("flour"), &converter);
+    flour.add(&q(2.0, Some("bags")), Some("flour"), &converter);
+    assert_eq!(flour.to_string(), "1 cup, 2 bags");
+
+    let mut flour = GroupedQuantity::empty();
+    flour.add(&q(1.0, Some("cup")), Some("flour"), &converter);
+    flour.add(
+        &Quantity::new(Value::Text("a pinch".into()), None),
+        Some("flour"),
+        &converter,
+    );
+    assert_eq!(flour.to_string(), "1 cup, a pinch");
+}
+
+#[test]
+fn ingredient_list_merge() {
+    let converter = converter();
+    let parser = CooklangParser::new(Extensions::all(), converter.clone());
+    let a = parser
+        .parse("@butter{1%stick} @milk{1%cup}")
+        .into_output()
+        .unwrap();
+    let b = parser
+        .parse("@butter{50%g} @milk{100%ml} @&butter{1/2%cup}")
+        .into_output()
+        .unwrap();
+
+    let mut list = IngredientList::new();
+    list.add_recipe(&a, &converter, false);
+    list.add_recipe(&b, &converter, false);
+    let list = list
+        .iter()
+        .map(|(name, q)| format!("{name}: {q}"))
+        .collect::<Vec<_>>();
+    assert_eq!(list, ["butter: 269.465 g", "milk: 1.423 c"]);
+}
+
+#[test]
+fn builder_errors() {
+    let dup: IngredientsFile =
+        toml::from_str("[[ingredient]]\nnames = [\"a\", \"A\"]\ndensity = 1").unwrap();
+    let err = Converter::builder().with_ingredients_file(dup).unwrap_err();
+    assert!(matches!(
+        err,
+        ConverterBuilderError::DuplicateIngredient { .. }
+    ));
+
+    let negative: IngredientsFile =
+        toml::from_str("[[ingredient]]\nnames = [\"a\"]\npiece = -1").unwrap();
+    let err = Converter::builder()
+        .with_ingredients_file(negative)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ConverterBuilderError::InvalidIngredient { .. }
+    ));
+
+    // later files override
+    let first: IngredientsFile =
+        toml::from_str("[[ingredient]]\nnames = [\"a\", \"b\"]\ndensity = 1").unwrap();
+    let second: IngredientsFile =
+        toml::from_str("[[ingredient]]\nnames = [\"a\"]\ndensity = 2").unwrap();
+    let converter = Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_ingredients_file(first)
+        .unwrap()
+        .with_ingredients_file(second)
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(
+        converter.ingredient_conversions("A").unwrap().density,
+        Some(2.0)
+    );
+    assert_eq!(
+        converter.ingredient_conversions("b").unwrap().density,
+        Some(1.0)
+    );
+}
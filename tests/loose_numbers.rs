@@ -0,0 +1,133 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    analysis::NumberWords, quantity::Number, Converter, CooklangParser, Extensions, ParseOptions,
+    Recipe, Value,
+};
+use test_case::test_case;
+
+fn parse_with(input: &str, extensions: Extensions, options: ParseOptions) -> Recipe {
+    CooklangParser::new(extensions, Converter::bundled())
+        .parse_with_options(input, options)
+        .into_output()
+        .unwrap()
+}
+
+fn parse(input: &str) -> Recipe {
+    parse_with(
+        input,
+        Extensions::all() | Extensions::LOOSE_NUMBERS,
+        ParseOptions::default(),
+    )
+}
+
+fn first_quantity(recipe: &Recipe) -> (Value, Option<String>) {
+    let q = recipe.ingredients[0].quantity.as_ref().unwrap();
+    (q.value().clone(), q.unit().map(String::from))
+}
+
+fn frac(whole: u32, num: u32, den: u32) -> Value {
+    Value::Number(Number::Fraction {
+        whole,
+        num,
+        den,
+        err: 0.0,
+    })
+}
+
+#[test_case("@milk{½%cup}" => (frac(0, 1, 2), Some("cup".into())); "vulgar fraction")]
+#[test_case("@milk{1½%cup}" => (frac(1, 1, 2), Some("cup".into())); "mixed")]
+#[test_case("@milk{1 ¾%cup}" => (frac(1, 3, 4), Some("cup".into())); "mixed with space")]
+#[test_case("@milk{3⁄8%cup}" => (frac(0, 3, 8), Some("cup".into())); "fraction slash")]
+#[test_case("@milk{½ cup}" => (frac(0, 1, 2), Some("cup".into())); "advanced units")]
+#[test_case("@eggs{two}" => (Value::from(2.0), None); "word")]
+#[test_case("@eggs{A  Dozen}" => (Value::from(12.0), None); "word case")]
+#[test_case("@eggs{half a dozen}" => (Value::from(6.0), None); "many words")]
+#[test_case("@onion{half-dozen}" => (Value::Text("half-dozen".into()), None); "not a range")]
+#[test_case("@flour{two cups}" => (Value::from(2.0), Some("cups".into())); "word and unit")]
+#[test_case("@flour{1,5%kg}" => (Value::Text("1,5".into()), Some("kg".into())); "no locale comma")]
+#[test_case("@milk{1-1½%cup}" => (Value::Range { start: 1.0.into(), end: Number::Fraction { whole: 1, num: 1, den: 2, err: 0.0 } }, Some("cup".into())); "range")]
+#[test_case("@salt{a pinch}" => (Value::Text("a pinch".into()), None); "text")]
+#[test_case("@lemon{one or two}" => (Value::Text("one or two".into()), None); "not a unit")]
+#[test_case("@flour{one big cup}" => (Value::Text("one big cup".into()), None); "not a unit after words")]
+fn loose_numbers(input: &str) -> (Value, Option<String>) {
+    first_quantity(&parse(input))
+}
+
+#[test_case("de" => (Value::from(1.5), Some("kg".into())); "german")]
+#[test_case("es_ES" => (Value::from(1.5), Some("kg".into())); "spanish")]
+#[test_case("en" => (Value::Text("1,5".into()), Some("kg".into())); "english")]
+fn decimal_comma(locale: &str) -> (Value, Option<String>) {
+    let recipe = parse(&format!("---\nlocale: {locale}\n---\n@flour{{1,5%kg}}"));
+    first_quantity(&recipe)
+}
+
+#[test]
+fn decimal_comma_without_separator() {
+    let recipe = parse("---\nlocale: de\n---\n@Mehl{1,5 kg}");
+    assert_eq!(
+        first_quantity(&recipe),
+        (Value::from(1.5), Some("kg".into()))
+    );
+}
+
+#[test]
+fn scalable() {
+    let mut recipe = parse("@eggs{two} @milk{½%cup} @salt{=½%tsp}");
+    recipe.scale(2.0, &Converter::empty());
+    let values = recipe
+        .ingredients
+        .iter()
+        .map(|i| i.quantity.as_ref().unwrap().value().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(values, ["4", "1", "1/2"]);
+}
+
+#[test]
+fn custom_words() {
+    let words = NumberWords::new()
+        .with("dos", 2.0)
+        .with("media docena", 6.0);
+    let recipe = parse_with(
+        "@huevos{media docena} @eggs{two}",
+        Extensions::all() | Extensions::LOOSE_NUMBERS,
+        ParseOptions {
+            number_words: Some(&words),
+            ..Default::default()
+        },
+    );
+    let values = recipe
+        .ingredients
+        .iter()
+        .map(|i| i.quantity.as_ref().unwrap().value().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(values, [Value::from(6.0), Value::Text("two".into())]);
+}
+
+#[test]
+fn not_in_all() {
+    assert!(!Extensions::all().contains(Extensions::LOOSE_NUMBERS));
+    assert!(!Extensions::default().contains(Extensions::LOOSE_NUMBERS));
+}
+
+#[test]
+fn disabled() {
+    let recipe = parse_with(
+        "@milk{½%cup} @eggs{two}",
+        Extensions::all(),
+        ParseOptions::default(),
+    );
+    assert_eq!(
+        first_quantity(&recipe),
+        (Value::Text("½".into()), Some("cup".into()))
+    );
+    let eggs = recipe.ingredients[1].quantity.as_ref().unwrap();
+    assert_eq!(eggs.value(), &Value::Text("two".into()));
+}
+
+#[test]
+fn timer() {
+    let recipe = parse("~{half%hour}");
+    let q = recipe.timers[0].quantity.as_ref().unwrap();
+    assert_eq!(q.value(), &Value::from(0.5));
+}
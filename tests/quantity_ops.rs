@@ -0,0 +1,162 @@
+#![cfg(feature = "bundled_units")]
+
+use std::cmp::Ordering;
+
+use cooklang::{quantity::Number, Converter, GroupedQuantity, Quantity, Value};
+use test_case::test_case;
+
+fn q(value: f64, unit: &str) -> Quantity {
+    Quantity::new(Value::from(value), Some(unit.to_string()))
+}
+
+fn range(start: f64, end: f64, unit: &str) -> Quantity {
+    Quantity::new(
+        Value::Range {
+            start: start.into(),
+            end: end.into(),
+        },
+        Some(unit.to_string()),
+    )
+}
+
+#[test_case(q(1.0, "kg"), q(250.0, "g") => "0.75 kg"; "converts rhs")]
+#[test_case(q(250.0, "g"), q(1.0, "kg") => "-750 g"; "negative")]
+#[test_case(range(2.0, 3.0, "kg"), q(1.0, "kg") => "1-2 kg"; "range minus number")]
+#[test_case(q(5.0, "kg"), range(1.0, 2.0, "kg") => "3-4 kg"; "number minus range")]
+#[test_case(range(3.0, 4.0, "kg"), range(1.0, 2.0, "kg") => "1-3 kg"; "range minus range")]
+#[test_case(q(3.0, "cans"), q(1.0, "cans") => "2 cans"; "unknown unit")]
+fn sub(a: Quantity, b: Quantity) -> String {
+    a.try_sub(&b, &Converter::bundled()).unwrap().to_string()
+}
+
+#[test]
+fn sub_fractions() {
+    let half = Quantity::new(
+        Value::Number(Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 2,
+            err: 0.0,
+        }),
+        Some("cup".into()),
+    );
+    let third = Quantity::new(
+        Value::Number(Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 3,
+            err: 0.0,
+        }),
+        Some("cup".into()),
+    );
+    let diff = half.try_sub(&third, &Converter::bundled()).unwrap();
+    assert_eq!(diff.to_string(), "1/6 cup");
+}
+
+#[test_case(q(1.0, "kg"), q(1.0, "l"); "different physical quantity")]
+#[test_case(q(1.0, "cans"), q(1.0, "jars"); "different unknown units")]
+#[test_case(Quantity::new(Value::from("a bit".to_string()), None), Quantity::new(Value::from(1.0), None); "text")]
+fn incompatible(a: Quantity, b: Quantity) {
+    let converter = Converter::bundled();
+    assert!(a.try_sub(&b, &converter).is_err());
+    assert!(a.try_cmp(&b, &converter).is_err());
+}
+
+#[test_case(q(250.0, "g"), 3.0 => "750 g"; "mul")]
+#[test_case(range(1.0, 2.0, "tbsp"), 2.0 => "2-4 tbsp"; "mul range")]
+#[test_case(range(1.0, 2.0, "tbsp"), -1.0 => "-2--1 tbsp"; "negative keeps range order")]
+fn mul(a: Quantity, factor: f64) -> String {
+    a.mul(factor).unwrap().to_string()
+}
+
+#[test]
+fn div_is_exact() {
+    let third = q(1.0, "cup").div(3.0).unwrap();
+    assert_eq!(third.mul(3.0).unwrap(), q(1.0, "cup"));
+    assert_eq!(range(3.0, 6.0, "g").div(3.0).unwrap(), range(1.0, 2.0, "g"));
+}
+
+#[test]
+fn mul_text() {
+    let text = Quantity::new(Value::from("some".to_string()), None);
+    assert!(text.mul(2.0).is_err());
+    assert!(text.div(2.0).is_err());
+}
+
+#[test_case(q(1.0, "kg"), q(500.0, "g") => Some(Ordering::Greater); "greater")]
+#[test_case(q(1.0, "kg"), q(1000.0, "g") => Some(Ordering::Equal); "equal")]
+#[test_case(q(1.0, "lb"), q(16.0, "oz") => Some(Ordering::Equal); "equal after rounding errors")]
+#[test_case(q(1.0, "cup"), q(1.0, "l") => Some(Ordering::Less); "less")]
+#[test_case(range(1.0, 2.0, "kg"), q(3.0, "kg") => Some(Ordering::Less); "range below")]
+#[test_case(range(1.0, 2.0, "kg"), q(1.5, "kg") => None; "number inside range")]
+#[test_case(range(1.0, 2.0, "kg"), range(1000.0, 2000.0, "g") => Some(Ordering::Equal); "same range")]
+#[test_case(range(1.0, 2.0, "kg"), range(2.0, 3.0, "kg") => None; "overlapping ranges")]
+fn cmp(a: Quantity, b: Quantity) -> Option<Ordering> {
+    a.try_cmp(&b, &Converter::bundled()).unwrap()
+}
+
+fn group(qs: &[Quantity]) -> GroupedQuantity {
+    let converter = Converter::bundled();
+    let mut g = GroupedQuantity::empty();
+    for q in qs {
+        g.add(q, None, &converter);
+    }
+    g
+}
+
+#[test]
+fn group_sub() {
+    let converter = Converter::bundled();
+    let mut g = group(&[q(1.0, "kg"), q(2.0, "cans")]);
+    g.try_sub(&q(200.0, "g"), &converter).unwrap();
+    g.try_sub(&q(1.0, "cans"), &converter).unwrap();
+    let mut parts = g
+        .into_vec()
+        .iter()
+        .map(|q| q.to_string())
+        .collect::<Vec<_>>();
+    parts.sort();
+    assert_eq!(parts, ["0.8 kg", "1 cans"]);
+}
+
+#[test]
+fn group_sub_missing() {
+    let converter = Converter::bundled();
+    let mut g = group(&[q(1.0, "kg")]);
+    g.try_sub(&q(1.0, "l"), &converter).unwrap();
+    assert_eq!(g.len(), 2);
+    assert!(g.iter().any(|q| q.to_string() == "-1 l"));
+}
+
+#[test]
+fn group_sub_group() {
+    let converter = Converter::bundled();
+    let mut g = group(&[q(1.0, "kg"), q(1.0, "l")]);
+    g.try_sub_group(&group(&[q(1.0, "kg"), q(500.0, "ml")]), &converter)
+        .unwrap();
+    assert!(g.iter().any(|q| q.to_string() == "0 kg"));
+    assert!(g.iter().any(|q| q.to_string() == "0.5 l"));
+}
+
+#[test]
+fn group_mul_div() {
+    let mut g = group(&[
+        q(1.0, "kg"),
+        range(1.0, 2.0, "cans"),
+        Quantity::new(Value::from("some".to_string()), None),
+    ]);
+    g.mul(3.0);
+    g.div(2.0);
+    let mut parts = g.iter().map(|q| q.to_string()).collect::<Vec<_>>();
+    parts.sort();
+    assert_eq!(parts, ["1.5 kg", "1.5-3 cans", "some"]);
+}
+
+#[test_case(&[q(1.0, "kg"), q(2.0, "cans")], &[q(500.0, "g")] => Some(Ordering::Greater); "greater")]
+#[test_case(&[q(1.0, "kg")], &[q(1000.0, "g")] => Some(Ordering::Equal); "equal")]
+#[test_case(&[q(1.0, "kg")], &[q(2.0, "kg"), q(1.0, "l")] => Some(Ordering::Less); "less")]
+#[test_case(&[q(1.0, "kg")], &[q(500.0, "g"), q(1.0, "l")] => None; "mixed")]
+#[test_case(&[], &[] => Some(Ordering::Equal); "empty")]
+fn group_cmp(a: &[Quantity], b: &[Quantity]) -> Option<Ordering> {
+    group(a).try_cmp(&group(b), &Converter::bundled()).unwrap()
+}
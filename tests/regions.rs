@@ -0,0 +1,155 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    convert::{ConvertError, ConvertTo, ConverterBuilderError, System, UnitsFile},
+    Converter, CooklangParser, Extensions, Quantity, Value,
+};
+use test_case::test_case;
+
+const UNITS: &str = r#"
+[region.test]
+system = "imperial"
+units = { cup = 0.25 }
+best = { volume = ["tsp", "tbsp", "cup"] }
+
+[region.metric_cup]
+system = "imperial"
+locales = ["xx"]
+units = { cup = 0.25 }
+"#;
+
+fn converter() -> Converter {
+    let file: UnitsFile = toml::from_str(UNITS).unwrap();
+    Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+fn q(value: f64, unit: Option<&str>) -> Quantity {
+    Quantity::new(Value::from(value), unit.map(String::from))
+}
+
+#[test_case("en", Some("GB") => Some("uk".to_string()))]
+#[test_case("en", Some("AU") => Some("au".to_string()))]
+#[test_case("EN", Some("us") => Some("us".to_string()))]
+#[test_case("en", None => None)]
+#[test_case("es", Some("ES") => None)]
+fn bundled_locales(lang: &str, dialect: Option<&str>) -> Option<String> {
+    Converter::bundled()
+        .region_for_locale(lang, dialect)
+        .map(|r| r.name().to_string())
+}
+
+#[test]
+fn find_unit_in_region() {
+    let converter = Converter::bundled();
+    let au = converter.region("AU").unwrap();
+    assert_eq!(au.system(), System::Metric);
+
+    let tbsp = converter.find_unit("tbsp").unwrap();
+    let au_tbsp = converter.find_unit_in("tablespoon", Some(au)).unwrap();
+    assert_eq!(tbsp.symbol(), au_tbsp.symbol());
+    assert_eq!(au_tbsp.ratio, 0.02);
+    assert_eq!(converter.find_unit_in("g", Some(au)).unwrap().ratio, 1.0);
+}
+
+#[test]
+fn variants_not_listed() {
+    let converter = converter();
+    let cups = converter.all_units().filter(|u| u.symbol() == "c").count();
+    assert_eq!(cups, 1);
+    assert_eq!(converter.all_units().count(), converter.unit_count());
+    assert_eq!(converter.unit_count(), Converter::bundled().unit_count());
+}
+
+#[test_case(None => "14.787 ml"; "no locale")]
+#[test_case(Some("en_US") => "14.787 ml"; "us")]
+#[test_case(Some("en_AU") => "20 ml"; "au")]
+#[test_case(Some("en_GB") => "15 ml"; "uk")]
+fn recipe_source_locale(locale: Option<&str>) -> String {
+    let converter = Converter::bundled();
+    let parser = CooklangParser::new(Extensions::all(), converter.clone());
+    let mut src = String::new();
+    if let Some(locale) = locale {
+        src.push_str(&format!("---\nlocale: {locale}\n---\n"));
+    }
+    src.push_str("@sugar{1%tbsp}");
+    let mut recipe = parser.parse(&src).into_output().unwrap();
+    let errors = recipe.convert(System::Metric, &converter);
+    assert!(errors.is_empty());
+    recipe.ingredients[0].quantity.as_ref().unwrap().to_string()
+}
+
+#[test]
+fn convert_to_region() {
+    let converter = converter();
+
+    // own best units
+    let mut q1 = q(500.0, Some("ml"));
+    q1.convert(ConvertTo::Region("test"), None, &converter)
+        .unwrap();
+    assert_eq!(q1.to_string(), "2 c");
+
+    // system best units with the variants
+    let mut q2 = q(500.0, Some("ml"));
+    q2.convert(ConvertTo::Region("metric_cup"), None, &converter)
+        .unwrap();
+    assert_eq!(q2.to_string(), "2 c");
+
+    // not affected by the region
+    let mut q3 = q(1.0, Some("kg"));
+    q3.convert(ConvertTo::Region("test"), None, &converter)
+        .unwrap();
+    let mut imperial = q(1.0, Some("kg"));
+    imperial
+        .convert(System::Imperial, None, &converter)
+        .unwrap();
+    assert_eq!(q3, imperial);
+
+    let mut q4 = q(1.0, Some("kg"));
+    let err = q4
+        .convert(ConvertTo::Region("mars"), None, &converter)
+        .unwrap_err();
+    assert!(matches!(err, ConvertError::UnknownRegion(_)));
+}
+
+#[test]
+fn recipe_convert_to_region() {
+    let converter = converter();
+    let parser = CooklangParser::new(Extensions::all(), converter.clone());
+    let src = "---\nlocale: xx\n---\n@milk{2%cups} @sugar{100%ml}";
+    let mut recipe = parser.parse(src).into_output().unwrap();
+
+    let errors = recipe.convert_to_region("mars", &converter);
+    assert!(matches!(errors[..], [ConvertError::UnknownRegion(_)]));
+
+    // the source is a metric_cup recipe
+    let errors = recipe.convert_to_region("test", &converter);
+    assert!(errors.is_empty());
+    let quantities = recipe
+        .ingredients
+        .iter()
+        .map(|i| i.quantity.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(quantities, ["2 c", "6.763 tbsp"]);
+}
+
+#[test_case("[region.a]\nunits = { cup = -1 }" => matches ConverterBuilderError::InvalidRegion { .. }; "negative ratio")]
+#[test_case("[region.a]\nunits = { cup = 1, c = 2 }" => matches ConverterBuilderError::InvalidRegion { .. }; "duplicate unit")]
+#[test_case("[region.a]\nunits = { nope = 1 }" => matches ConverterBuilderError::UnknownUnit(_); "unknown unit")]
+#[test_case("[region.a]\nbest = { volume = [] }" => matches ConverterBuilderError::EmptyBest { .. }; "empty best")]
+#[test_case("[region.a]\nbest = { energy = [\"g\"] }" => matches ConverterBuilderError::UndeclaredQuantity { .. }; "undeclared quantity")]
+fn builder_errors(units: &str) -> ConverterBuilderError {
+    let file: UnitsFile = toml::from_str(units).unwrap();
+    Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap_err()
+}
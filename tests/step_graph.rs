@@ -0,0 +1,202 @@
+use cooklang::{
+    step_graph::{EdgeKind, StepNode},
+    Converter, CooklangParser, Extensions, Recipe,
+};
+use indoc::indoc;
+
+fn parse(input: &str) -> Recipe {
+    CooklangParser::new(Extensions::all(), Converter::empty())
+        .parse(input)
+        .into_output()
+        .unwrap()
+}
+
+fn step(section: usize, content: usize) -> StepNode {
+    StepNode::Step { section, content }
+}
+
+fn section(section: usize) -> StepNode {
+    StepNode::Section { section }
+}
+
+fn edges(recipe: &Recipe) -> Vec<(StepNode, StepNode, EdgeKind)> {
+    recipe
+        .step_graph()
+        .edges()
+        .iter()
+        .map(|e| (e.from, e.to, e.kind))
+        .collect()
+}
+
+#[test]
+fn intermediate_steps() {
+    let recipe = parse(indoc! {"
+        Mix @flour{200%g} and @water.
+
+        Chop @onion.
+
+        Let the @&(~2)dough{} rest.
+
+        Add the @&(2)chopped onion{} to the @&(3)dough{}.
+    "});
+    assert_eq!(
+        edges(&recipe),
+        vec![
+            (step(0, 0), step(0, 2), EdgeKind::UsesOutput),
+            (step(0, 1), step(0, 3), EdgeKind::UsesOutput),
+            (step(0, 2), step(0, 3), EdgeKind::UsesOutput),
+            (step(0, 3), section(0), EdgeKind::PartOf),
+        ]
+    );
+}
+
+#[test]
+fn regular_references() {
+    let recipe = parse(indoc! {"
+        Melt @butter{50%g}.
+
+        Add @sugar{100%g} and the rest of the @&butter.
+
+        Dust with @&sugar.
+    "});
+    let graph = recipe.step_graph();
+    assert_eq!(
+        edges(&recipe),
+        vec![
+            (step(0, 0), step(0, 1), EdgeKind::UsesIngredient),
+            (step(0, 1), step(0, 2), EdgeKind::UsesIngredient),
+            (step(0, 2), section(0), EdgeKind::PartOf),
+        ]
+    );
+    assert_eq!(graph.edges()[0].ingredients, vec![2]);
+}
+
+#[test]
+fn same_step_reference() {
+    let recipe = parse("Add @salt and more @&salt.");
+    assert_eq!(
+        edges(&recipe),
+        vec![(step(0, 0), section(0), EdgeKind::PartOf)]
+    );
+}
+
+#[test]
+fn sections() {
+    let recipe = parse(indoc! {"
+        == Dough ==
+        Mix @flour{200%g} and @water.
+
+        Knead.
+
+        == Sauce ==
+        Cook @tomato.
+
+        == Pizza ==
+        Spread the @&(=2)sauce{} over the @&(=~2)dough{}.
+
+        Top with @&tomato.
+    "});
+    let graph = recipe.step_graph();
+    assert_eq!(
+        graph.nodes(),
+        [
+            step(0, 0),
+            step(0, 1),
+            section(0),
+            step(1, 0),
+            section(1),
+            step(2, 0),
+            step(2, 1),
+            section(2),
+        ]
+    );
+    assert_eq!(
+        edges(&recipe),
+        vec![
+            (section(1), step(2, 0), EdgeKind::UsesOutput),
+            (section(0), step(2, 0), EdgeKind::UsesOutput),
+            (step(1, 0), step(2, 1), EdgeKind::UsesIngredient),
+            (step(0, 0), section(0), EdgeKind::PartOf),
+            (step(0, 1), section(0), EdgeKind::PartOf),
+            (step(1, 0), section(1), EdgeKind::PartOf),
+            (step(2, 0), section(2), EdgeKind::PartOf),
+            (step(2, 1), section(2), EdgeKind::PartOf),
+        ]
+    );
+    assert_eq!(graph.dependencies(step(2, 0)).count(), 2);
+    assert_eq!(graph.dependents(step(1, 0)).count(), 2);
+    assert_eq!(graph.label(step(2, 1)), "Step 2");
+    assert_eq!(graph.label(section(1)), "Sauce");
+}
+
+#[test]
+fn text_steps_are_not_nodes() {
+    let recipe = parse(indoc! {"
+        > Some text.
+
+        Mix @flour.
+
+        > More text.
+
+        Bake the @&(~1)dough{}.
+    "});
+    assert_eq!(
+        recipe.step_graph().nodes(),
+        [step(0, 1), step(0, 3), section(0)]
+    );
+    assert_eq!(
+        edges(&recipe)[0],
+        (step(0, 1), step(0, 3), EdgeKind::UsesOutput)
+    );
+}
+
+#[test]
+fn dot() {
+    let recipe = parse(indoc! {r#"
+        == Dough ==
+        Mix @flour{200%g} and @water.
+
+        == Bread ==
+        Bake the @&(=1)dough{}.
+    "#});
+    assert_eq!(
+        recipe.step_graph().to_dot(),
+        indoc! {r#"
+            digraph recipe {
+              subgraph cluster_0 {
+                label="Dough";
+                s0_0 [label="Step 1"];
+                sec0 [label="Dough", shape=box];
+              }
+              subgraph cluster_1 {
+                label="Bread";
+                s1_0 [label="Step 1"];
+                sec1 [label="Bread", shape=box];
+              }
+              sec0 -> s1_0 [label="dough"];
+              s0_0 -> sec0 [style=dashed];
+              s1_0 -> sec1 [style=dashed];
+            }
+        "#}
+    );
+}
+
+#[test]
+fn mermaid() {
+    let recipe = parse(indoc! {r##"
+        Mix @flour{200%g} and @water.
+
+        Let the @&(~1)"wet" dough{} rest.
+    "##});
+    assert_eq!(
+        recipe.step_graph().to_mermaid(),
+        indoc! {r##"
+            flowchart TD
+              s0_0["Step 1"]
+              s0_1["Step 2"]
+              sec0[["Section 1"]]
+              s0_0 -->|"#quot;wet#quot; dough"| s0_1
+              s0_1 -.-> sec0
+        "##}
+    );
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{error::SourceReport, Converter, CooklangParser, Extensions};
+use indoc::indoc;
+use test_case::test_case;
+
+fn report(input: &str, extensions: Extensions) -> SourceReport {
+    CooklangParser::new(extensions, Converter::bundled())
+        .parse(input)
+        .into_report()
+}
+
+/// Hints with a suggestion of all the diagnostics
+fn suggestions(input: &str) -> Vec<String> {
+    report(input, Extensions::all())
+        .iter()
+        .flat_map(|d| &d.hints)
+        .filter(|h| h.starts_with("Did you mean"))
+        .map(|h| h.to_string())
+        .collect()
+}
+
+#[test_case("@flour{200%gramms}" => vec!["Did you mean 'grams'?"]; "ingredient unit")]
+#[test_case("@sugar{1%Tbsp}" => vec!["Did you mean 'tbsp'?"]; "unit case")]
+#[test_case("#pot{2%litre}" => Vec::<String>::new(); "known unit")]
+#[test_case("@salt{1%pinch}" => Vec::<String>::new(); "unknown unit")]
+#[test_case("@tea{1%mug}" => Vec::<String>::new(); "short unit")]
+#[test_case("@rice{50%grains}" => Vec::<String>::new(); "real word")]
+#[test_case("~{10%mintues}" => vec!["Did you mean 'minutes'?"]; "timer unit")]
+fn units(input: &str) -> Vec<String> {
+    suggestions(input)
+}
+
+#[test]
+fn unit_typo_is_warning() {
+    let report = report("@flour{200%gramms}", Extensions::empty());
+    assert!(!report.has_errors());
+    assert_eq!(report.warnings().count(), 1);
+}
+
+#[test_case("---\nservigs: 2\n---\nstep" => vec!["Did you mean 'servings'?"]; "frontmatter")]
+#[test_case(">> servigs: 2\nstep" => vec!["Did you mean 'servings'?"]; "old style")]
+#[test_case("---\nprep tme: 10 min\n---\nstep" => vec!["Did you mean 'prep time'?"]; "space")]
+#[test_case("---\nrating: 5\n---\nstep" => Vec::<String>::new(); "custom key")]
+fn metadata_keys(input: &str) -> Vec<String> {
+    suggestions(input)
+}
+
+#[test_case(">> [mode]: componets" => vec!["Did you mean 'components'?"]; "mode value")]
+#[test_case(">> [duplicate]: refrence" => vec!["Did you mean 'reference'?"]; "duplicate value")]
+#[test_case(">> [mdoe]: steps" => vec!["Did you mean '[mode]'?"]; "config key")]
+fn modes(input: &str) -> Vec<String> {
+    suggestions(input)
+}
+
+#[test]
+fn references() {
+    let input = indoc! {"
+        Mix @flour{200%g} and @sugar{50%g}.
+
+        Add the @&flur.
+    "};
+    assert_eq!(suggestions(input), ["Did you mean 'flour'?"]);
+}
+
+#[test]
+fn no_reference_suggestion() {
+    let input = indoc! {"
+        Mix @flour{200%g}.
+
+        Add the @&eggs.
+    "};
+    assert!(suggestions(input).is_empty());
+}
+
+#[test]
+fn suggest_unit() {
+    let converter = Converter::bundled();
+    assert_eq!(converter.suggest_unit("kilogrms"), Some("kilograms"));
+    assert_eq!(converter.suggest_unit("kilograms"), None);
+    assert_eq!(converter.suggest_unit("handful"), None);
+    assert_eq!(converter.suggest_unit("grains"), None);
+    assert_eq!(converter.suggest_unit("slices"), None);
+}
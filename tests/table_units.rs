@@ -0,0 +1,197 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    convert::{ConvertError, ConverterBuilderError, System, UnitsFile},
+    model::Item,
+    Converter, CooklangParser, Extensions, Quantity, Value,
+};
+use test_case::test_case;
+
+fn q(value: f64, unit: &str) -> Quantity {
+    Quantity::new(Value::from(value), Some(unit.to_string()))
+}
+
+#[test_case(q(180.0, "°C"), "gas mark" => "4 gas mark")]
+#[test_case(q(175.0, "°C"), "gas mark" => "3.5 gas mark"; "interpolated")]
+#[test_case(q(110.0, "°C"), "gas mark" => "0.25 gas mark"; "first point")]
+#[test_case(q(260.0, "°C"), "gas mark" => "10 gas mark"; "last point")]
+#[test_case(q(270.0, "°C"), "gas mark" => "10 gas mark"; "above the table")]
+#[test_case(q(100.0, "°C"), "gas mark" => "0.25 gas mark"; "below the table")]
+#[test_case(q(4.0, "°C"), "gas mark" => "0.25 gas mark"; "fridge")]
+#[test_case(q(12.0, "gas mark"), "°C" => "260 °C"; "back above the table")]
+#[test_case(q(356.0, "°F"), "gas mark" => "4 gas mark"; "from fahrenheit")]
+#[test_case(q(4.0, "Gas Mark"), "°C" => "180 °C"; "back")]
+#[test_case(q(0.5, "gas mark"), "°C" => "120 °C"; "fraction back")]
+#[test_case(q(6.0, "gas mark"), "°F" => "392 °F"; "to fahrenheit")]
+#[test_case(q(160.0, "°C fan"), "°C" => "180 °C"; "fan")]
+#[test_case(q(160.0, "°C fan"), "gas mark" => "4 gas mark"; "fan to gas")]
+fn convert(mut q: Quantity, to: &str) -> String {
+    q.convert(to, None, &Converter::bundled()).unwrap();
+    q.to_string()
+}
+
+#[test_case(q(175.0, "°C") => "4 gas mark")]
+#[test_case(q(115.0, "°C") => "0.5 gas mark")]
+fn humanize(mut q: Quantity) -> String {
+    let converter = Converter::bundled();
+    q.convert("gas mark", None, &converter).unwrap();
+    q.humanize(&converter);
+    q.to_string()
+}
+
+#[test_case(q(2.0, "heat"), "heat" => "2 heat")]
+#[test_case(Quantity::new(Value::Text("medium".into()), Some("heat".into())), "heat" => "2 heat"; "preset")]
+#[test_case(Quantity::new(Value::Text("High".into()), Some("heat".into())), "heat" => "3 heat"; "preset case")]
+fn convert_heat(mut q: Quantity, to: &str) -> String {
+    q.convert(to, None, &Converter::bundled()).unwrap();
+    q.to_string()
+}
+
+#[test]
+fn heat_is_not_a_temperature() {
+    let converter = Converter::bundled();
+    let mut q = Quantity::new(Value::Text("medium".into()), Some("heat".into()));
+    assert!(matches!(
+        q.convert("°C", None, &converter),
+        Err(ConvertError::MixedQuantities { .. })
+    ));
+    q.convert(System::Imperial, None, &converter).unwrap();
+    assert_eq!(q.to_string(), "medium heat");
+}
+
+#[test]
+fn unknown_preset() {
+    let mut q = Quantity::new(Value::Text("scorching".into()), Some("heat".into()));
+    assert!(q.convert("heat", None, &Converter::bundled()).is_err());
+}
+
+#[test_case("Preheat the oven to 180 °C." => "180 °C"; "oven")]
+#[test_case("Keep at 4 °C." => "4 °C"; "fridge")]
+fn uk_keeps_celsius(text: &str) -> String {
+    let converter = Converter::bundled();
+    let mut recipe = CooklangParser::new(Extensions::all(), converter.clone())
+        .parse(text)
+        .into_output()
+        .unwrap();
+    let errors = recipe.convert_to_region("uk", &converter);
+    assert!(errors.is_empty());
+    recipe.inline_quantities[0].to_string()
+}
+
+#[test]
+fn region_best_opt_in() {
+    let file: UnitsFile =
+        toml::from_str("[region.uk]\nbest = { temperature = [\"gas mark\"] }").unwrap();
+    let converter = Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap();
+    let mut recipe = CooklangParser::new(Extensions::all(), converter.clone())
+        .parse("Preheat the oven to 180 °C and add @flour{200%g}.")
+        .into_output()
+        .unwrap();
+    let errors = recipe.convert_to_region("uk", &converter);
+    assert!(errors.is_empty());
+    assert_eq!(recipe.inline_quantities[0].to_string(), "4 gas mark");
+    assert_eq!(
+        recipe.ingredients[0].quantity.as_ref().unwrap().to_string(),
+        "200 g"
+    );
+}
+
+#[test_case("table = [[1, 140]]"; "one point")]
+#[test_case("table = [[1, 140], [2, 130]]"; "decreasing")]
+#[test_case("table = [[1, 140], [1, 150]]"; "repeated")]
+#[test_case("table = [[1, 140], [2, 150]], expand_si = true"; "expand si")]
+fn invalid_table(entry: &str) {
+    let units = format!(
+        "[[quantity]]\nquantity = \"temperature\"\n[quantity.units]\nunspecified = [{{ names = [\"dial\"], symbols = [], ratio = 1, {entry} }}]"
+    );
+    let file: UnitsFile = toml::from_str(&units).unwrap();
+    let err = Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap_err();
+    assert!(matches!(err, ConverterBuilderError::InvalidTable { .. }));
+}
+
+#[test]
+fn no_region_variant() {
+    let file: UnitsFile = toml::from_str("[region.a]\nunits = { \"gas mark\" = 2 }").unwrap();
+    let err = Converter::builder()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap_err();
+    assert!(matches!(err, ConverterBuilderError::InvalidRegion { .. }));
+}
+
+fn inline(text: &str) -> Vec<String> {
+    CooklangParser::new(Extensions::all(), Converter::bundled())
+        .parse(text)
+        .into_output()
+        .unwrap()
+        .inline_quantities
+        .iter()
+        .map(|q| q.to_string())
+        .collect()
+}
+
+#[test_case("Bake at 160 °C fan for 20 min." => vec!["160 °C fan", "20 min"]; "multi word symbol")]
+#[test_case("Bake at 180ºC." => vec!["180 ºC"]; "no space")]
+#[test_case("Bake at 4 gas mark." => vec!["4 gas mark"]; "multi word name")]
+#[test_case("Bake at Gas Mark 4, or 160 °C fan." => vec!["4 Gas Mark", "160 °C fan"]; "prefix")]
+#[test_case("Bake (GM 6) until golden" => vec!["6 GM"]; "prefix in parens")]
+#[test_case("Cook over medium-high heat, then low heat." => vec!["medium-high heat", "low heat"]; "presets")]
+#[test_case("Use 2 eggs, mark 3 of them" => Vec::<String>::new(); "no unit")]
+#[test_case("Use the cup 2 times." => Vec::<String>::new(); "cup before number")]
+#[test_case("Take vitamin C 2 times a day." => Vec::<String>::new(); "symbol before number")]
+#[test_case("Add the g 5 and l 3 later" => Vec::<String>::new(); "letters before numbers")]
+#[test_case("Cool for 1-2 h" => Vec::<String>::new(); "range")]
+fn inline_quantities(text: &str) -> Vec<String> {
+    inline(text)
+}
+
+#[test]
+fn inline_items() {
+    let recipe = CooklangParser::new(Extensions::all(), Converter::bundled())
+        .parse("Bake at Gas Mark 4 for a while.")
+        .into_output()
+        .unwrap();
+    let items = &recipe.sections[0].content[0].unwrap_step().items;
+    assert_eq!(
+        items,
+        &vec![
+            Item::Text {
+                value: "Bake at ".into()
+            },
+            Item::InlineQuantity { index: 0 },
+            Item::Text {
+                value: " for a while.".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn inline_region() {
+    let converter = Converter::bundled();
+    let mut recipe = CooklangParser::new(Extensions::all(), converter.clone())
+        .parse("Fry over medium heat, then bake at Gas Mark 4.")
+        .into_output()
+        .unwrap();
+    let errors = recipe.convert(System::Metric, &converter);
+    assert!(errors.is_empty());
+    let inline = recipe
+        .inline_quantities
+        .iter()
+        .map(|q| q.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(inline, ["medium heat", "180 °C"]);
+}
@@ -0,0 +1,395 @@
+#![cfg(feature = "bundled_units")]
+
+use cooklang::{
+    metadata::RecipeTime,
+    step_graph::StepNode,
+    timeline::{ClockTime, MealTimeline, Timeline, TimelineOptions},
+    Converter, CooklangParser, Extensions, Recipe,
+};
+use indoc::indoc;
+use test_case::test_case;
+
+fn parse(input: &str) -> Recipe {
+    CooklangParser::new(Extensions::all(), Converter::bundled())
+        .parse(input)
+        .into_output()
+        .unwrap()
+}
+
+fn timeline(recipe: &Recipe, options: TimelineOptions) -> Timeline {
+    recipe.timeline(&Converter::bundled(), &options)
+}
+
+/// Steps without references can be done at any moment
+fn parallel() -> TimelineOptions {
+    TimelineOptions {
+        sequential: false,
+        ..Default::default()
+    }
+}
+
+fn step(section: usize, content: usize) -> StepNode {
+    StepNode::Step { section, content }
+}
+
+fn starts(timeline: &Timeline) -> Vec<(StepNode, f64)> {
+    timeline.steps.iter().map(|s| (s.node, s.start)).collect()
+}
+
+const BREAD: &str = indoc! {"
+    Mix @flour{500%g} and @water{300%ml}.
+
+    Let the @&(~1)dough{} rise for ~{2%hour}.
+
+    Chop the @olives{100%g}.
+
+    Fold the @&olives into the @&(2)dough{} and bake for ~{40-45%min}.
+"};
+
+#[test]
+fn passive_time_in_parallel() {
+    let recipe = parse(BREAD);
+    let timeline = timeline(&recipe, parallel());
+    assert_eq!(
+        starts(&timeline),
+        vec![
+            (step(0, 0), 0.0),
+            (step(0, 1), 5.0),
+            (step(0, 2), 10.0),
+            (step(0, 3), 130.0),
+        ]
+    );
+    assert_eq!(timeline.total, 180.0);
+    assert_eq!(timeline.active_time(), 20.0);
+    assert_eq!(timeline.passive_time(), 165.0);
+    assert_eq!(
+        timeline.critical_path,
+        vec![step(0, 0), step(0, 1), step(0, 3)]
+    );
+    assert!(!timeline.step(step(0, 2)).unwrap().critical);
+    assert!(timeline.unknown_timers.is_empty());
+}
+
+#[test]
+fn one_cook_at_a_time() {
+    let recipe = parse(indoc! {"
+        Chop @onion.
+
+        Chop @garlic.
+
+        Fry the @&onion and @&garlic.
+    "});
+    let options = TimelineOptions {
+        active_minutes: 10.0,
+        ..parallel()
+    };
+    let timeline = timeline(&recipe, options.clone());
+    assert_eq!(
+        starts(&timeline),
+        vec![(step(0, 0), 0.0), (step(0, 1), 10.0), (step(0, 2), 20.0)]
+    );
+
+    let timeline = self::timeline(
+        &recipe,
+        TimelineOptions {
+            cooks: 2,
+            ..options
+        },
+    );
+    assert_eq!(
+        starts(&timeline),
+        vec![(step(0, 0), 0.0), (step(0, 1), 0.0), (step(0, 2), 10.0)]
+    );
+    assert_eq!(timeline.steps[1].cook, 1);
+    assert_eq!(timeline.total, 20.0);
+}
+
+#[test]
+fn longest_chain_first() {
+    let recipe = parse(indoc! {"
+        Set the table.
+
+        Marinate the @chicken for ~{1%hour}.
+
+        Grill the @&chicken.
+    "});
+    let timeline = timeline(&recipe, parallel());
+    // the marinade goes first even if it's later in the recipe
+    assert_eq!(
+        starts(&timeline),
+        vec![(step(0, 1), 0.0), (step(0, 0), 5.0), (step(0, 2), 65.0)]
+    );
+}
+
+#[test]
+fn sequential() {
+    let recipe = parse(indoc! {"
+        Mix @flour and @water.
+
+        Put it in the oven for ~{30%min}.
+
+        Serve.
+    "});
+    let timeline = timeline(&recipe, TimelineOptions::default());
+    assert_eq!(
+        starts(&timeline),
+        vec![(step(0, 0), 0.0), (step(0, 1), 5.0), (step(0, 2), 40.0)]
+    );
+    assert_eq!(timeline.total, 45.0);
+
+    // without references nothing says the oven goes after mixing
+    assert_eq!(self::timeline(&recipe, parallel()).total, 35.0);
+}
+
+#[test]
+fn no_references() {
+    let recipe = parse(indoc! {"
+        Preheat the #oven to 180 °C.
+
+        Mix @flour{200%g}, @sugar{100%g} and @eggs{2}.
+
+        Pour into a #tin and bake for ~{30%min}.
+
+        Let cool for ~{10%min} and serve.
+    "});
+    let timeline = timeline(&recipe, TimelineOptions::default());
+    assert_eq!(
+        starts(&timeline),
+        vec![
+            (step(0, 0), 0.0),
+            (step(0, 1), 5.0),
+            (step(0, 2), 10.0),
+            (step(0, 3), 45.0)
+        ]
+    );
+    assert_eq!(timeline.total, 60.0);
+    assert_eq!(
+        timeline.critical_path,
+        vec![step(0, 0), step(0, 1), step(0, 2), step(0, 3)]
+    );
+}
+
+#[test]
+fn sections() {
+    let recipe = parse(indoc! {"
+        == Sauce ==
+        Simmer @tomatoes for ~{20%min}.
+
+        == Pasta ==
+        Boil @pasta for ~{10%min}.
+
+        Mix with the @&(=1)sauce{}.
+    "});
+    let timeline = timeline(&recipe, TimelineOptions::default());
+    assert_eq!(
+        starts(&timeline),
+        vec![(step(0, 0), 0.0), (step(1, 0), 5.0), (step(1, 1), 25.0)]
+    );
+    assert_eq!(timeline.total, 30.0);
+}
+
+#[test]
+fn unknown_timers() {
+    let recipe = CooklangParser::new(Extensions::empty(), Converter::bundled())
+        .parse("Wait ~{a while}.")
+        .into_output()
+        .unwrap();
+    let timeline = timeline(&recipe, TimelineOptions::default());
+    assert_eq!(timeline.unknown_timers, vec![0]);
+    assert_eq!(timeline.total, 5.0);
+}
+
+#[test]
+fn start_times() {
+    let recipe = parse(BREAD);
+    let timeline = timeline(&recipe, parallel());
+    let dinner: ClockTime = "19:30".parse().unwrap();
+    assert_eq!(timeline.start_at(dinner).to_string(), "16:30");
+    let times = timeline
+        .clock_times(dinner)
+        .into_iter()
+        .map(|(_, t)| t.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(times, ["16:30", "16:35", "16:40", "18:40"]);
+
+    let breakfast = ClockTime::new(1, 0).unwrap();
+    let start = timeline.start_at(breakfast);
+    assert_eq!(start.day(), -1);
+    assert_eq!(start.to_string(), "22:00 (-1 day)");
+}
+
+#[test_case("7:05" => ClockTime::new(7, 5); "one digit hour")]
+#[test_case("23:59" => ClockTime::new(23, 59))]
+#[test_case("24:00" => None; "hour out of range")]
+#[test_case("12:5" => None; "one digit minute")]
+#[test_case("noon" => None)]
+fn parse_clock_time(s: &str) -> Option<ClockTime> {
+    s.parse().ok()
+}
+
+#[test]
+fn compare_time() {
+    let recipe = parse(&format!(
+        "---\nprep time: 20 min\ncook time: 2 h\n---\n{BREAD}"
+    ));
+    let timeline = timeline(&recipe, parallel());
+    let comparison = timeline
+        .compare_time(&recipe.metadata, &Converter::bundled())
+        .unwrap();
+    assert_eq!(
+        comparison.expected,
+        RecipeTime::Composed {
+            prep_time: Some(20),
+            cook_time: Some(120)
+        }
+    );
+    assert_eq!(comparison.difference(), 40.0);
+    assert_eq!(comparison.prep_difference(), Some(0.0));
+    assert!(!comparison.is_consistent(0.1));
+    assert!(comparison.is_consistent(0.5));
+
+    let recipe = parse(BREAD);
+    assert!(timeline
+        .compare_time(&recipe.metadata, &Converter::bundled())
+        .is_none());
+}
+
+fn meal(recipes: &[&Recipe], options: TimelineOptions) -> MealTimeline {
+    MealTimeline::new(recipes, &Converter::bundled(), &options)
+}
+
+fn meal_starts(meal: &MealTimeline) -> Vec<(usize, StepNode, f64)> {
+    meal.steps
+        .iter()
+        .map(|s| (s.recipe, s.step.node, s.step.start))
+        .collect()
+}
+
+fn oven(capacity: usize) -> TimelineOptions {
+    TimelineOptions {
+        cookware_capacity: [("Oven".to_string(), capacity)].into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn single_recipe_cookware() {
+    let recipe = parse(indoc! {"
+        Roast the @peppers in the #oven for ~{20%min}.
+
+        Bake the @bread in the #oven for ~{30%min}.
+    "});
+    let timeline = timeline(&recipe, parallel());
+    assert_eq!(
+        starts(&timeline),
+        vec![(step(0, 1), 0.0), (step(0, 0), 35.0)]
+    );
+    assert_eq!(timeline.steps[0].cookware, vec![1]);
+
+    let timeline = self::timeline(
+        &recipe,
+        TimelineOptions {
+            sequential: false,
+            ..oven(2)
+        },
+    );
+    assert_eq!(
+        starts(&timeline),
+        vec![(step(0, 1), 0.0), (step(0, 0), 5.0)]
+    );
+    assert_eq!(timeline.total, 35.0);
+}
+
+#[test]
+fn meal_ready_at_the_same_time() {
+    let stew = parse(indoc! {"
+        Brown the @beef.
+
+        Simmer the @&beef with @wine for ~{1%hour}.
+    "});
+    let salad = parse("Toss the @lettuce with @dressing.");
+    let meal = meal(&[&stew, &salad], TimelineOptions::default());
+    assert_eq!(
+        meal_starts(&meal),
+        vec![
+            (0, step(0, 0), 0.0),
+            (0, step(0, 1), 5.0),
+            (1, step(0, 0), 65.0)
+        ]
+    );
+    assert_eq!(meal.total, 70.0);
+    assert_eq!(meal.ready, [70.0, 70.0]);
+}
+
+#[test]
+fn meal_cooks_shared() {
+    let a = parse("Chop @onion.");
+    let b = parse("Chop @carrot.");
+    let meal = meal(&[&a, &b], TimelineOptions::default());
+    assert_eq!(meal.total, 10.0);
+    assert_eq!(meal.ready, [5.0, 10.0]);
+
+    let meal = self::meal(
+        &[&a, &b],
+        TimelineOptions {
+            cooks: 2,
+            ..Default::default()
+        },
+    );
+    assert_eq!(meal.total, 5.0);
+    assert_eq!(meal.ready, [5.0, 5.0]);
+}
+
+const TURKEY: &str = "Roast the @turkey in the #oven at 180 °C for ~{3%hours}.";
+
+#[test_case("180 °C" => 185.0; "same temperature")]
+#[test_case("356 °F" => 185.0; "same temperature other unit")]
+#[test_case("200 °C" => 235.0; "different temperature")]
+fn meal_oven_temperatures(temperature: &str) -> f64 {
+    let turkey = parse(TURKEY);
+    let pie = parse(&format!(
+        "Bake the @pie in the #oven at {temperature} for ~{{45%min}}."
+    ));
+    let meal = meal(&[&turkey, &pie], oven(2));
+    assert!(meal.steps.iter().all(|s| s.step.temperature.is_some()));
+    meal.total
+}
+
+#[test]
+fn heat_is_not_an_oven_temperature() {
+    let sauce = parse("Warm the @sauce in a #pan over medium heat for ~{20%min}.");
+    let step = &timeline(&sauce, TimelineOptions::default()).steps[0];
+    assert!(step.temperature.is_none());
+}
+
+#[test]
+fn meal_cookware_amount() {
+    let soup = parse("Boil the @soup in two #pots{2} on the #stove for ~{30%min}.");
+    let pasta = parse("Boil the @pasta on the #stove{2} for ~{10%min}.");
+    let stove = |capacity| TimelineOptions {
+        cookware_capacity: [("stove".to_string(), capacity)].into(),
+        ..Default::default()
+    };
+    // soup uses 1 burner, pasta 2
+    assert_eq!(meal(&[&soup, &pasta], stove(3)).total, 35.0);
+    assert_eq!(meal(&[&soup, &pasta], stove(2)).total, 50.0);
+}
+
+#[test]
+fn meal_clock_times() {
+    let turkey = parse(TURKEY);
+    let bread = CooklangParser::new(Extensions::empty(), Converter::bundled())
+        .parse("Warm the @bread for ~{a bit}.")
+        .into_output()
+        .unwrap();
+    let meal = meal(&[&turkey, &bread], TimelineOptions::default());
+    assert_eq!(meal.unknown_timers, vec![(1, 0)]);
+    let dinner = ClockTime::new(20, 0).unwrap();
+    assert_eq!(meal.start_at(dinner).to_string(), "16:55");
+    let times = meal
+        .clock_times(dinner)
+        .into_iter()
+        .map(|(s, t)| (s.recipe, t.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(times, [(0, "16:55".to_string()), (1, "19:55".to_string())]);
+}
@@ -0,0 +1,296 @@
+#![cfg(all(feature = "bundled_units", feature = "units_diagnostics"))]
+
+use std::ops::Range;
+
+use cooklang::{convert::ConverterBuilder, Converter};
+use indoc::indoc;
+
+fn bundled() -> ConverterBuilder {
+    Converter::builder().with_bundled_units().unwrap()
+}
+
+/// Message, text of each label and hints of the first error
+fn first_error(builder: &ConverterBuilder, input: &str) -> (String, Vec<String>, Vec<String>) {
+    let (file, report) = builder.parse_units_file_lenient(input).into_tuple();
+    assert!(file.is_none());
+    let error = report.errors().next().expect("no error");
+    let labels = error
+        .labels
+        .iter()
+        .map(|(span, _)| input[Range::from(*span)].to_string())
+        .collect();
+    let hints = error.hints.iter().map(|h| h.to_string()).collect();
+    (error.message.to_string(), labels, hints)
+}
+
+fn warnings(builder: &ConverterBuilder, input: &str) -> Vec<(String, String)> {
+    let (file, report) = builder.parse_units_file_lenient(input).into_tuple();
+    assert!(file.is_some(), "{:?}", report.errors().next());
+    report
+        .warnings()
+        .map(|w| {
+            let (span, _) = w.labels.first().expect("no label");
+            (w.message.to_string(), input[Range::from(*span)].to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn valid_file() {
+    let input = indoc! {r#"
+        custom_quantities = ["count"]
+
+        [[quantity]]
+        quantity = "count"
+        best = ["piece", "dozen"]
+        [quantity.units]
+        unspecified = [
+            { names = ["piece", "pieces"], symbols = ["pc"], ratio = 1 },
+            { names = ["dozen"], symbols = ["dz"], ratio = 12 },
+        ]
+    "#};
+    let mut builder = bundled();
+    let (file, report) = builder.parse_units_file_lenient(input).into_tuple();
+    assert!(report.is_empty());
+    builder.add_units_file(file.unwrap()).unwrap();
+    let converter = builder.finish().unwrap();
+    assert!(converter.find_unit("dz").is_some());
+}
+
+#[test]
+fn toml_syntax() {
+    let (message, labels, _) = first_error(&bundled(), "[extend.units\ncup = 1\n");
+    assert!(message.contains("invalid table header"), "{message}");
+    assert_eq!(labels.len(), 1);
+}
+
+#[test]
+fn unknown_field() {
+    let input = indoc! {r#"
+        custom_quantities = ["count"]
+
+        [[quantity]]
+        quantity = "count"
+        bets = ["piece"]
+    "#};
+    let (message, labels, _) = first_error(&bundled(), input);
+    assert!(message.contains("unknown field `bets`"), "{message}");
+    assert_eq!(labels.len(), 1);
+}
+
+#[test]
+fn duplicate_symbol_from_other_file() {
+    let input = indoc! {r#"
+        [[quantity]]
+        quantity = "mass"
+        [quantity.units]
+        metric = [{ names = ["gramo"], symbols = ["g"], ratio = 1 }]
+    "#};
+    let (message, labels, hints) = first_error(&bundled(), input);
+    assert_eq!(message, "Duplicate unit: g");
+    assert_eq!(labels, [r#""g""#]);
+    assert_eq!(hints, ["'g' is already used by another unit"]);
+}
+
+#[test]
+fn duplicate_in_same_file() {
+    let input = indoc! {r#"
+        custom_quantities = ["count"]
+
+        [[quantity]]
+        quantity = "count"
+        best = ["piece"]
+        [quantity.units]
+        unspecified = [
+            { names = ["piece"], symbols = ["pc"], ratio = 1 },
+            { names = ["pack"], symbols = ["pc"], ratio = 6 },
+        ]
+    "#};
+    let (message, labels, _) = first_error(&bundled(), input);
+    assert_eq!(message, "Duplicate unit: pc");
+    assert_eq!(labels, [r#""pc""#, r#""pc""#]);
+    let (file, report) = bundled().parse_units_file_lenient(input).into_tuple();
+    assert!(file.is_none());
+    let spans = &report.errors().next().unwrap().labels;
+    assert!(spans[0].0.start() > spans[1].0.start(), "duplicate first");
+}
+
+#[test]
+fn best_unit_not_defined() {
+    let input = indoc! {r#"
+        custom_quantities = ["count"]
+
+        [[quantity]]
+        quantity = "count"
+        best = ["piece", "dozn"]
+        [quantity.units]
+        unspecified = [
+            { names = ["piece"], symbols = ["pc"], ratio = 1 },
+            { names = ["dozen"], symbols = ["dz"], ratio = 12 },
+        ]
+    "#};
+    let (message, labels, hints) = first_error(&bundled(), input);
+    assert_eq!(message, "Unknown unit: 'dozn'");
+    assert_eq!(labels, [r#""dozn""#]);
+    assert_eq!(hints, ["Did you mean 'dozen'?"]);
+}
+
+#[test]
+fn undeclared_quantity() {
+    let input = indoc! {r#"
+        [[quantity]]
+        quantity = "volum"
+        best = ["l"]
+    "#};
+    let (message, labels, _) = first_error(&bundled(), input);
+    assert_eq!(
+        message,
+        "Physical quantity 'volum' is not declared in the custom quantities"
+    );
+    assert_eq!(labels, [r#""volum""#]);
+}
+
+#[test]
+fn empty_custom_best() {
+    let input = indoc! {r#"
+        custom_quantities = ["count"]
+    "#};
+    let (message, labels, _) = first_error(&bundled(), input);
+    assert_eq!(
+        message,
+        "Invalid custom quantity 'count': no best units given"
+    );
+    assert_eq!(labels, [r#""count""#]);
+}
+
+#[test]
+fn extend_unknown_unit() {
+    let input = indoc! {r#"
+        [extend.units]
+        cupp = { ratio = 0.25 }
+    "#};
+    let (message, labels, hints) = first_error(&bundled(), input);
+    assert_eq!(message, "Unknown unit: 'cupp'");
+    assert_eq!(labels, ["cupp"]);
+    assert_eq!(hints, ["Did you mean 'cup'?"]);
+}
+
+#[test]
+fn bad_expand_si() {
+    let input = indoc! {r#"
+        [[quantity]]
+        quantity = "temperature"
+        [quantity.units]
+        metric = [
+            { names = ["kelvin"], symbols = ["K"], ratio = 1, expand_si = true, table = [[0, 0], [1, 1]] },
+        ]
+    "#};
+    let (message, labels, _) = first_error(&bundled(), input);
+    assert_eq!(
+        message,
+        "Invalid conversion table for 'K': units with a table can't expand SI"
+    );
+    assert_eq!(labels, ["table"]);
+}
+
+#[test]
+fn expand_si_without_prefixes() {
+    let input = indoc! {r#"
+        custom_quantities = ["energy"]
+
+        [[quantity]]
+        quantity = "energy"
+        best = ["J"]
+        [quantity.units]
+        metric = [{ names = ["joule"], symbols = ["J"], ratio = 1, expand_si = true }]
+    "#};
+    let (message, labels, hints) = first_error(&Converter::builder(), input);
+    assert!(message.contains("No SI prefixes"), "{message}");
+    assert_eq!(labels, ["expand_si"]);
+    assert_eq!(hints, ["Add `[si.prefixes]` and `[si.symbol_prefixes]`"]);
+}
+
+#[test]
+fn error_in_other_file() {
+    let input = indoc! {r#"
+        custom_quantities = ["count"]
+
+        [[quantity]]
+        quantity = "count"
+        best = ["piece"]
+        [quantity.units]
+        unspecified = [{ names = ["piece"], symbols = ["pc"], ratio = 1 }]
+    "#};
+    // without the bundled units, volume and others don't have best units
+    let (message, labels, hints) = first_error(&Converter::builder(), input);
+    assert!(message.starts_with("Best units for"), "{message}");
+    assert!(labels.is_empty());
+    assert_eq!(hints, ["The problem may be in another units file"]);
+}
+
+#[test]
+fn override_removes_names() {
+    let input = indoc! {r#"
+        [extend]
+        precedence = "override"
+        [extend.units]
+        cup = { names = ["cup"] }
+    "#};
+    assert_eq!(
+        warnings(&bundled(), input),
+        [(
+            "Override removes 'cups' from unit 'cup'".to_string(),
+            "cup".to_string()
+        )]
+    );
+}
+
+#[test]
+fn extended_by_two_files() {
+    let first = toml::from_str(indoc! {r#"
+        [extend.units]
+        cup = { ratio = 0.25 }
+    "#})
+    .unwrap();
+    let builder = bundled().with_units_file(first).unwrap();
+    let input = indoc! {r#"
+        [extend.units]
+        c = { ratio = 0.24, aliases = ["mug"] }
+    "#};
+    assert_eq!(
+        warnings(&builder, input),
+        [(
+            "Unit 'c' is also extended by another units file".to_string(),
+            "c".to_string()
+        )]
+    );
+
+    // not a conflict
+    let input = indoc! {r#"
+        [extend.units]
+        cup = { aliases = ["mug"] }
+    "#};
+    assert!(warnings(&builder, input).is_empty());
+}
+
+#[test]
+fn si_override() {
+    let input = indoc! {r#"
+        [si]
+        precedence = "override"
+        [si.prefixes]
+        kilo = ["kilo"]
+        hecto = ["hecto"]
+        deca = ["deca"]
+        deci = ["deci"]
+        centi = ["centi"]
+        milli = ["milli"]
+    "#};
+    assert_eq!(
+        warnings(&bundled(), input),
+        [(
+            "SI prefixes from other files are replaced".to_string(),
+            "precedence".to_string()
+        )]
+    );
+}
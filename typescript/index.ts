@@ -21,6 +21,7 @@ export {
     quantity_display
 };
 export type {ScaledRecipeWithReport, Value, Quantity, Ingredient, Cookware, Timer, Section, Content, Step, Item} from "./pkg/cooklang_wasm.js";
+export type {CookSession, CookEvent, CookSessionUpdate, SessionTimer, TimerState, StepNode} from "./pkg/cooklang_wasm.js";
 
 // ============================================================================
 // Numeric Value Extraction Helpers
@@ -1,4 +1,5 @@
 use cooklang::ast::build_ast;
+use cooklang::cook_session::{CookEvent, CookSession};
 use cooklang::error::SourceReport;
 use cooklang::metadata::{CooklangValueExt, NameAndUrl, RecipeTime, Servings, StdKey};
 use cooklang::{parser::PullParser, quantity, Cookware, Extensions, GroupedQuantity, Ingredient};
@@ -22,6 +23,13 @@ pub struct GroupedIndexAndQuantity {
     quantity: GroupedQuantity,
 }
 
+#[derive(Tsify, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct CookSessionUpdate {
+    session: CookSession,
+    finished_timers: Vec<usize>,
+}
+
 #[wasm_bindgen]
 pub struct Parser {
     parser: CooklangParser,
@@ -97,7 +105,8 @@ impl Parser {
     }
     #[wasm_bindgen(setter)]
     pub fn set_extensions(&mut self, bits: u32) {
-        self.extensions = Extensions::from_bits_truncate(bits);
+        let known = Extensions::all() | Extensions::LOOSE_NUMBERS;
+        self.extensions = Extensions::from_bits_retain(bits & known.bits());
         self.update_parser();
     }
 
@@ -195,6 +204,26 @@ impl Parser {
             .collect()
     }
 
+    /// starts a cook session at the first step of r.recipe
+    pub fn start_cooking(&self, r: &ScaledRecipeWithReport) -> CookSession {
+        CookSession::new(&r.recipe)
+    }
+
+    /// applies an event to a cook session, returns the new session and the
+    /// indices in r.recipe.timers of the timers that finished
+    pub fn cook_session_apply(
+        &self,
+        r: &ScaledRecipeWithReport,
+        mut session: CookSession,
+        event: CookEvent,
+    ) -> Result<CookSessionUpdate, JsError> {
+        let finished_timers = session.apply(&r.recipe, self.parser.converter(), event)?;
+        Ok(CookSessionUpdate {
+            session,
+            finished_timers,
+        })
+    }
+
     pub fn parse_full(&self, input: &str, json: bool) -> FallibleResult {
         let (recipe, report) = self.parser.parse(input).into_tuple();
         let value = match recipe {